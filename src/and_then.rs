@@ -0,0 +1,182 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Two services combined where the response of the first is passed through an async closure
+/// before becoming the request of the second.
+///
+/// The future returned by the closure runs inside the response future, so no extra service is
+/// needed between the stages. If it fails the second service isn't called.
+///
+/// Created with [`Pipeline::and_then_async`].
+///
+/// ```
+/// use tower_pipeline::Pipeline;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: u64| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let combined = Pipeline::new(length_svc, double_svc)
+///     .and_then_async(|len: usize| async move {
+///         tokio::task::yield_now().await;
+///         Ok::<_, BoxError>(len as u64)
+///     });
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// # }
+/// ```
+///
+/// [`Pipeline::and_then_async`]: crate::Pipeline::and_then_async
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AndThen<A, F, B> {
+    first: A,
+    f: F,
+    second: B,
+}
+
+impl<A, F, B> AndThen<A, F, B> {
+    /// Create a new [`AndThen`] from two [`Service`]s and an async closure.
+    pub fn new(first: A, f: F, second: B) -> Self {
+        Self { first, f, second }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, A, F, Fut, T, E, B> Service<R> for AndThen<A, F, B>
+where
+    A: Service<R>,
+    F: FnOnce(A::Response) -> Fut + Clone,
+    Fut: Future<Output = Result<T, E>>,
+    B: Service<T> + Clone,
+    A::Error: Into<B::Error>,
+    E: Into<B::Error>,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = AndThenFuture<R, A, F, Fut, T, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        AndThenFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            f: Some(self.f.clone()),
+            second: Some(self.second.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`AndThen`].
+    pub struct AndThenFuture<R, A, F, Fut, T, B>
+    where
+        A: Service<R>,
+        B: Service<T>,
+    {
+        #[pin]
+        state: State<R, A, Fut, T, B>,
+        f: Option<F>,
+        second: Option<B>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, Fut, T, B>
+    where
+        A: Service<R>,
+        B: Service<T>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        ClosureFuturePending { #[pin] future: Fut },
+        CallingSecond { #[pin] future: Oneshot<B, T> },
+    }
+}
+
+impl<R, A, F, Fut, T, E, B> Future for AndThenFuture<R, A, F, Fut, T, B>
+where
+    A: Service<R>,
+    F: FnOnce(A::Response) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    B: Service<T>,
+    A::Error: Into<B::Error>,
+    E: Into<B::Error>,
+{
+    type Output = Result<B::Response, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    let f = this.f.take().unwrap();
+                    State::ClosureFuturePending {
+                        future: f(first_res),
+                    }
+                }
+
+                StateProj::ClosureFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    State::CallingSecond {
+                        future: Oneshot::new(this.second.take().unwrap(), first_res),
+                    }
+                }
+
+                StateProj::CallingSecond { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{
+    buffer::Closed,
+    queue_metrics::{QueueMetrics, Queued},
+    semaphore::{Permit, Semaphore},
+    BoxError, SharedError,
+};
+use alloc::{boxed::Box, vec::Vec};
+use std::future::Future;
+use std::{
+    error::Error,
+    fmt,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::Instant,
+};
+use tower_service::Service;
+
+/// A service that collects requests into batches and calls a batch-oriented service with them
+/// on a spawned task.
+///
+/// A batch is sent when it contains `max_size` requests or `max_delay` after its first request
+/// arrived, whichever comes first. The inner service receives the requests of a batch as a
+/// `Vec` and must respond with a `Vec` containing a response for each request, in the same
+/// order. The responses are then sent back to each caller.
+///
+/// If the inner service fails every caller in the batch receives a [`SharedError`], and if it
+/// responds with the wrong number of responses every caller receives a [`BatchSizeMismatch`].
+///
+/// `poll_ready` returns `Poll::Pending` while `max_size` calls are waiting for their responses.
+///
+/// Usually created with [`Pipeline::batch_second`].
+///
+/// ```
+/// use tower_pipeline::Pipeline;
+/// use tower::{service_fn, BoxError, Service, ServiceExt};
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let batch_double_svc = service_fn(|batch: Vec<usize>| async move {
+///     Ok::<_, BoxError>(batch.into_iter().map(|n| n * 2).collect::<Vec<_>>())
+/// });
+///
+/// let mut combined = Pipeline::new(length_svc, batch_double_svc)
+///     .batch_second(2, Duration::from_millis(10));
+///
+/// let a = combined.ready().await.unwrap().call("rust");
+/// let b = combined.ready().await.unwrap().call("tower");
+///
+/// let (a, b) = tokio::join!(a, b);
+///
+/// assert_eq!(a.unwrap(), 8);
+/// assert_eq!(b.unwrap(), 10);
+/// # }
+/// ```
+///
+/// [`Pipeline::batch_second`]: crate::Pipeline::batch_second
+pub struct Batch<T, U> {
+    tx: mpsc::UnboundedSender<Message<T, U>>,
+    semaphore: Arc<Semaphore>,
+    permit: Option<Permit>,
+    metrics: QueueMetrics,
+}
+
+struct Message<T, U> {
+    req: T,
+    tx: oneshot::Sender<Result<U, BoxError>>,
+    queued: Queued,
+    permit: Permit,
+}
+
+impl<T, U> Batch<T, U> {
+    /// Create a new [`Batch`] that sends batches of at most `max_size` requests to `service`,
+    /// waiting at most `max_delay` for a batch to fill up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_size` is zero or if called outside a tokio runtime since the worker is
+    /// spawned with [`tokio::spawn`].
+    pub fn new<S>(service: S, max_size: usize, max_delay: Duration) -> Self
+    where
+        S: Service<Vec<T>, Response = Vec<U>> + Send + 'static,
+        S::Error: Into<BoxError>,
+        S::Future: Send,
+        T: Send + 'static,
+        U: Send + 'static,
+    {
+        assert!(max_size > 0, "`max_size` must be greater than zero");
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(run(service, rx, max_size, max_delay));
+        Self {
+            tx,
+            semaphore: Semaphore::new(max_size),
+            permit: None,
+            metrics: QueueMetrics::default(),
+        }
+    }
+
+    /// Get the metrics of the calls waiting to be sent to the inner service.
+    pub fn metrics(&self) -> &QueueMetrics {
+        &self.metrics
+    }
+}
+
+async fn run<S, T, U>(
+    mut service: S,
+    mut rx: mpsc::UnboundedReceiver<Message<T, U>>,
+    max_size: usize,
+    max_delay: Duration,
+) where
+    S: Service<Vec<T>, Response = Vec<U>>,
+    S::Error: Into<BoxError>,
+{
+    while let Some(msg) = rx.recv().await {
+        let deadline = Instant::now() + max_delay;
+        let mut batch = Vec::with_capacity(max_size);
+        batch.push(msg);
+        while batch.len() < max_size {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some(msg)) => batch.push(msg),
+                Ok(None) | Err(_) => break,
+            }
+        }
+
+        let ready: Result<(), BoxError> = std::future::poll_fn(|cx| service.poll_ready(cx))
+            .await
+            .map_err(Into::into);
+
+        let (reqs, txs): (Vec<_>, Vec<_>) = batch
+            .into_iter()
+            .map(|msg| {
+                if ready.is_ok() {
+                    msg.queued.dequeue();
+                }
+                (msg.req, (msg.tx, msg.permit))
+            })
+            .unzip();
+
+        if let Err(err) = ready {
+            // the service is unusable after failing `poll_ready` so the worker stops and
+            // later calls fail with `Closed`
+            let err = SharedError::new(err);
+            for (tx, _permit) in txs {
+                let _ = tx.send(Err(Box::new(err.clone())));
+            }
+            return;
+        }
+
+        match service.call(reqs).await {
+            Ok(responses) if responses.len() == txs.len() => {
+                for ((tx, _permit), res) in txs.into_iter().zip(responses) {
+                    let _ = tx.send(Ok(res));
+                }
+            }
+            Ok(responses) => {
+                let err = BatchSizeMismatch {
+                    expected: txs.len(),
+                    actual: responses.len(),
+                };
+                for (tx, _permit) in txs {
+                    let _ = tx.send(Err(Box::new(err)));
+                }
+            }
+            Err(err) => {
+                let err = SharedError::new(err.into());
+                for (tx, _permit) in txs {
+                    let _ = tx.send(Err(Box::new(err.clone())));
+                }
+            }
+        }
+    }
+}
+
+impl<T, U> Clone for Batch<T, U> {
+    fn clone(&self) -> Self {
+        // permits aren't cloned since that would allow more calls than the capacity
+        Self {
+            tx: self.tx.clone(),
+            semaphore: Arc::clone(&self.semaphore),
+            permit: None,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+impl<T, U> fmt::Debug for Batch<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Batch")
+            .field("tx", &self.tx)
+            .field("semaphore", &self.semaphore)
+            .field("permit", &self.permit)
+            .field("metrics", &self.metrics)
+            .finish()
+    }
+}
+
+impl<T, U> Service<T> for Batch<T, U> {
+    type Response = U;
+    type Error = BoxError;
+    type Future = BatchFuture<U>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.tx.is_closed() {
+            return Poll::Ready(Err(Box::new(Closed(()))));
+        }
+        if self.permit.is_none() {
+            match Semaphore::poll_acquire(&self.semaphore, cx) {
+                Poll::Ready(permit) => self.permit = Some(permit),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        let permit = self
+            .permit
+            .take()
+            .expect("`poll_ready` must be called before `call`");
+        let (tx, rx) = oneshot::channel();
+        // if the worker is gone `rx` is dropped along with the message and the future fails
+        // with `Closed`
+        let _ = self.tx.send(Message {
+            req,
+            tx,
+            queued: self.metrics.enqueue(),
+            permit,
+        });
+        BatchFuture { rx }
+    }
+}
+
+/// Response future of [`Batch`].
+#[derive(Debug)]
+pub struct BatchFuture<U> {
+    rx: oneshot::Receiver<Result<U, BoxError>>,
+}
+
+impl<U> Future for BatchFuture<U> {
+    type Output = Result<U, BoxError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.rx).poll(cx) {
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(Box::new(Closed(())))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Error produced by [`Batch`] when the inner service responds with a different number of
+/// responses than it received requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchSizeMismatch {
+    expected: usize,
+    actual: usize,
+}
+
+impl BatchSizeMismatch {
+    /// The number of requests in the batch.
+    pub fn expected(&self) -> usize {
+        self.expected
+    }
+
+    /// The number of responses the inner service responded with.
+    pub fn actual(&self) -> usize {
+        self.actual
+    }
+}
+
+impl fmt::Display for BatchSizeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "batch service responded with {} responses to {} requests",
+            self.actual, self.expected
+        )
+    }
+}
+
+impl Error for BatchSizeMismatch {}
@@ -0,0 +1,18 @@
+use std::future::Future;
+use tokio::runtime::{Builder, Runtime};
+
+std::thread_local! {
+    // each thread that calls a pipeline from synchronous code reuses the same runtime
+    static RUNTIME: Runtime = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build tokio runtime for blocking calls");
+}
+
+/// Drive `future` to completion on the current thread's blocking runtime.
+pub(crate) fn block_on<F>(future: F) -> F::Output
+where
+    F: Future,
+{
+    RUNTIME.with(|runtime| runtime.block_on(future))
+}
@@ -0,0 +1,167 @@
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Two services combined where the second service borrows the response of the first, rather
+/// than taking ownership of it.
+///
+/// Useful when the response of the first service is large, such as a buffer or a parsed
+/// document, and the second service only needs to read it. The second service must implement
+/// `for<'a> Service<&'a T>`, with a response future that doesn't borrow the request. The
+/// response of the first service is dropped once the second service has been called.
+///
+/// Created with [`Pipeline::borrowing`].
+///
+/// ```
+/// use futures_util::future::ready;
+/// use tower_pipeline::Pipeline;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let repeat_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.repeat(1000))
+/// });
+///
+/// let length_svc = service_fn(|document: &String| ready(Ok::<_, BoxError>(document.len())));
+///
+/// let combined = Pipeline::new(repeat_svc, length_svc).borrowing();
+///
+/// assert_eq!(combined.oneshot("rust").await.unwrap(), 4000);
+/// # }
+/// ```
+///
+/// [`Pipeline::borrowing`]: crate::Pipeline::borrowing
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Borrowing<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> Borrowing<A, B> {
+    /// Create a new [`Borrowing`] from two [`Service`]s.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, A, B, F, T, E> Service<R> for Borrowing<A, B>
+where
+    A: Service<R>,
+    B: for<'a> Service<&'a A::Response, Response = T, Error = E, Future = F> + Clone,
+    F: Future<Output = Result<T, E>>,
+    A::Error: Into<E>,
+{
+    type Response = T;
+    type Error = E;
+    type Future = BorrowingFuture<R, A, B, F>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        BorrowingFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            second: Some(self.second.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Borrowing`].
+    pub struct BorrowingFuture<R, A, B, F>
+    where
+        A: Service<R>,
+    {
+        #[pin]
+        state: State<R, A, F>,
+        second: Option<B>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, F>
+    where
+        A: Service<R>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        PollReadySecond { first_res: A::Response },
+        SecondFuturePending { #[pin] future: F },
+    }
+}
+
+impl<R, A, B, F, T, E> Future for BorrowingFuture<R, A, B, F>
+where
+    A: Service<R>,
+    B: for<'a> Service<&'a A::Response, Response = T, Error = E, Future = F>,
+    F: Future<Output = Result<T, E>>,
+    A::Error: Into<E>,
+{
+    type Output = Result<T, E>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx)).map_err(Into::into)?;
+                    State::PollReadySecond { first_res }
+                }
+
+                StateProj::PollReadySecond { first_res } => {
+                    let second = this.second.as_mut().unwrap();
+                    let _ready: () = ready!(Service::<&A::Response>::poll_ready(second, cx))?;
+                    // the future returned by the second service doesn't borrow the response,
+                    // so the response can be dropped along with this state
+                    State::SecondFuturePending {
+                        future: second.call(&*first_res),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
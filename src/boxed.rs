@@ -0,0 +1,275 @@
+use alloc::boxed::Box;
+use core::{
+    fmt,
+    task::{Context, Poll},
+};
+use futures_util::future::{BoxFuture, LocalBoxFuture};
+use tower_service::Service;
+
+/// A boxed, type-erased pipeline.
+///
+/// Nested pipelines have long and complicated types. [`BoxPipeline`] erases that type so the
+/// pipeline can be stored in structs and returned from functions. It is equivalent to
+/// `tower::util::BoxService` but doesn't require depending on `tower`.
+///
+/// Created with [`Pipeline::boxed`] or [`BoxPipeline::new`].
+///
+/// ```
+/// use tower_pipeline::{BoxPipeline, PipelineExt};
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// fn make_pipeline() -> BoxPipeline<&'static str, usize, BoxError> {
+///     let length_svc = service_fn(|input: &'static str| async move {
+///         Ok::<_, BoxError>(input.len())
+///     });
+///
+///     let double_svc = service_fn(|input: usize| async move {
+///         Ok::<_, BoxError>(input * 2)
+///     });
+///
+///     length_svc.pipeline(double_svc).boxed()
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let result = make_pipeline().oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// # }
+/// ```
+///
+/// [`Pipeline::boxed`]: crate::Pipeline::boxed
+pub struct BoxPipeline<T, U, E> {
+    inner: Box<
+        dyn Service<T, Response = U, Error = E, Future = BoxFuture<'static, Result<U, E>>> + Send,
+    >,
+}
+
+impl<T, U, E> BoxPipeline<T, U, E> {
+    /// Create a new [`BoxPipeline`] from any [`Service`].
+    pub fn new<S>(service: S) -> Self
+    where
+        S: Service<T, Response = U, Error = E> + Send + 'static,
+        S::Future: Send + 'static,
+    {
+        Self {
+            inner: Box::new(BoxFutures(service)),
+        }
+    }
+}
+
+impl<T, U, E> Service<T> for BoxPipeline<T, U, E> {
+    type Response = U;
+    type Error = E;
+    type Future = BoxFuture<'static, Result<U, E>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        self.inner.call(req)
+    }
+}
+
+impl<T, U, E> fmt::Debug for BoxPipeline<T, U, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxPipeline").finish()
+    }
+}
+
+/// A boxed, type-erased pipeline that doesn't require [`Send`].
+///
+/// Like [`BoxPipeline`] but neither the erased service nor its response futures have to
+/// implement [`Send`], so it works with `Rc` state, inside a `LocalSet`, or on targets such as
+/// `wasm32-unknown-unknown`. It is equivalent to `tower::util::UnsyncBoxService`.
+///
+/// Created with [`Pipeline::boxed_local`] or [`LocalBoxPipeline::new`].
+///
+/// ```
+/// use std::rc::Rc;
+/// use tower_pipeline::{LocalBoxPipeline, PipelineExt};
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// fn make_pipeline() -> LocalBoxPipeline<&'static str, usize, BoxError> {
+///     let length_svc = service_fn(|input: &'static str| async move {
+///         Ok::<_, BoxError>(input.len())
+///     });
+///
+///     // `Rc` isn't `Send`
+///     let factor = Rc::new(2);
+///     let multiply_svc = service_fn(move |input: usize| {
+///         let factor = Rc::clone(&factor);
+///         async move { Ok::<_, BoxError>(input * *factor) }
+///     });
+///
+///     length_svc.pipeline(multiply_svc).boxed_local()
+/// }
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let result = make_pipeline().oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// # }
+/// ```
+///
+/// [`Pipeline::boxed_local`]: crate::Pipeline::boxed_local
+pub struct LocalBoxPipeline<T, U, E> {
+    inner: Box<
+        dyn Service<T, Response = U, Error = E, Future = LocalBoxFuture<'static, Result<U, E>>>,
+    >,
+}
+
+impl<T, U, E> LocalBoxPipeline<T, U, E> {
+    /// Create a new [`LocalBoxPipeline`] from any [`Service`].
+    pub fn new<S>(service: S) -> Self
+    where
+        S: Service<T, Response = U, Error = E> + 'static,
+        S::Future: 'static,
+    {
+        Self {
+            inner: Box::new(LocalBoxFutures(service)),
+        }
+    }
+}
+
+impl<T, U, E> Service<T> for LocalBoxPipeline<T, U, E> {
+    type Response = U;
+    type Error = E;
+    type Future = LocalBoxFuture<'static, Result<U, E>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        self.inner.call(req)
+    }
+}
+
+impl<T, U, E> fmt::Debug for LocalBoxPipeline<T, U, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalBoxPipeline").finish()
+    }
+}
+
+/// A boxed, type-erased pipeline that implements [`Clone`].
+///
+/// Like [`BoxPipeline`] but requires the erased service to implement [`Clone`], which makes it
+/// usable as a stage of another pipeline. It is equivalent to `tower::util::BoxCloneService`.
+///
+/// Created with [`Pipeline::boxed_clone`] or [`BoxClonePipeline::new`].
+///
+/// [`Pipeline::boxed_clone`]: crate::Pipeline::boxed_clone
+pub struct BoxClonePipeline<T, U, E> {
+    inner: Box<
+        dyn CloneService<T, Response = U, Error = E, Future = BoxFuture<'static, Result<U, E>>>
+            + Send,
+    >,
+}
+
+impl<T, U, E> BoxClonePipeline<T, U, E> {
+    /// Create a new [`BoxClonePipeline`] from any [`Service`] that implements [`Clone`].
+    pub fn new<S>(service: S) -> Self
+    where
+        S: Service<T, Response = U, Error = E> + Clone + Send + 'static,
+        S::Future: Send + 'static,
+    {
+        Self {
+            inner: Box::new(BoxFutures(service)),
+        }
+    }
+}
+
+impl<T, U, E> Service<T> for BoxClonePipeline<T, U, E> {
+    type Response = U;
+    type Error = E;
+    type Future = BoxFuture<'static, Result<U, E>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        self.inner.call(req)
+    }
+}
+
+impl<T, U, E> Clone for BoxClonePipeline<T, U, E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone_box(),
+        }
+    }
+}
+
+impl<T, U, E> fmt::Debug for BoxClonePipeline<T, U, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxClonePipeline").finish()
+    }
+}
+
+trait CloneService<T>: Service<T> {
+    fn clone_box(
+        &self,
+    ) -> Box<
+        dyn CloneService<T, Response = Self::Response, Error = Self::Error, Future = Self::Future>
+            + Send,
+    >;
+}
+
+impl<T, S> CloneService<T> for S
+where
+    S: Service<T> + Clone + Send + 'static,
+{
+    fn clone_box(
+        &self,
+    ) -> Box<dyn CloneService<T, Response = S::Response, Error = S::Error, Future = S::Future> + Send>
+    {
+        Box::new(self.clone())
+    }
+}
+
+/// Adapter that boxes the response futures of a service.
+#[derive(Clone)]
+struct BoxFutures<S>(S);
+
+impl<S, T> Service<T> for BoxFutures<S>
+where
+    S: Service<T>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        Box::pin(self.0.call(req))
+    }
+}
+
+/// Adapter that boxes the response futures of a service without requiring them to be [`Send`].
+struct LocalBoxFutures<S>(S);
+
+impl<S, T> Service<T> for LocalBoxFutures<S>
+where
+    S: Service<T>,
+    S::Future: 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = LocalBoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        Box::pin(self.0.call(req))
+    }
+}
@@ -0,0 +1,194 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::{future::Either, ready};
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// A service whose response is routed to one of two second services based on a predicate.
+///
+/// Only the service that is picked is driven to readiness and called.
+///
+/// Created with [`Pipeline::branch`] or [`PipelineExt::pipeline_branch`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let even_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(format!("{} is even", input))
+/// });
+///
+/// let odd_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(format!("{} is odd", input))
+/// });
+///
+/// let combined = length_svc.pipeline_branch(|len: &usize| len % 2 == 0, even_svc, odd_svc);
+///
+/// assert_eq!(combined.clone().oneshot("rust").await.unwrap(), "4 is even");
+/// assert_eq!(combined.oneshot("tower").await.unwrap(), "5 is odd");
+/// # }
+/// ```
+///
+/// [`Pipeline::branch`]: crate::Pipeline::branch
+/// [`PipelineExt::pipeline_branch`]: crate::PipelineExt::pipeline_branch
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Branch<A, P, T, F> {
+    first: A,
+    predicate: P,
+    if_true: T,
+    if_false: F,
+}
+
+impl<A, P, T, F> Branch<A, P, T, F> {
+    /// Create a new [`Branch`].
+    ///
+    /// The response of `first` is sent to `if_true` if `predicate` returns `true`, and to
+    /// `if_false` otherwise.
+    pub fn new(first: A, predicate: P, if_true: T, if_false: F) -> Self {
+        Self {
+            first,
+            predicate,
+            if_true,
+            if_false,
+        }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the service used when the predicate returns `true`.
+    pub fn if_true_as_ref(&self) -> &T {
+        &self.if_true
+    }
+
+    /// Get a mutable reference to the service used when the predicate returns `true`.
+    pub fn if_true_as_mut(&mut self) -> &mut T {
+        &mut self.if_true
+    }
+
+    /// Get a reference to the service used when the predicate returns `false`.
+    pub fn if_false_as_ref(&self) -> &F {
+        &self.if_false
+    }
+
+    /// Get a mutable reference to the service used when the predicate returns `false`.
+    pub fn if_false_as_mut(&mut self) -> &mut F {
+        &mut self.if_false
+    }
+}
+
+impl<R, A, P, T, F> Service<R> for Branch<A, P, T, F>
+where
+    A: Service<R>,
+    P: FnOnce(&A::Response) -> bool + Clone,
+    T: Service<A::Response> + Clone,
+    F: Service<A::Response, Response = T::Response, Error = T::Error> + Clone,
+    A::Error: Into<T::Error>,
+{
+    type Response = T::Response;
+    type Error = T::Error;
+    type Future = BranchFuture<R, A, P, T, F>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        BranchFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            predicate: Some(self.predicate.clone()),
+            if_true: Some(self.if_true.clone()),
+            if_false: Some(self.if_false.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Branch`].
+    pub struct BranchFuture<R, A, P, T, F>
+    where
+        A: Service<R>,
+        T: Service<A::Response>,
+        F: Service<A::Response>,
+    {
+        #[pin]
+        state: State<R, A, T, F>,
+        predicate: Option<P>,
+        if_true: Option<T>,
+        if_false: Option<F>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, T, F>
+    where
+        A: Service<R>,
+        T: Service<A::Response>,
+        F: Service<A::Response>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturePending {
+            #[pin]
+            future: Either<Oneshot<T, A::Response>, Oneshot<F, A::Response>>,
+        },
+    }
+}
+
+impl<R, A, P, T, F> Future for BranchFuture<R, A, P, T, F>
+where
+    A: Service<R>,
+    P: FnOnce(&A::Response) -> bool,
+    T: Service<A::Response>,
+    F: Service<A::Response, Response = T::Response, Error = T::Error>,
+    A::Error: Into<T::Error>,
+{
+    type Output = Result<T::Response, T::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    let predicate = this.predicate.take().unwrap();
+                    let future = if predicate(&first_res) {
+                        Either::Left(Oneshot::new(this.if_true.take().unwrap(), first_res))
+                    } else {
+                        Either::Right(Oneshot::new(this.if_false.take().unwrap(), first_res))
+                    };
+                    State::SecondFuturePending { future }
+                }
+
+                StateProj::SecondFuturePending { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
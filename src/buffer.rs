@@ -0,0 +1,394 @@
+use crate::{
+    priority::PriorityQueue,
+    queue_metrics::{QueueMetrics, Queued},
+    semaphore::{Permit, Semaphore},
+    BoxError,
+};
+use alloc::boxed::Box;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    error::Error,
+    fmt,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::sync::{mpsc, oneshot};
+use tower_service::Service;
+
+/// A service that runs the inner service on a spawned task and sends calls to it over a
+/// channel.
+///
+/// This means the inner service doesn't have to implement [`Clone`] and that readiness becomes
+/// backpressure on the channel: `poll_ready` returns `Poll::Pending` while `capacity` calls are
+/// waiting for the inner service to become ready.
+///
+/// The response future of the inner service is sent back to the caller and driven there.
+///
+/// Usually created with [`Pipeline::buffered`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// // a service that doesn't implement `Clone`
+/// let not_clone = String::from("not clone");
+/// let double_svc = service_fn(move |input: usize| {
+///     let _ = &not_clone;
+///     async move { Ok::<_, BoxError>(input * 2) }
+/// });
+///
+/// let combined = length_svc.pipeline(double_svc).buffered(16);
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// # }
+/// ```
+///
+/// [`Pipeline::buffered`]: crate::Pipeline::buffered
+pub struct Buffer<T, F> {
+    tx: mpsc::UnboundedSender<Message<T, F>>,
+    semaphore: Arc<Semaphore>,
+    permit: Option<Permit>,
+    metrics: QueueMetrics,
+}
+
+struct Message<T, F> {
+    req: T,
+    tx: oneshot::Sender<Result<F, BoxError>>,
+    queued: Queued,
+    _permit: Permit,
+}
+
+impl<T, F> Buffer<T, F> {
+    /// Create a new [`Buffer`] that allows at most `capacity` calls waiting for `service`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or if called outside a tokio runtime since the worker is
+    /// spawned with [`tokio::spawn`].
+    pub fn new<S>(service: S, capacity: usize) -> Self
+    where
+        S: Service<T, Future = F> + Send + 'static,
+        S::Error: Into<BoxError>,
+        T: Send + 'static,
+        F: Send + 'static,
+    {
+        assert!(capacity > 0, "`capacity` must be greater than zero");
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(run(service, rx));
+        Self {
+            tx,
+            semaphore: Semaphore::new(capacity),
+            permit: None,
+            metrics: QueueMetrics::default(),
+        }
+    }
+
+    /// Create a new [`Buffer`] whose worker is spawned on the current [`LocalSet`].
+    ///
+    /// Unlike [`Buffer::new`] neither `service` nor its requests and response futures have to
+    /// implement [`Send`].
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use tower_pipeline::Buffer;
+    /// use tower::{service_fn, BoxError, ServiceExt};
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let local = tokio::task::LocalSet::new();
+    ///
+    /// local.run_until(async {
+    ///     // `Rc` isn't `Send`
+    ///     let factor = Rc::new(2);
+    ///     let double_svc = service_fn(move |input: usize| {
+    ///         let factor = Rc::clone(&factor);
+    ///         async move { Ok::<_, BoxError>(input * *factor) }
+    ///     });
+    ///
+    ///     let buffer = Buffer::new_local(double_svc, 16);
+    ///
+    ///     assert_eq!(buffer.oneshot(4).await.unwrap(), 8);
+    /// }).await;
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or if called outside a [`LocalSet`] since the worker is
+    /// spawned with [`tokio::task::spawn_local`].
+    ///
+    /// [`LocalSet`]: tokio::task::LocalSet
+    pub fn new_local<S>(service: S, capacity: usize) -> Self
+    where
+        S: Service<T, Future = F> + 'static,
+        S::Error: Into<BoxError>,
+        T: 'static,
+        F: 'static,
+    {
+        assert!(capacity > 0, "`capacity` must be greater than zero");
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::task::spawn_local(run(service, rx));
+        Self {
+            tx,
+            semaphore: Semaphore::new(capacity),
+            permit: None,
+            metrics: QueueMetrics::default(),
+        }
+    }
+
+    /// Create a new [`Buffer`] where waiting calls are sent to `service` in order of priority,
+    /// as extracted from each request by `priority`.
+    ///
+    /// Calls with the highest priority are sent first. Calls with the same priority are sent in
+    /// the order they were made. Calls that have already been sent aren't affected.
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let parse_svc = service_fn(|input: &'static str| async move {
+    ///     // `!` marks interactive traffic
+    ///     Ok::<_, BoxError>((input.starts_with('!'), input.len()))
+    /// });
+    ///
+    /// let length_svc = service_fn(|(_, len): (bool, usize)| async move {
+    ///     Ok::<_, BoxError>(len)
+    /// });
+    ///
+    /// let combined = parse_svc
+    ///     .pipeline(length_svc)
+    ///     .buffered_with_priority(16, |(interactive, _): &(bool, usize)| *interactive);
+    ///
+    /// let result = combined.oneshot("!rust").await.unwrap();
+    ///
+    /// assert_eq!(result, 5);
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or if called outside a tokio runtime since the worker is
+    /// spawned with [`tokio::spawn`].
+    pub fn new_with_priority<S, P, K>(service: S, capacity: usize, priority: P) -> Self
+    where
+        S: Service<T, Future = F> + Send + 'static,
+        S::Error: Into<BoxError>,
+        P: Fn(&T) -> K + Send + 'static,
+        K: Ord + Send + 'static,
+        T: Send + 'static,
+        F: Send + 'static,
+    {
+        assert!(capacity > 0, "`capacity` must be greater than zero");
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(run_with_priority(service, rx, priority));
+        Self {
+            tx,
+            semaphore: Semaphore::new(capacity),
+            permit: None,
+            metrics: QueueMetrics::default(),
+        }
+    }
+
+    /// Get the metrics of the calls waiting for the inner service.
+    pub fn metrics(&self) -> &QueueMetrics {
+        &self.metrics
+    }
+}
+
+async fn run<S, T>(mut service: S, mut rx: mpsc::UnboundedReceiver<Message<T, S::Future>>)
+where
+    S: Service<T>,
+    S::Error: Into<BoxError>,
+{
+    while let Some(msg) = rx.recv().await {
+        match std::future::poll_fn(|cx| service.poll_ready(cx)).await {
+            Ok(()) => {
+                msg.queued.dequeue();
+                let _ = msg.tx.send(Ok(service.call(msg.req)));
+            }
+            Err(err) => {
+                // the service is unusable after failing `poll_ready` so the worker stops and
+                // later calls fail with `Closed`
+                let _ = msg.tx.send(Err(err.into()));
+                return;
+            }
+        }
+    }
+}
+
+async fn run_with_priority<S, T, P, K>(
+    mut service: S,
+    mut rx: mpsc::UnboundedReceiver<Message<T, S::Future>>,
+    priority: P,
+) where
+    S: Service<T>,
+    S::Error: Into<BoxError>,
+    P: Fn(&T) -> K,
+    K: Ord,
+{
+    let mut queue = PriorityQueue::new();
+    loop {
+        if queue.is_empty() {
+            match rx.recv().await {
+                Some(msg) => queue.push(priority(&msg.req), msg),
+                None => return,
+            }
+        }
+
+        let ready = std::future::poll_fn(|cx| service.poll_ready(cx)).await;
+
+        // calls made while waiting for the service compete with the ones already queued
+        while let Ok(msg) = rx.try_recv() {
+            queue.push(priority(&msg.req), msg);
+        }
+        let msg = queue.pop().unwrap();
+
+        match ready {
+            Ok(()) => {
+                msg.queued.dequeue();
+                let _ = msg.tx.send(Ok(service.call(msg.req)));
+            }
+            Err(err) => {
+                let _ = msg.tx.send(Err(err.into()));
+                return;
+            }
+        }
+    }
+}
+
+impl<T, F> Clone for Buffer<T, F> {
+    fn clone(&self) -> Self {
+        // permits aren't cloned since that would allow more calls than the capacity
+        Self {
+            tx: self.tx.clone(),
+            semaphore: Arc::clone(&self.semaphore),
+            permit: None,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+impl<T, F> fmt::Debug for Buffer<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Buffer")
+            .field("tx", &self.tx)
+            .field("semaphore", &self.semaphore)
+            .field("permit", &self.permit)
+            .field("metrics", &self.metrics)
+            .finish()
+    }
+}
+
+impl<T, F, U, E> Service<T> for Buffer<T, F>
+where
+    F: Future<Output = Result<U, E>>,
+    E: Into<BoxError>,
+{
+    type Response = U;
+    type Error = BoxError;
+    type Future = BufferFuture<F>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.tx.is_closed() {
+            return Poll::Ready(Err(Box::new(Closed(()))));
+        }
+        if self.permit.is_none() {
+            match Semaphore::poll_acquire(&self.semaphore, cx) {
+                Poll::Ready(permit) => self.permit = Some(permit),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        let permit = self
+            .permit
+            .take()
+            .expect("`poll_ready` must be called before `call`");
+        let (tx, rx) = oneshot::channel();
+        // if the worker is gone `rx` is dropped along with the message and the future fails
+        // with `Closed`
+        let _ = self.tx.send(Message {
+            req,
+            tx,
+            queued: self.metrics.enqueue(),
+            _permit: permit,
+        });
+        BufferFuture {
+            state: State::Waiting { rx },
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Buffer`].
+    pub struct BufferFuture<F> {
+        #[pin]
+        state: State<F>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<F> {
+        Waiting { rx: oneshot::Receiver<Result<F, BoxError>> },
+        Called { #[pin] future: F },
+    }
+}
+
+impl<F, U, E> Future for BufferFuture<F>
+where
+    F: Future<Output = Result<U, E>>,
+    E: Into<BoxError>,
+{
+    type Output = Result<U, BoxError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::Waiting { rx } => match Pin::new(rx).poll(cx) {
+                    Poll::Ready(Ok(Ok(future))) => State::Called { future },
+                    Poll::Ready(Ok(Err(err))) => return Poll::Ready(Err(err)),
+                    Poll::Ready(Err(_)) => return Poll::Ready(Err(Box::new(Closed(())))),
+                    Poll::Pending => return Poll::Pending,
+                },
+
+                StateProj::Called { future } => return future.poll(cx).map_err(Into::into),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
+
+/// Error produced when the worker task of a [`Buffer`] or [`Parallel`] has stopped.
+///
+/// This happens if a service fails `poll_ready` or if the runtime the worker was spawned on
+/// shuts down.
+///
+/// [`Parallel`]: crate::Parallel
+#[derive(Debug)]
+pub struct Closed(pub(crate) ());
+
+impl fmt::Display for Closed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("worker task closed unexpectedly")
+    }
+}
+
+impl Error for Closed {}
@@ -0,0 +1,78 @@
+use crate::{BoxPipeline, Pipeline, PipelineMap};
+use tower_service::Service;
+
+/// Builder for pipelines of any number of stages.
+///
+/// Each call to [`then`](PipelineBuilder::then) adds a stage whose request is the response of
+/// the stages so far, and [`then_map`](PipelineBuilder::then_map) does the same with an adapter
+/// closure in between, for stages whose types don't line up.
+///
+/// ```
+/// use tower_pipeline::PipelineBuilder;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// // service that expects a `u64` rather than a `usize`
+/// let to_string_svc = service_fn(|input: u64| async move {
+///     Ok::<_, BoxError>(input.to_string())
+/// });
+///
+/// let combined = PipelineBuilder::new(length_svc)
+///     .then(double_svc)
+///     .then_map(|len: usize| len as u64, to_string_svc)
+///     .build();
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, "8");
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PipelineBuilder<S> {
+    service: S,
+}
+
+impl<S> PipelineBuilder<S> {
+    /// Create a new [`PipelineBuilder`] with `first` as the first stage.
+    pub fn new(first: S) -> Self {
+        Self { service: first }
+    }
+
+    /// Add a stage whose request is the response of the stages so far.
+    pub fn then<B>(self, next: B) -> PipelineBuilder<Pipeline<S, B>> {
+        PipelineBuilder {
+            service: Pipeline::new(self.service, next),
+        }
+    }
+
+    /// Add a stage whose request is the response of the stages so far, passed through
+    /// `adapter`.
+    pub fn then_map<F, B>(self, adapter: F, next: B) -> PipelineBuilder<PipelineMap<S, F, B>> {
+        PipelineBuilder {
+            service: PipelineMap::new(self.service, adapter, next),
+        }
+    }
+
+    /// Build the pipeline.
+    pub fn build(self) -> S {
+        self.service
+    }
+
+    /// Build the pipeline and erase its type, producing a [`BoxPipeline`].
+    pub fn build_boxed<R>(self) -> BoxPipeline<R, S::Response, S::Error>
+    where
+        S: Service<R> + Send + 'static,
+        S::Future: Send + 'static,
+    {
+        BoxPipeline::new(self.service)
+    }
+}
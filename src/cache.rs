@@ -0,0 +1,234 @@
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// An asynchronous key-value store used by [`Cached`], such as Redis or [moka].
+///
+/// [moka]: https://crates.io/crates/moka
+pub trait AsyncCache<K, V> {
+    /// The error produced by the store.
+    type Error;
+
+    /// The future returned by [`get`](AsyncCache::get).
+    type GetFuture: Future<Output = Result<Option<V>, Self::Error>>;
+
+    /// The future returned by [`put`](AsyncCache::put).
+    type PutFuture: Future<Output = Result<(), Self::Error>>;
+
+    /// Look up the value stored for `key`.
+    fn get(&self, key: &K) -> Self::GetFuture;
+
+    /// Store `value` for `key`, expiring after `ttl` if it is `Some`.
+    fn put(&self, key: K, value: V, ttl: Option<Duration>) -> Self::PutFuture;
+}
+
+/// A service that consults an [`AsyncCache`] before calling the inner service, and stores the
+/// response in the cache afterwards.
+///
+/// Errors from the cache are treated as misses, so an unavailable cache doesn't fail calls.
+/// Errors from the inner service aren't cached. The response is stored before the response
+/// future completes.
+///
+/// Usually created with [`Pipeline::cache_first`].
+///
+/// ```
+/// use tower_pipeline::{AsyncCache, PipelineExt};
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use futures_util::future::{ready, Ready};
+/// use std::{
+///     collections::HashMap,
+///     convert::Infallible,
+///     sync::{Arc, Mutex},
+///     time::Duration,
+/// };
+///
+/// #[derive(Clone, Default)]
+/// struct MemoryCache {
+///     values: Arc<Mutex<HashMap<&'static str, usize>>>,
+/// }
+///
+/// impl AsyncCache<&'static str, usize> for MemoryCache {
+///     type Error = Infallible;
+///     type GetFuture = Ready<Result<Option<usize>, Infallible>>;
+///     type PutFuture = Ready<Result<(), Infallible>>;
+///
+///     fn get(&self, key: &&'static str) -> Self::GetFuture {
+///         ready(Ok(self.values.lock().unwrap().get(key).copied()))
+///     }
+///
+///     fn put(&self, key: &'static str, value: usize, _ttl: Option<Duration>) -> Self::PutFuture {
+///         self.values.lock().unwrap().insert(key, value);
+///         ready(Ok(()))
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let cache = MemoryCache::default();
+///
+/// let combined = length_svc
+///     .pipeline(double_svc)
+///     .cache_first(cache.clone(), Some(Duration::from_secs(60)));
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// assert_eq!(cache.values.lock().unwrap()["rust"], 4);
+/// # }
+/// ```
+///
+/// [`Pipeline::cache_first`]: crate::Pipeline::cache_first
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Cached<S, C> {
+    inner: S,
+    cache: C,
+    ttl: Option<Duration>,
+}
+
+impl<S, C> Cached<S, C> {
+    /// Create a new [`Cached`] whose responses expire after `ttl`, if it is `Some`.
+    pub fn new(inner: S, cache: C, ttl: Option<Duration>) -> Self {
+        Self { inner, cache, ttl }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Get a reference to the cache.
+    pub fn cache(&self) -> &C {
+        &self.cache
+    }
+}
+
+impl<K, S, C> Service<K> for Cached<S, C>
+where
+    K: Clone,
+    S: Service<K> + Clone,
+    S::Response: Clone,
+    C: AsyncCache<K, S::Response> + Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = CachedFuture<K, S, C>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: K) -> Self::Future {
+        // the inner service is called once the cache has been consulted, so take the service
+        // that was driven to readiness and leave a clone in its place
+        let clone = self.inner.clone();
+        let inner = core::mem::replace(&mut self.inner, clone);
+
+        CachedFuture {
+            state: State::Getting {
+                future: self.cache.get(&req),
+            },
+            inner: Some(inner),
+            cache: self.cache.clone(),
+            ttl: self.ttl,
+            key: Some(req),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Cached`].
+    pub struct CachedFuture<K, S, C>
+    where
+        S: Service<K>,
+        C: AsyncCache<K, S::Response>,
+    {
+        #[pin]
+        state: State<K, S, C>,
+        inner: Option<S>,
+        cache: C,
+        ttl: Option<Duration>,
+        key: Option<K>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<K, S, C>
+    where
+        S: Service<K>,
+        C: AsyncCache<K, S::Response>,
+    {
+        Getting { #[pin] future: C::GetFuture },
+        Calling { #[pin] future: S::Future },
+        Putting { #[pin] future: C::PutFuture, res: Option<S::Response> },
+    }
+}
+
+impl<K, S, C> Future for CachedFuture<K, S, C>
+where
+    K: Clone,
+    S: Service<K>,
+    S::Response: Clone,
+    C: AsyncCache<K, S::Response>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::Getting { future } => {
+                    if let Ok(Some(res)) = ready!(future.poll(cx)) {
+                        return Poll::Ready(Ok(res));
+                    }
+                    let req = this.key.clone().unwrap();
+                    State::Calling {
+                        future: this.inner.take().unwrap().call(req),
+                    }
+                }
+
+                StateProj::Calling { future } => {
+                    let res = ready!(future.poll(cx))?;
+                    let key = this.key.take().unwrap();
+                    State::Putting {
+                        future: this.cache.put(key, res.clone(), *this.ttl),
+                        res: Some(res),
+                    }
+                }
+
+                StateProj::Putting { future, res } => {
+                    // failing to populate the cache doesn't fail the call
+                    let _ = ready!(future.poll(cx));
+                    return Poll::Ready(Ok(res.take().unwrap()));
+                }
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
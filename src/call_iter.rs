@@ -0,0 +1,138 @@
+use crate::ordering::{InFlight, Ordering};
+use alloc::vec::Vec;
+use core::future::Future;
+use core::{
+    fmt, mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+/// Future that calls a service with every item of an iterator and collects the results.
+///
+/// The service is driven to readiness before each call, so calls are only made as fast as the
+/// service can accept them, while the calls already made run concurrently. By default the
+/// results are in the same order as the items. With [`Pipeline::call_iter_unordered`] they
+/// are in the order the calls complete.
+///
+/// A failed call doesn't stop the other calls. If the service fails to become ready the error
+/// is the last result and the remaining items aren't used.
+///
+/// Created with [`Pipeline::call_iter`] or [`Pipeline::call_iter_unordered`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let parse_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.parse::<usize>()?)
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let results = parse_svc
+///     .pipeline(double_svc)
+///     .call_iter(vec!["1", "two", "3"])
+///     .await;
+///
+/// assert_eq!(results[0].as_ref().unwrap(), &2);
+/// assert!(results[1].is_err());
+/// assert_eq!(results[2].as_ref().unwrap(), &6);
+/// # }
+/// ```
+///
+/// [`Pipeline::call_iter`]: crate::Pipeline::call_iter
+/// [`Pipeline::call_iter_unordered`]: crate::Pipeline::call_iter_unordered
+pub struct CallIter<I, S>
+where
+    I: Iterator,
+    S: Service<I::Item>,
+{
+    items: Option<I>,
+    service: S,
+    in_flight: InFlight<S::Future>,
+    results: Vec<Result<S::Response, S::Error>>,
+    ready_error: Option<S::Error>,
+}
+
+impl<I, S> CallIter<I, S>
+where
+    I: Iterator,
+    S: Service<I::Item>,
+{
+    pub(crate) fn new(service: S, items: I, ordering: Ordering) -> Self {
+        Self {
+            results: Vec::with_capacity(items.size_hint().0),
+            items: Some(items),
+            service,
+            in_flight: InFlight::new(ordering),
+            ready_error: None,
+        }
+    }
+}
+
+// nothing is pinned structurally, the futures in flight are kept on the heap by
+// `FuturesOrdered` and `FuturesUnordered`
+impl<I, S> Unpin for CallIter<I, S>
+where
+    I: Iterator,
+    S: Service<I::Item>,
+{
+}
+
+impl<I, S> Future for CallIter<I, S>
+where
+    I: Iterator,
+    S: Service<I::Item>,
+{
+    type Output = Vec<Result<S::Response, S::Error>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+
+        while let Some(items) = &mut this.items {
+            match this.service.poll_ready(cx) {
+                Poll::Ready(Ok(())) => match items.next() {
+                    Some(item) => this.in_flight.push(this.service.call(item)),
+                    None => this.items = None,
+                },
+                Poll::Ready(Err(err)) => {
+                    this.items = None;
+                    this.ready_error = Some(err);
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        loop {
+            match this.in_flight.poll_next(cx) {
+                Poll::Ready(Some(result)) => this.results.push(result),
+                Poll::Ready(None) if this.items.is_none() => {
+                    if let Some(err) = this.ready_error.take() {
+                        this.results.push(Err(err));
+                    }
+                    return Poll::Ready(mem::take(&mut this.results));
+                }
+                Poll::Ready(None) | Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<I, S> fmt::Debug for CallIter<I, S>
+where
+    I: Iterator + fmt::Debug,
+    S: Service<I::Item> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallIter")
+            .field("items", &self.items)
+            .field("service", &self.service)
+            .field("completed", &self.results.len())
+            .finish()
+    }
+}
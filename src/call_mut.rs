@@ -0,0 +1,97 @@
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+pin_project! {
+    /// Future that drives both services of a [`Pipeline`] through mutable borrows.
+    ///
+    /// Created with [`Pipeline::call_mut`].
+    ///
+    /// [`Pipeline`]: crate::Pipeline
+    /// [`Pipeline::call_mut`]: crate::Pipeline::call_mut
+    pub struct CallMut<'a, R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        first: &'a mut A,
+        second: &'a mut B,
+        #[pin]
+        state: State<R, A, B>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        PollReadyFirst { req: Option<R> },
+        FirstFuturePending { #[pin] future: A::Future },
+        PollReadySecond { first_res: Option<A::Response> },
+        SecondFuturePending { #[pin] future: B::Future },
+    }
+}
+
+impl<'a, R, A, B> CallMut<'a, R, A, B>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+{
+    pub(crate) fn new(first: &'a mut A, second: &'a mut B, req: R) -> Self {
+        Self {
+            first,
+            second,
+            state: State::PollReadyFirst { req: Some(req) },
+        }
+    }
+}
+
+impl<'a, R, A, B> Future for CallMut<'a, R, A, B>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+    A::Error: Into<B::Error>,
+{
+    type Output = Result<B::Response, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::PollReadyFirst { req } => {
+                    let _ready: () = ready!(this.first.poll_ready(cx).map_err(Into::into)?);
+                    State::FirstFuturePending {
+                        future: this.first.call(req.take().unwrap()),
+                    }
+                }
+
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    State::PollReadySecond {
+                        first_res: Some(first_res),
+                    }
+                }
+
+                StateProj::PollReadySecond { first_res } => {
+                    let _ready: () = ready!(this.second.poll_ready(cx)?);
+                    State::SecondFuturePending {
+                        future: this.second.call(first_res.take().unwrap()),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::oneshot::Oneshot;
+use alloc::boxed::Box;
+use core::future::Future;
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::stream::Stream;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+pin_project! {
+    /// Stream of responses produced by calling a pipeline with every item of a stream.
+    ///
+    /// The stages are overlapped, like a hardware pipeline: while the second service processes
+    /// item `n` the first service processes item `n + 1`. Responses are produced in the same
+    /// order as the requests.
+    ///
+    /// If the first service fails to become ready the error is yielded and the stream ends.
+    ///
+    /// Created with [`Pipeline::call_stream`].
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError};
+    /// use futures_util::stream::{self, StreamExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let requests = stream::iter(vec!["a", "bb", "ccc"]);
+    ///
+    /// let results = length_svc
+    ///     .pipeline(double_svc)
+    ///     .call_stream(requests)
+    ///     .map(Result::unwrap)
+    ///     .collect::<Vec<_>>()
+    ///     .await;
+    ///
+    /// assert_eq!(results, vec![2, 4, 6]);
+    /// # }
+    /// ```
+    ///
+    /// [`Pipeline::call_stream`]: crate::Pipeline::call_stream
+    pub struct CallStream<St, A, B>
+    where
+        St: Stream,
+        A: Service<St::Item>,
+        B: Service<A::Response>,
+    {
+        #[pin]
+        requests: St,
+        first: A,
+        second: B,
+        first_future: Option<Pin<Box<A::Future>>>,
+        intermediate: Option<Result<A::Response, B::Error>>,
+        second_future: Option<Pin<Box<Oneshot<B, A::Response>>>>,
+        exhausted: bool,
+    }
+}
+
+impl<St, A, B> CallStream<St, A, B>
+where
+    St: Stream,
+    A: Service<St::Item>,
+    B: Service<A::Response>,
+{
+    pub(crate) fn new(first: A, second: B, requests: St) -> Self {
+        Self {
+            requests,
+            first,
+            second,
+            first_future: None,
+            intermediate: None,
+            second_future: None,
+            exhausted: false,
+        }
+    }
+}
+
+impl<St, A, B> Stream for CallStream<St, A, B>
+where
+    St: Stream,
+    A: Service<St::Item>,
+    B: Service<A::Response> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Item = Result<B::Response, B::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(future) = this.second_future {
+                if let Poll::Ready(result) = future.as_mut().poll(cx) {
+                    *this.second_future = None;
+                    return Poll::Ready(Some(result));
+                }
+            }
+
+            if this.second_future.is_none() {
+                match this.intermediate.take() {
+                    Some(Ok(first_res)) => {
+                        let second = this.second.clone();
+                        *this.second_future = Some(Box::pin(Oneshot::new(second, first_res)));
+                        continue;
+                    }
+                    Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                    None => {}
+                }
+            }
+
+            if let Some(future) = this.first_future {
+                if let Poll::Ready(result) = future.as_mut().poll(cx) {
+                    *this.first_future = None;
+                    *this.intermediate = Some(result.map_err(Into::into));
+                    continue;
+                }
+            }
+
+            if this.first_future.is_none() && this.intermediate.is_none() && !*this.exhausted {
+                match this.first.poll_ready(cx) {
+                    Poll::Ready(Ok(())) => match this.requests.as_mut().poll_next(cx) {
+                        Poll::Ready(Some(req)) => {
+                            *this.first_future = Some(Box::pin(this.first.call(req)));
+                            continue;
+                        }
+                        Poll::Ready(None) => *this.exhausted = true,
+                        Poll::Pending => {}
+                    },
+                    Poll::Ready(Err(err)) => {
+                        *this.exhausted = true;
+                        *this.intermediate = Some(Err(err.into()));
+                        continue;
+                    }
+                    Poll::Pending => {}
+                }
+            }
+
+            let idle = this.first_future.is_none()
+                && this.intermediate.is_none()
+                && this.second_future.is_none();
+
+            if *this.exhausted && idle {
+                return Poll::Ready(None);
+            }
+
+            return Poll::Pending;
+        }
+    }
+}
+
+impl<St, A, B> fmt::Debug for CallStream<St, A, B>
+where
+    St: Stream + fmt::Debug,
+    A: Service<St::Item> + fmt::Debug,
+    B: Service<A::Response> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallStream")
+            .field("requests", &self.requests)
+            .field("first", &self.first)
+            .field("second", &self.second)
+            .field("exhausted", &self.exhausted)
+            .finish()
+    }
+}
@@ -0,0 +1,324 @@
+use crate::{oneshot::Oneshot, Stage, StageNames};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    error::Error,
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+use tower_service::Service;
+
+/// Two services combined where the response of the first is the request of the second, and each
+/// call can be cancelled with a [`CancellationToken`].
+///
+/// The request is a pair of the request for the first service and a [`CancellationToken`]. When
+/// the token is cancelled whichever stage is pending is dropped and the call fails with a
+/// [`Cancelled`] error. Use [`CancellationToken::child_token`] to derive per call tokens from a
+/// parent token.
+///
+/// If the first service has already responded when the call is cancelled, the hook set with
+/// [`Cancellable::on_cancel`] is called with a copy of the intermediate value.
+///
+/// Created with [`Pipeline::cancellable`].
+///
+/// ```
+/// use tower_pipeline::{Cancelled, PipelineExt};
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use tokio_util::sync::CancellationToken;
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let slow_svc = service_fn(|input: usize| async move {
+///     tokio::time::sleep(Duration::from_secs(10)).await;
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let combined = length_svc
+///     .pipeline(slow_svc)
+///     .cancellable()
+///     .on_cancel(|len: usize| println!("cancelled after computing {}", len));
+///
+/// let token = CancellationToken::new();
+/// let call = tokio::spawn(combined.oneshot(("rust", token.clone())));
+///
+/// token.cancel();
+///
+/// let err = call.await.unwrap().unwrap_err();
+/// assert!(err.is::<Cancelled>());
+/// # }
+/// ```
+///
+/// [`Pipeline::cancellable`]: crate::Pipeline::cancellable
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Cancellable<A, B, H = NoHook> {
+    first: A,
+    second: B,
+    hook: H,
+    names: StageNames,
+}
+
+impl<A, B> Cancellable<A, B> {
+    /// Create a new [`Cancellable`] from two [`Service`]s.
+    pub fn new(first: A, second: B) -> Self {
+        Self {
+            first,
+            second,
+            hook: NoHook(()),
+            names: StageNames::default(),
+        }
+    }
+}
+
+impl<A, B, H> Cancellable<A, B, H> {
+    /// Set the hook that is called with the intermediate value if a call is cancelled after the
+    /// first service has responded.
+    ///
+    /// `hook` can be any closure of the form `FnOnce(T) + Clone` where `T` is the response of the
+    /// first service. That requires `T` to implement [`Clone`] since the original value is moved
+    /// into the second service.
+    pub fn on_cancel<H2>(self, hook: H2) -> Cancellable<A, B, H2> {
+        Cancellable {
+            first: self.first,
+            second: self.second,
+            hook,
+            names: self.names,
+        }
+    }
+
+    pub(crate) fn with_names(mut self, names: StageNames) -> Self {
+        self.names = names;
+        self
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+/// Hook called with the intermediate value when a call to a [`Cancellable`] is cancelled after
+/// the first service has responded.
+///
+/// Implemented for [`NoHook`] and for closures of the form `FnOnce(T)` where `T: Clone`.
+pub trait CancelHook<T> {
+    /// The part of the intermediate value that is retained for the hook.
+    type Retained;
+
+    /// Retain whatever the hook needs before the intermediate value is moved into the second
+    /// service.
+    fn retain(&self, intermediate: &T) -> Self::Retained;
+
+    /// Called when the call is cancelled.
+    fn on_cancel(self, retained: Self::Retained);
+}
+
+/// A [`CancelHook`] that does nothing.
+///
+/// This is the default hook of [`Cancellable`] and doesn't require the intermediate value to
+/// implement [`Clone`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoHook(());
+
+impl<T> CancelHook<T> for NoHook {
+    type Retained = ();
+
+    fn retain(&self, _intermediate: &T) {}
+
+    fn on_cancel(self, _retained: ()) {}
+}
+
+impl<T, F> CancelHook<T> for F
+where
+    T: Clone,
+    F: FnOnce(T),
+{
+    type Retained = T;
+
+    fn retain(&self, intermediate: &T) -> T {
+        intermediate.clone()
+    }
+
+    fn on_cancel(self, retained: T) {
+        self(retained)
+    }
+}
+
+impl<R, A, B, H> Service<(R, CancellationToken)> for Cancellable<A, B, H>
+where
+    A: Service<R>,
+    B: Service<A::Response> + Clone,
+    H: CancelHook<A::Response> + Clone,
+    A::Error: Into<B::Error>,
+    Cancelled: Into<B::Error>,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = CancellableFuture<R, A, B, H>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, (req, token): (R, CancellationToken)) -> Self::Future {
+        CancellableFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            cancelled: token.cancelled_owned(),
+            second: Some(self.second.clone()),
+            hook: Some(self.hook.clone()),
+            names: self.names,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Cancellable`].
+    pub struct CancellableFuture<R, A, B, H>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+        H: CancelHook<A::Response>,
+    {
+        #[pin]
+        state: State<R, A, B, H>,
+        #[pin]
+        cancelled: WaitForCancellationFutureOwned,
+        second: Option<B>,
+        hook: Option<H>,
+        names: StageNames,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B, H>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+        H: CancelHook<A::Response>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturePending {
+            #[pin]
+            future: Oneshot<B, A::Response>,
+            retained: Option<H::Retained>,
+        },
+    }
+}
+
+impl<R, A, B, H> Future for CancellableFuture<R, A, B, H>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+    H: CancelHook<A::Response>,
+    A::Error: Into<B::Error>,
+    Cancelled: Into<B::Error>,
+{
+    type Output = Result<B::Response, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = match future.poll(cx) {
+                        Poll::Ready(result) => result.map_err(Into::into)?,
+                        Poll::Pending => {
+                            ready!(this.cancelled.poll(cx));
+                            return Poll::Ready(Err(Cancelled {
+                                stage: Stage::First,
+                                name: this.names.first(),
+                            }
+                            .into()));
+                        }
+                    };
+                    let retained = this.hook.as_ref().unwrap().retain(&first_res);
+                    State::SecondFuturePending {
+                        future: Oneshot::new(this.second.take().unwrap(), first_res),
+                        retained: Some(retained),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future, retained } => {
+                    if let Poll::Ready(result) = future.poll(cx) {
+                        return Poll::Ready(result);
+                    }
+                    ready!(this.cancelled.poll(cx));
+                    let hook = this.hook.take().unwrap();
+                    hook.on_cancel(retained.take().unwrap());
+                    return Poll::Ready(Err(Cancelled {
+                        stage: Stage::Second,
+                        name: this.names.second(),
+                    }
+                    .into()));
+                }
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
+
+/// Error produced when a call to a [`Cancellable`] is cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled {
+    stage: Stage,
+    name: &'static str,
+}
+
+impl Cancelled {
+    /// The stage that was pending when the call was cancelled.
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    /// The name of the stage, as set with [`Pipeline::named`].
+    ///
+    /// [`Pipeline::named`]: crate::Pipeline::named
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "call cancelled while {} service was pending", self.name)
+    }
+}
+
+impl Error for Cancelled {}
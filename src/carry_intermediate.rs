@@ -0,0 +1,182 @@
+use crate::{oneshot::Oneshot, PipelineError, SecondError};
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Two services combined where the response of the first is the request of the second, and
+/// errors of the second service carry the intermediate value it was called with.
+///
+/// This makes it possible to compensate or log when the second service fails. Errors of the
+/// first service are reported as [`PipelineError::First`] and errors of the second as
+/// [`PipelineError::Second`] with a [`SecondError`]. The intermediate value is cloned before it
+/// is passed to the second service, so it must implement [`Clone`]. Like [`SplitErrors`] the
+/// error types of the two services are unrelated.
+///
+/// Created with [`Pipeline::carry_intermediate`].
+///
+/// ```
+/// use tower_pipeline::{Pipeline, PipelineError};
+/// use tower::{service_fn, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let parse_svc = service_fn(|input: &'static str| async move {
+///     input.parse::<u32>()
+/// });
+///
+/// let double_svc = service_fn(|input: u32| async move {
+///     input.checked_mul(2).ok_or("overflow")
+/// });
+///
+/// let combined = Pipeline::new(parse_svc, double_svc).carry_intermediate();
+///
+/// let err = combined.oneshot("4294967295").await.unwrap_err();
+///
+/// match err {
+///     PipelineError::Second(err) => {
+///         assert_eq!(err.error, "overflow");
+///         assert_eq!(err.intermediate, 4294967295);
+///     }
+///     _ => panic!("expected the second service to fail"),
+/// }
+/// # }
+/// ```
+///
+/// [`SplitErrors`]: crate::SplitErrors
+/// [`Pipeline::carry_intermediate`]: crate::Pipeline::carry_intermediate
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CarryIntermediate<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> CarryIntermediate<A, B> {
+    /// Create a new [`CarryIntermediate`] from two [`Service`]s.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, A, B> Service<R> for CarryIntermediate<A, B>
+where
+    A: Service<R>,
+    A::Response: Clone,
+    B: Service<A::Response> + Clone,
+{
+    type Response = B::Response;
+    type Error = PipelineError<A::Error, SecondError<A::Response, B::Error>>;
+    type Future = CarryIntermediateFuture<R, A, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(PipelineError::First)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        CarryIntermediateFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            second: Some(self.second.clone()),
+            intermediate: None,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`CarryIntermediate`].
+    pub struct CarryIntermediateFuture<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        #[pin]
+        state: State<R, A, B>,
+        second: Option<B>,
+        intermediate: Option<A::Response>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturePending { #[pin] future: Oneshot<B, A::Response> },
+    }
+}
+
+impl<R, A, B> Future for CarryIntermediateFuture<R, A, B>
+where
+    A: Service<R>,
+    A::Response: Clone,
+    B: Service<A::Response>,
+{
+    type Output = Result<B::Response, PipelineError<A::Error, SecondError<A::Response, B::Error>>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(PipelineError::First)?);
+                    let second = this.second.take().unwrap();
+                    *this.intermediate = Some(first_res.clone());
+                    State::SecondFuturePending {
+                        future: Oneshot::new(second, first_res),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => {
+                    let result = ready!(future.poll(cx));
+                    let intermediate = this.intermediate.take().unwrap();
+                    return Poll::Ready(result.map_err(|error| second_error(error, intermediate)));
+                }
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
+
+fn second_error<T, E1, E2>(error: E2, intermediate: T) -> PipelineError<E1, SecondError<T, E2>> {
+    PipelineError::Second(SecondError::new(error, intermediate))
+}
@@ -0,0 +1,345 @@
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// A tuple of services combined where the response of each is the request of the next.
+///
+/// Unlike nesting [`Pipeline`]s the type stays flat regardless of how many services are
+/// chained. Tuples of two to eight services are supported.
+///
+/// Every service's error must convert into the error of the last service and every service but
+/// the first must implement [`Clone`].
+///
+/// ```
+/// use tower_pipeline::IntoPipeline;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let to_string_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input.to_string())
+/// });
+///
+/// let combined = (length_svc, double_svc, to_string_svc).into_pipeline();
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, "8");
+/// # }
+/// ```
+///
+/// [`Pipeline`]: crate::Pipeline
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Chain<T> {
+    services: T,
+}
+
+impl<T> Chain<T> {
+    /// Create a new [`Chain`] from a tuple of [`Service`]s.
+    pub fn new(services: T) -> Self {
+        Self { services }
+    }
+
+    /// Get a reference to the tuple of services.
+    pub fn get_ref(&self) -> &T {
+        &self.services
+    }
+
+    /// Get a mutable reference to the tuple of services.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.services
+    }
+
+    /// Consume `self`, returning the tuple of services.
+    pub fn into_inner(self) -> T {
+        self.services
+    }
+}
+
+impl<R, T> Service<R> for Chain<T>
+where
+    T: Stages<R>,
+{
+    type Response = T::Response;
+    type Error = T::Error;
+    type Future = ChainFuture<R, T>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.services.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        ChainFuture {
+            inner: self.services.call(req),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Chain`].
+    pub struct ChainFuture<R, T>
+    where
+        T: Stages<R>,
+    {
+        #[pin]
+        inner: T::Future,
+    }
+}
+
+impl<R, T> Future for ChainFuture<R, T>
+where
+    T: Stages<R>,
+{
+    type Output = Result<T::Response, T::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().inner.poll(cx)
+    }
+}
+
+/// Convert a tuple of services into a [`Chain`].
+///
+/// This trait is implemented for tuples of two to eight elements.
+pub trait IntoPipeline: Sized {
+    /// Construct a [`Chain`].
+    fn into_pipeline(self) -> Chain<Self> {
+        Chain::new(self)
+    }
+}
+
+mod sealed {
+    use super::*;
+
+    /// A tuple of services that can be driven by a [`Chain`].
+    pub trait Stages<R> {
+        type Response;
+        type Error;
+        type Future: Future<Output = Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>>;
+
+        fn call(&mut self, req: R) -> Self::Future;
+    }
+}
+
+use sealed::Stages;
+
+macro_rules! impl_chain {
+    (
+        $future:ident, $proj:ident;
+        ($first:ident, $first_svc:ident, $first_pending:ident);
+        $(($prev:ident, $prev_pending:ident, $svc:ident, $field:ident, $ready:ident, $pending:ident)),+;
+        ($last:ident, $last_pending:ident)
+    ) => {
+        impl<$first, $($svc),+> IntoPipeline for ($first, $($svc),+) {}
+
+        impl<R, $first, $($svc),+> Stages<R> for ($first, $($svc),+)
+        where
+            $first: Service<R>,
+            $first::Error: Into<$last::Error>,
+            $(
+                $svc: Service<$prev::Response> + Clone,
+                $svc::Error: Into<$last::Error>,
+            )+
+        {
+            type Response = $last::Response;
+            type Error = $last::Error;
+            type Future = $future<R, $first, $($svc),+>;
+
+            fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+                let ($first_svc, ..) = self;
+                $first_svc.poll_ready(cx).map_err(Into::into)
+            }
+
+            fn call(&mut self, req: R) -> Self::Future {
+                let ($first_svc, $($field),+) = self;
+                $future {
+                    state: $proj::$first_pending {
+                        future: $first_svc.call(req),
+                    },
+                    $($field: Some($field.clone()),)+
+                }
+            }
+        }
+
+        pin_project! {
+            #[doc(hidden)]
+            pub struct $future<R, $first, $($svc),+>
+            where
+                $first: Service<R>,
+                $($svc: Service<$prev::Response>,)+
+            {
+                #[pin]
+                state: $proj<R, $first, $($svc),+>,
+                $($field: Option<$svc>,)+
+            }
+        }
+
+        pin_project! {
+            #[project = StateProj]
+            #[doc(hidden)]
+            pub enum $proj<R, $first, $($svc),+>
+            where
+                $first: Service<R>,
+                $($svc: Service<$prev::Response>,)+
+            {
+                $first_pending { #[pin] future: $first::Future },
+                $(
+                    $ready { req: Option<$prev::Response>, svc: $svc },
+                    $pending { #[pin] future: $svc::Future },
+                )+
+            }
+        }
+
+        impl<R, $first, $($svc),+> Future for $future<R, $first, $($svc),+>
+        where
+            $first: Service<R>,
+            $first::Error: Into<$last::Error>,
+            $(
+                $svc: Service<$prev::Response>,
+                $svc::Error: Into<$last::Error>,
+            )+
+        {
+            type Output = Result<$last::Response, $last::Error>;
+
+            fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                loop {
+                    let mut this = self.as_mut().project();
+
+                    let new_state = match this.state.as_mut().project() {
+                        $(
+                            StateProj::$prev_pending { future } => {
+                                let res = ready!(future.poll(cx).map_err(Into::into)?);
+                                $proj::$ready {
+                                    req: Some(res),
+                                    svc: this.$field.take().unwrap(),
+                                }
+                            }
+
+                            StateProj::$ready { req, svc } => {
+                                let _ready: () = ready!(svc.poll_ready(cx).map_err(Into::into)?);
+                                $proj::$pending {
+                                    future: svc.call(req.take().unwrap()),
+                                }
+                            }
+                        )+
+
+                        StateProj::$last_pending { future } => {
+                            return future.poll(cx).map_err(Into::into);
+                        }
+                    };
+
+                    this.state.set(new_state);
+                }
+            }
+        }
+    };
+}
+
+mod arity {
+    use super::*;
+
+    mod two {
+        use super::*;
+        impl_chain!(
+            Chain2Future, Chain2State;
+            (S1, s1, Pending1);
+            (S1, Pending1, S2, s2, Ready2, Pending2);
+            (S2, Pending2)
+        );
+    }
+
+    mod three {
+        use super::*;
+        impl_chain!(
+            Chain3Future, Chain3State;
+            (S1, s1, Pending1);
+            (S1, Pending1, S2, s2, Ready2, Pending2),
+            (S2, Pending2, S3, s3, Ready3, Pending3);
+            (S3, Pending3)
+        );
+    }
+
+    mod four {
+        use super::*;
+        impl_chain!(
+            Chain4Future, Chain4State;
+            (S1, s1, Pending1);
+            (S1, Pending1, S2, s2, Ready2, Pending2),
+            (S2, Pending2, S3, s3, Ready3, Pending3),
+            (S3, Pending3, S4, s4, Ready4, Pending4);
+            (S4, Pending4)
+        );
+    }
+
+    mod five {
+        use super::*;
+        impl_chain!(
+            Chain5Future, Chain5State;
+            (S1, s1, Pending1);
+            (S1, Pending1, S2, s2, Ready2, Pending2),
+            (S2, Pending2, S3, s3, Ready3, Pending3),
+            (S3, Pending3, S4, s4, Ready4, Pending4),
+            (S4, Pending4, S5, s5, Ready5, Pending5);
+            (S5, Pending5)
+        );
+    }
+
+    mod six {
+        use super::*;
+        impl_chain!(
+            Chain6Future, Chain6State;
+            (S1, s1, Pending1);
+            (S1, Pending1, S2, s2, Ready2, Pending2),
+            (S2, Pending2, S3, s3, Ready3, Pending3),
+            (S3, Pending3, S4, s4, Ready4, Pending4),
+            (S4, Pending4, S5, s5, Ready5, Pending5),
+            (S5, Pending5, S6, s6, Ready6, Pending6);
+            (S6, Pending6)
+        );
+    }
+
+    mod seven {
+        use super::*;
+        impl_chain!(
+            Chain7Future, Chain7State;
+            (S1, s1, Pending1);
+            (S1, Pending1, S2, s2, Ready2, Pending2),
+            (S2, Pending2, S3, s3, Ready3, Pending3),
+            (S3, Pending3, S4, s4, Ready4, Pending4),
+            (S4, Pending4, S5, s5, Ready5, Pending5),
+            (S5, Pending5, S6, s6, Ready6, Pending6),
+            (S6, Pending6, S7, s7, Ready7, Pending7);
+            (S7, Pending7)
+        );
+    }
+
+    mod eight {
+        use super::*;
+        impl_chain!(
+            Chain8Future, Chain8State;
+            (S1, s1, Pending1);
+            (S1, Pending1, S2, s2, Ready2, Pending2),
+            (S2, Pending2, S3, s3, Ready3, Pending3),
+            (S3, Pending3, S4, s4, Ready4, Pending4),
+            (S4, Pending4, S5, s5, Ready5, Pending5),
+            (S5, Pending5, S6, s6, Ready6, Pending6),
+            (S6, Pending6, S7, s7, Ready7, Pending7),
+            (S7, Pending7, S8, s8, Ready8, Pending8);
+            (S8, Pending8)
+        );
+    }
+}
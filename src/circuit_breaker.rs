@@ -0,0 +1,327 @@
+use crate::{BoxError, Stage};
+use alloc::boxed::Box;
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    error::Error,
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tower_service::Service;
+
+/// A service that stops calling the inner service after repeated failures.
+///
+/// After [`CircuitBreakerConfig::failure_threshold`] consecutive failures the circuit opens and
+/// calls fail immediately with a [`CircuitOpen`] error, without calling the inner service. Once
+/// [`CircuitBreakerConfig::reset_timeout`] has passed a single trial call is let through. If it
+/// succeeds the circuit closes again, otherwise it stays open for another timeout.
+///
+/// Clones share the same circuit so it also covers the copies of the second service a
+/// [`Pipeline`] hands to each response future. Combine it with [`Pipeline::with_fallback`] to
+/// call a fallback instead of failing.
+///
+/// Usually created with [`Pipeline::circuit_breaker`].
+///
+/// ```
+/// use tower_pipeline::{CircuitBreakerConfig, CircuitOpen, PipelineExt, Stage};
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let failing_svc = service_fn(|_: usize| async move {
+///     Err::<usize, BoxError>("downstream is down".into())
+/// });
+///
+/// let config = CircuitBreakerConfig::new()
+///     .failure_threshold(2)
+///     .reset_timeout(Duration::from_secs(30));
+///
+/// let combined = length_svc.pipeline(failing_svc).circuit_breaker(config);
+///
+/// // the first two calls reach the second service
+/// for _ in 0..2 {
+///     let err = combined.clone().oneshot("rust").await.unwrap_err();
+///     assert_eq!(err.to_string(), "downstream is down");
+/// }
+///
+/// // then the circuit opens
+/// let err = combined.oneshot("rust").await.unwrap_err();
+/// assert_eq!(err.downcast_ref::<CircuitOpen>().unwrap().stage(), Stage::Second);
+/// # }
+/// ```
+///
+/// [`Pipeline`]: crate::Pipeline
+/// [`Pipeline::circuit_breaker`]: crate::Pipeline::circuit_breaker
+/// [`Pipeline::with_fallback`]: crate::Pipeline::with_fallback
+#[derive(Debug)]
+pub struct CircuitBreaker<S> {
+    inner: S,
+    circuit: Arc<Mutex<Circuit>>,
+    config: CircuitBreakerConfig,
+    stage: Stage,
+    name: &'static str,
+    permitted: bool,
+}
+
+/// Configuration for a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitBreakerConfig {
+    failure_threshold: usize,
+    reset_timeout: Duration,
+}
+
+impl CircuitBreakerConfig {
+    /// Create a new [`CircuitBreakerConfig`].
+    ///
+    /// Defaults to opening after 5 consecutive failures and trying again after 30 seconds.
+    pub fn new() -> Self {
+        Self {
+            failure_threshold: 5,
+            reset_timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Set the number of consecutive failures after which the circuit opens.
+    pub fn failure_threshold(mut self, failure_threshold: usize) -> Self {
+        self.failure_threshold = failure_threshold;
+        self
+    }
+
+    /// Set how long the circuit stays open before a trial call is let through.
+    pub fn reset_timeout(mut self, reset_timeout: Duration) -> Self {
+        self.reset_timeout = reset_timeout;
+        self
+    }
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+enum Circuit {
+    Closed { failures: usize },
+    Open { until: Instant },
+    // the trial call might never complete, so another is let through after `until`
+    HalfOpen { until: Instant },
+}
+
+impl Circuit {
+    fn record(&mut self, success: bool, config: &CircuitBreakerConfig) {
+        *self = match (&*self, success) {
+            (_, true) => Circuit::Closed { failures: 0 },
+            (Circuit::Closed { failures }, false) if failures + 1 < config.failure_threshold => {
+                Circuit::Closed {
+                    failures: failures + 1,
+                }
+            }
+            (_, false) => Circuit::Open {
+                until: Instant::now() + config.reset_timeout,
+            },
+        };
+    }
+}
+
+impl<S> CircuitBreaker<S> {
+    /// Create a new [`CircuitBreaker`] for the given stage.
+    pub fn new(inner: S, config: CircuitBreakerConfig, stage: Stage) -> Self {
+        Self {
+            inner,
+            circuit: Arc::new(Mutex::new(Circuit::Closed { failures: 0 })),
+            config,
+            stage,
+            name: stage.default_name(),
+            permitted: false,
+        }
+    }
+
+    /// Set the name of the stage used in errors.
+    ///
+    /// Defaults to the name of the [`Stage`], `"first"` or `"second"`. [`Pipeline::named`] sets
+    /// this for middleware added to a pipeline afterwards.
+    ///
+    /// [`Pipeline::named`]: crate::Pipeline::named
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Returns `true` if the circuit is open and calls are failing immediately.
+    pub fn is_open(&self) -> bool {
+        !matches!(*self.circuit.lock().unwrap(), Circuit::Closed { .. })
+    }
+}
+
+impl<S> Clone for CircuitBreaker<S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            circuit: Arc::clone(&self.circuit),
+            config: self.config,
+            stage: self.stage,
+            name: self.name,
+            permitted: false,
+        }
+    }
+}
+
+impl<S, T> Service<T> for CircuitBreaker<S>
+where
+    S: Service<T>,
+    S::Error: Into<BoxError>,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = CircuitBreakerFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if !self.permitted {
+            let mut circuit = self.circuit.lock().unwrap();
+            self.permitted = match *circuit {
+                Circuit::Closed { .. } => true,
+                Circuit::Open { until } | Circuit::HalfOpen { until }
+                    if Instant::now() >= until =>
+                {
+                    *circuit = Circuit::HalfOpen {
+                        until: Instant::now() + self.config.reset_timeout,
+                    };
+                    true
+                }
+                Circuit::Open { .. } | Circuit::HalfOpen { .. } => false,
+            };
+        }
+
+        if self.permitted {
+            self.inner.poll_ready(cx).map_err(Into::into)
+        } else {
+            // the call fails immediately, without waiting for the inner service
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        if std::mem::replace(&mut self.permitted, false) {
+            CircuitBreakerFuture {
+                state: State::Called {
+                    future: self.inner.call(req),
+                    circuit: Arc::clone(&self.circuit),
+                    config: self.config,
+                },
+            }
+        } else {
+            CircuitBreakerFuture {
+                state: State::Open {
+                    error: Some(CircuitOpen {
+                        stage: self.stage,
+                        name: self.name,
+                    }),
+                },
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`CircuitBreaker`].
+    pub struct CircuitBreakerFuture<F> {
+        #[pin]
+        state: State<F>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<F> {
+        Called {
+            #[pin]
+            future: F,
+            circuit: Arc<Mutex<Circuit>>,
+            config: CircuitBreakerConfig,
+        },
+        Open { error: Option<CircuitOpen> },
+    }
+}
+
+impl<F, T, E> Future for CircuitBreakerFuture<F>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Into<BoxError>,
+{
+    type Output = Result<T, BoxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().state.project() {
+            StateProj::Called {
+                future,
+                circuit,
+                config,
+            } => {
+                let result = ready!(future.poll(cx));
+                circuit.lock().unwrap().record(result.is_ok(), config);
+                Poll::Ready(result.map_err(Into::into))
+            }
+            StateProj::Open { error } => {
+                let error = error.take().expect("polled after completion");
+                Poll::Ready(Err(Box::new(error)))
+            }
+        }
+    }
+}
+
+/// Error produced when the circuit of a [`CircuitBreaker`] is open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitOpen {
+    stage: Stage,
+    name: &'static str,
+}
+
+impl CircuitOpen {
+    /// The stage whose circuit is open.
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    /// The name of the stage, as set with [`Pipeline::named`].
+    ///
+    /// [`Pipeline::named`]: crate::Pipeline::named
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "circuit for {} service is open", self.name)
+    }
+}
+
+impl Error for CircuitOpen {}
@@ -0,0 +1,142 @@
+use crate::semaphore::{Permit, Semaphore};
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+/// A service that limits the number of calls in flight.
+///
+/// `poll_ready` returns `Poll::Pending` while `max` calls are in flight. Clones share the same
+/// limit, so the limit also holds for the copies of the second service a [`Pipeline`] hands to
+/// each response future.
+///
+/// Usually created with [`Pipeline::concurrency_limit`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// // a fragile service that can only handle two calls at a time
+/// let fragile_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let combined = length_svc
+///     .pipeline(fragile_svc)
+///     .concurrency_limit(100, 2);
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// # }
+/// ```
+///
+/// [`Pipeline`]: crate::Pipeline
+/// [`Pipeline::concurrency_limit`]: crate::Pipeline::concurrency_limit
+#[derive(Debug)]
+pub struct ConcurrencyLimit<S> {
+    inner: S,
+    semaphore: Arc<Semaphore>,
+    permit: Option<Permit>,
+}
+
+impl<S> ConcurrencyLimit<S> {
+    /// Create a new [`ConcurrencyLimit`] that allows at most `max` calls in flight.
+    pub fn new(inner: S, max: usize) -> Self {
+        Self {
+            inner,
+            semaphore: Semaphore::new(max),
+            permit: None,
+        }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Clone for ConcurrencyLimit<S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        // permits aren't cloned since that would allow more calls than the limit
+        Self {
+            inner: self.inner.clone(),
+            semaphore: Arc::clone(&self.semaphore),
+            permit: None,
+        }
+    }
+}
+
+impl<R, S> Service<R> for ConcurrencyLimit<S>
+where
+    S: Service<R>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ConcurrencyLimitFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.permit.is_none() {
+            match Semaphore::poll_acquire(&self.semaphore, cx) {
+                Poll::Ready(permit) => self.permit = Some(permit),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        let permit = self
+            .permit
+            .take()
+            .expect("`poll_ready` must be called before `call`");
+        ConcurrencyLimitFuture {
+            future: self.inner.call(req),
+            _permit: permit,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`ConcurrencyLimit`].
+    #[derive(Debug)]
+    pub struct ConcurrencyLimitFuture<F> {
+        #[pin]
+        future: F,
+        _permit: Permit,
+    }
+}
+
+impl<F> Future for ConcurrencyLimitFuture<F>
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().future.poll(cx)
+    }
+}
@@ -0,0 +1,325 @@
+use crate::{BoxError, Elapsed, Pipeline, ResponseFuture, ResponseStage, Stage, StageNames};
+use alloc::boxed::Box;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::{Instant, Sleep};
+use tower_service::Service;
+
+/// A [`Pipeline`] that fails requests that take longer than a single budget shared by both
+/// stages.
+///
+/// The budget starts when the pipeline is called, so time spent in the first service reduces
+/// the time left for the second service to become ready and respond. Unlike a timeout for
+/// each stage, the latency of a request is never more than the budget. Requests that run out of
+/// time fail with an [`Elapsed`] error that says which stage was running.
+///
+/// Created with [`Pipeline::deadline`].
+///
+/// ```
+/// use tower_pipeline::{Elapsed, PipelineExt, Stage};
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let slow_length_svc = service_fn(|input: &'static str| async move {
+///     tokio::time::sleep(Duration::from_millis(30)).await;
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let slow_double_svc = service_fn(|input: usize| async move {
+///     tokio::time::sleep(Duration::from_millis(30)).await;
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// // each stage is fast enough on its own, but not both of them
+/// let combined = slow_length_svc
+///     .pipeline(slow_double_svc)
+///     .deadline(Duration::from_millis(50));
+///
+/// let err = combined.oneshot("rust").await.unwrap_err();
+///
+/// assert_eq!(err.downcast_ref::<Elapsed>().unwrap().stage(), Stage::Second);
+/// # }
+/// ```
+///
+/// [`Pipeline::deadline`]: crate::Pipeline::deadline
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline<A, B> {
+    inner: Pipeline<A, B>,
+    budget: Duration,
+}
+
+impl<A, B> Deadline<A, B> {
+    /// Create a new [`Deadline`] of a [`Pipeline`].
+    pub fn new(inner: Pipeline<A, B>, budget: Duration) -> Self {
+        Self { inner, budget }
+    }
+
+    /// The budget shared by both stages.
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Get a reference to the pipeline.
+    pub fn get_ref(&self) -> &Pipeline<A, B> {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the pipeline.
+    pub fn get_mut(&mut self) -> &mut Pipeline<A, B> {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the pipeline.
+    pub fn into_inner(self) -> Pipeline<A, B> {
+        self.inner
+    }
+}
+
+impl<R, A, B> Service<R> for Deadline<A, B>
+where
+    A: Service<R>,
+    B: Service<A::Response> + Clone,
+    A::Error: Into<B::Error>,
+    B::Error: Into<BoxError>,
+{
+    type Response = B::Response;
+    type Error = BoxError;
+    type Future = DeadlineFuture<R, A, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        DeadlineFuture {
+            inner: self.inner.call(req),
+            sleep: tokio::time::sleep(self.budget),
+            names: self.inner.names(),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Deadline`].
+    pub struct DeadlineFuture<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        #[pin]
+        inner: ResponseFuture<R, A, B>,
+        #[pin]
+        sleep: Sleep,
+        names: StageNames,
+    }
+}
+
+impl<R, A, B> Future for DeadlineFuture<R, A, B>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+    A::Error: Into<B::Error>,
+    B::Error: Into<BoxError>,
+{
+    type Output = Result<B::Response, BoxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        if let Poll::Ready(result) = this.inner.as_mut().poll(cx) {
+            return Poll::Ready(result.map_err(Into::into));
+        }
+
+        match this.sleep.poll(cx) {
+            Poll::Ready(()) => {
+                let stage = match this.inner.stage() {
+                    ResponseStage::FirstPending => Stage::First,
+                    _ => Stage::Second,
+                };
+                let elapsed = Elapsed::new(stage, this.names.get(stage));
+                Poll::Ready(Err(Box::new(elapsed)))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A value that carries a deadline, such as a request with a deadline parsed from a header.
+///
+/// Used by [`PropagateDeadline`] to enforce the deadline of the intermediate value on the second
+/// service.
+pub trait DeadlineCarrier {
+    /// The point in time the value must be handled by, if any.
+    fn deadline(&self) -> Option<Instant>;
+}
+
+/// A service that fails requests that aren't handled before the deadline they carry.
+///
+/// Requests without a deadline aren't limited. Requests whose deadline has already passed fail
+/// without calling the inner service. Requests that run out of time fail with an [`Elapsed`]
+/// error.
+///
+/// Usually created with [`Pipeline::propagate_deadline`], in which case the deadline carried
+/// by the response of the first service is enforced on the second.
+///
+/// ```
+/// use tower_pipeline::{DeadlineCarrier, Elapsed, PipelineExt, Stage};
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use tokio::time::{Duration, Instant};
+///
+/// struct Parsed {
+///     body: String,
+///     deadline: Option<Instant>,
+/// }
+///
+/// impl DeadlineCarrier for Parsed {
+///     fn deadline(&self) -> Option<Instant> {
+///         self.deadline
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let parse_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(Parsed {
+///         body: input.to_owned(),
+///         deadline: Some(Instant::now() + Duration::from_millis(10)),
+///     })
+/// });
+///
+/// let slow_svc = service_fn(|parsed: Parsed| async move {
+///     tokio::time::sleep(Duration::from_secs(10)).await;
+///     Ok::<_, BoxError>(parsed.body.len())
+/// });
+///
+/// let combined = parse_svc.pipeline(slow_svc).propagate_deadline();
+///
+/// let err = combined.oneshot("rust").await.unwrap_err();
+///
+/// assert_eq!(err.downcast_ref::<Elapsed>().unwrap().stage(), Stage::Second);
+/// # }
+/// ```
+///
+/// [`Pipeline::propagate_deadline`]: crate::Pipeline::propagate_deadline
+#[derive(Debug, Clone, Copy)]
+pub struct PropagateDeadline<S> {
+    inner: S,
+    name: &'static str,
+}
+
+impl<S> PropagateDeadline<S> {
+    /// Create a new [`PropagateDeadline`].
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            name: Stage::Second.default_name(),
+        }
+    }
+
+    /// Set the name of the stage used in errors.
+    ///
+    /// Defaults to `"second"`.
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, T> Service<T> for PropagateDeadline<S>
+where
+    S: Service<T>,
+    S::Error: Into<BoxError>,
+    T: DeadlineCarrier,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = PropagateDeadlineFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        let elapsed = Elapsed::new(Stage::Second, self.name);
+
+        let state = match req.deadline() {
+            Some(deadline) if deadline <= Instant::now() => PropagateState::Expired,
+            deadline => PropagateState::Running {
+                future: self.inner.call(req),
+                sleep: deadline.map(tokio::time::sleep_until),
+            },
+        };
+
+        PropagateDeadlineFuture { state, elapsed }
+    }
+}
+
+pin_project! {
+    /// Response future of [`PropagateDeadline`].
+    pub struct PropagateDeadlineFuture<F> {
+        #[pin]
+        state: PropagateState<F>,
+        elapsed: Elapsed,
+    }
+}
+
+pin_project! {
+    #[project = PropagateStateProj]
+    enum PropagateState<F> {
+        Expired,
+        Running {
+            #[pin]
+            future: F,
+            #[pin]
+            sleep: Option<Sleep>,
+        },
+    }
+}
+
+impl<F, T, E> Future for PropagateDeadlineFuture<F>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Into<BoxError>,
+{
+    type Output = Result<T, BoxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let (future, sleep) = match this.state.project() {
+            PropagateStateProj::Expired => return Poll::Ready(Err(Box::new(*this.elapsed))),
+            PropagateStateProj::Running { future, sleep } => (future, sleep),
+        };
+
+        if let Poll::Ready(result) = future.poll(cx) {
+            return Poll::Ready(result.map_err(Into::into));
+        }
+
+        match sleep.as_pin_mut().map(|sleep| sleep.poll(cx)) {
+            Some(Poll::Ready(())) => Poll::Ready(Err(Box::new(*this.elapsed))),
+            _ => Poll::Pending,
+        }
+    }
+}
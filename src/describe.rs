@@ -0,0 +1,465 @@
+use crate::{
+    Branch, Fallback, Join, OrElse, Pipeline, Pipeline3, ScatterGather, Steer, Tee, Unzip,
+};
+use alloc::{string::String, vec, vec::Vec};
+use core::{
+    fmt::{self, Write},
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+/// Services that can describe the graph of stages they are made of.
+///
+/// Implemented by [`Pipeline`] and the other combinators in this crate, as long as their inner
+/// services implement [`Describe`] as well. Middleware that wraps a single service, such as
+/// [`Retry`], is transparent and describes the service it wraps. Wrap the services at the
+/// leaves of the graph in a [`Labeled`] to give them a name.
+///
+/// The graph can be rendered as [DOT] or [Mermaid].
+///
+/// ```
+/// use tower_pipeline::{Graph, Labeled, PipelineExt};
+/// use tower::{service_fn, BoxError};
+///
+/// let geo_lookup = service_fn(|ip: &'static str| async move {
+///     Ok::<_, BoxError>(ip.len())
+/// });
+///
+/// let enrichment = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let combined = Labeled::new(geo_lookup, "geo-lookup")
+///     .pipeline(Labeled::new(enrichment, "enrichment"));
+///
+/// let graph = Graph::describe(&combined);
+///
+/// assert_eq!(
+///     graph.to_mermaid(),
+///     "flowchart LR\n    \
+///         n0[\"geo-lookup\"]\n    \
+///         n1[\"enrichment\"]\n    \
+///         n0 --> n1\n",
+/// );
+///
+/// assert_eq!(
+///     graph.to_dot(),
+///     "digraph {\n    \
+///         n0 [label=\"geo-lookup\"];\n    \
+///         n1 [label=\"enrichment\"];\n    \
+///         n0 -> n1;\n\
+///     }\n",
+/// );
+/// ```
+///
+/// [`Retry`]: crate::Retry
+/// [DOT]: https://graphviz.org/doc/info/lang.html
+/// [Mermaid]: https://mermaid.js.org/syntax/flowchart.html
+pub trait Describe {
+    /// Add the stages of `self` to `graph`.
+    ///
+    /// Returns the nodes requests enter through and the nodes responses leave from, so callers
+    /// can connect them to the stages before and after.
+    fn describe(&self, graph: &mut Graph) -> Subgraph;
+}
+
+/// The graph of stages of a service, built with [`Describe`].
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    nodes: Vec<&'static str>,
+    edges: Vec<(NodeId, NodeId)>,
+}
+
+impl Graph {
+    /// Create a new empty [`Graph`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new [`Graph`] with the stages of `service`.
+    pub fn describe<S>(service: &S) -> Self
+    where
+        S: Describe,
+    {
+        let mut graph = Self::new();
+        service.describe(&mut graph);
+        graph
+    }
+
+    /// Add a node with the given label.
+    pub fn add_node(&mut self, label: &'static str) -> NodeId {
+        self.nodes.push(label);
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// Add an edge between two nodes.
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) {
+        self.edges.push((from, to));
+    }
+
+    /// Add edges from every exit of `from` to every entry of `to`.
+    pub fn connect(&mut self, from: &Subgraph, to: &Subgraph) {
+        for &exit in &from.exits {
+            for &entry in &to.entries {
+                self.add_edge(exit, entry);
+            }
+        }
+    }
+
+    /// Render the graph in the [DOT] language used by Graphviz.
+    ///
+    /// [DOT]: https://graphviz.org/doc/info/lang.html
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        self.write_dot(&mut out)
+            .expect("writing to a `String` never fails");
+        out
+    }
+
+    /// Render the graph as a [Mermaid] flowchart.
+    ///
+    /// [Mermaid]: https://mermaid.js.org/syntax/flowchart.html
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::new();
+        self.write_mermaid(&mut out)
+            .expect("writing to a `String` never fails");
+        out
+    }
+
+    fn write_dot(&self, out: &mut String) -> fmt::Result {
+        out.push_str("digraph {\n");
+        for (id, label) in self.nodes.iter().enumerate() {
+            writeln!(
+                out,
+                "    n{} [label=\"{}\"];",
+                id,
+                label.replace('"', "\\\"")
+            )?;
+        }
+        for (from, to) in &self.edges {
+            writeln!(out, "    n{} -> n{};", from.0, to.0)?;
+        }
+        out.push_str("}\n");
+        Ok(())
+    }
+
+    fn write_mermaid(&self, out: &mut String) -> fmt::Result {
+        out.push_str("flowchart LR\n");
+        for (id, label) in self.nodes.iter().enumerate() {
+            writeln!(out, "    n{}[\"{}\"]", id, label.replace('"', "#quot;"))?;
+        }
+        for (from, to) in &self.edges {
+            writeln!(out, "    n{} --> n{}", from.0, to.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Identifies a node in a [`Graph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// The nodes a service added to a [`Graph`] is entered through and left from.
+///
+/// Returned by [`Describe::describe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subgraph {
+    entries: Vec<NodeId>,
+    exits: Vec<NodeId>,
+}
+
+impl Subgraph {
+    /// Create a new [`Subgraph`] from its entry and exit nodes.
+    pub fn new(entries: Vec<NodeId>, exits: Vec<NodeId>) -> Self {
+        Self { entries, exits }
+    }
+
+    /// Create a new [`Subgraph`] consisting of a single node.
+    pub fn node(id: NodeId) -> Self {
+        Self::new(vec![id], vec![id])
+    }
+
+    /// The nodes requests enter through.
+    pub fn entries(&self) -> &[NodeId] {
+        &self.entries
+    }
+
+    /// The nodes responses leave from.
+    pub fn exits(&self) -> &[NodeId] {
+        &self.exits
+    }
+
+    fn then(graph: &mut Graph, first: Subgraph, rest: &[Subgraph]) -> Self {
+        let exits = rest
+            .iter()
+            .flat_map(|next| {
+                graph.connect(&first, next);
+                next.exits.iter().copied()
+            })
+            .collect();
+        Self::new(first.entries, exits)
+    }
+}
+
+/// A service with a label, used as a node by [`Describe`].
+///
+/// The service is otherwise unchanged.
+#[derive(Debug, Clone, Copy)]
+pub struct Labeled<S> {
+    inner: S,
+    label: &'static str,
+}
+
+impl<S> Labeled<S> {
+    /// Create a new [`Labeled`].
+    pub fn new(inner: S, label: &'static str) -> Self {
+        Self { inner, label }
+    }
+
+    /// The label of the service.
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, R> Service<R> for Labeled<S>
+where
+    S: Service<R>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        self.inner.call(req)
+    }
+}
+
+impl<S> Describe for Labeled<S> {
+    fn describe(&self, graph: &mut Graph) -> Subgraph {
+        Subgraph::node(graph.add_node(self.label))
+    }
+}
+
+impl<A, B> Describe for Pipeline<A, B>
+where
+    A: Describe,
+    B: Describe,
+{
+    fn describe(&self, graph: &mut Graph) -> Subgraph {
+        let first = self.first.describe(graph);
+        let second = self.second.describe(graph);
+        Subgraph::then(graph, first, &[second])
+    }
+}
+
+impl<A, B, C> Describe for Pipeline3<A, B, C>
+where
+    A: Describe,
+    B: Describe,
+    C: Describe,
+{
+    fn describe(&self, graph: &mut Graph) -> Subgraph {
+        let first = self.first_as_ref().describe(graph);
+        let second = self.second_as_ref().describe(graph);
+        let second = Subgraph::then(graph, first, &[second]);
+        let third = self.third_as_ref().describe(graph);
+        Subgraph::then(graph, second, &[third])
+    }
+}
+
+impl<A, B, C> Describe for Tee<A, B, C>
+where
+    A: Describe,
+    B: Describe,
+    C: Describe,
+{
+    fn describe(&self, graph: &mut Graph) -> Subgraph {
+        let first = self.first_as_ref().describe(graph);
+        let second = self.second_as_ref().describe(graph);
+        let side = self.side_as_ref().describe(graph);
+        graph.connect(&first, &side);
+        // the response of the side service is discarded
+        Subgraph::then(graph, first, &[second])
+    }
+}
+
+impl<A, B, C> Describe for Join<A, B, C>
+where
+    A: Describe,
+    B: Describe,
+    C: Describe,
+{
+    fn describe(&self, graph: &mut Graph) -> Subgraph {
+        let first = self.first_as_ref().describe(graph);
+        let second = self.second_as_ref().describe(graph);
+        let consumer = self.consumer_as_ref().describe(graph);
+        graph.connect(&first, &consumer);
+        graph.connect(&second, &consumer);
+        let entries = first.entries.into_iter().chain(second.entries).collect();
+        Subgraph::new(entries, consumer.exits)
+    }
+}
+
+impl<A, P, T, F> Describe for Branch<A, P, T, F>
+where
+    A: Describe,
+    T: Describe,
+    F: Describe,
+{
+    fn describe(&self, graph: &mut Graph) -> Subgraph {
+        let first = self.first_as_ref().describe(graph);
+        let if_true = self.if_true_as_ref().describe(graph);
+        let if_false = self.if_false_as_ref().describe(graph);
+        Subgraph::then(graph, first, &[if_true, if_false])
+    }
+}
+
+impl<A, B, F> Describe for ScatterGather<A, B, F>
+where
+    A: Describe,
+    B: Describe,
+{
+    fn describe(&self, graph: &mut Graph) -> Subgraph {
+        let first = self.first_as_ref().describe(graph);
+        let seconds = describe_all(self.seconds_as_ref(), graph);
+        Subgraph::then(graph, first, &seconds)
+    }
+}
+
+impl<A, P, B> Describe for Steer<A, P, B>
+where
+    A: Describe,
+    B: Describe,
+{
+    fn describe(&self, graph: &mut Graph) -> Subgraph {
+        let first = self.first_as_ref().describe(graph);
+        let seconds = describe_all(self.seconds_as_ref(), graph);
+        Subgraph::then(graph, first, &seconds)
+    }
+}
+
+impl<A, L, R> Describe for Unzip<A, L, R>
+where
+    A: Describe,
+    L: Describe,
+    R: Describe,
+{
+    fn describe(&self, graph: &mut Graph) -> Subgraph {
+        let first = self.first_as_ref().describe(graph);
+        let left = self.left_as_ref().describe(graph);
+        let right = self.right_as_ref().describe(graph);
+        Subgraph::then(graph, first, &[left, right])
+    }
+}
+
+impl<S, F> Describe for Fallback<S, F>
+where
+    S: Describe,
+    F: Describe,
+{
+    fn describe(&self, graph: &mut Graph) -> Subgraph {
+        // the fallback receives the same requests as the service it backs up
+        let inner = self.get_ref().describe(graph);
+        let fallback = self.fallback_as_ref().describe(graph);
+        Subgraph::new(
+            inner.entries.into_iter().chain(fallback.entries).collect(),
+            inner.exits.into_iter().chain(fallback.exits).collect(),
+        )
+    }
+}
+
+impl<S, H> Describe for OrElse<S, H>
+where
+    S: Describe,
+    H: Describe,
+{
+    fn describe(&self, graph: &mut Graph) -> Subgraph {
+        // the handler receives the errors of the service it wraps
+        let inner = self.get_ref().describe(graph);
+        let handler = self.handler_as_ref().describe(graph);
+        graph.connect(&inner, &handler);
+        Subgraph::new(
+            inner.entries,
+            inner.exits.into_iter().chain(handler.exits).collect(),
+        )
+    }
+}
+
+fn describe_all<S>(services: &[S], graph: &mut Graph) -> Vec<Subgraph>
+where
+    S: Describe,
+{
+    services
+        .iter()
+        .map(|service| service.describe(graph))
+        .collect()
+}
+
+macro_rules! transparent {
+    ($($(#[$m:meta])* $ty:ident<S $(, $param:ident)*>),* $(,)?) => {
+        $(
+            $(#[$m])*
+            impl<S $(, $param)*> Describe for crate::$ty<S $(, $param)*>
+            where
+                S: Describe,
+            {
+                fn describe(&self, graph: &mut Graph) -> Subgraph {
+                    self.get_ref().describe(graph)
+                }
+            }
+        )*
+    };
+}
+
+transparent! {
+    AsyncFilter<S, P>,
+    Cached<S, C>,
+    Inspect<S, F>,
+    LoadShed<S>,
+    MapErr<S, F>,
+    Retry<S, P>,
+    #[cfg(feature = "std")]
+    CircuitBreaker<S>,
+    #[cfg(feature = "std")]
+    ConcurrencyLimit<S>,
+    #[cfg(feature = "std")]
+    Memoize<S, K, V>,
+    #[cfg(feature = "std")]
+    Pausable<S>,
+    #[cfg(feature = "std")]
+    Record<S, St>,
+    #[cfg(feature = "std")]
+    Singleflight<S, F, K, V>,
+    #[cfg(feature = "tokio")]
+    Hedge<S>,
+    #[cfg(feature = "tokio")]
+    PropagateDeadline<S>,
+    #[cfg(feature = "tokio")]
+    RateLimit<S>,
+    #[cfg(feature = "tokio")]
+    SpawnReady<S>,
+    #[cfg(feature = "tokio")]
+    Timeout<S>,
+    #[cfg(feature = "tracing")]
+    LogIntermediate<S>,
+}
@@ -0,0 +1,325 @@
+use crate::BoxError;
+use alloc::{boxed::Box, vec::Vec};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    error::Error,
+    fmt,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+};
+use tower_service::Service;
+
+/// A service that can be drained for graceful shutdown.
+///
+/// Once [`DrainHandle::drain`] has been called `poll_ready` fails with a [`Draining`] error, so
+/// no new calls are accepted, while calls already in flight are allowed to complete. The
+/// future returned by [`DrainHandle::drain`] resolves once there are no calls in flight.
+///
+/// A call counts as in flight from the moment `poll_ready` succeeds, so a call that was
+/// admitted just before draining started is still waited for.
+///
+/// Created with [`Pipeline::drainable`] or [`Drain::new`].
+///
+/// ```
+/// use tower_pipeline::{Draining, PipelineExt};
+/// use tower::{service_fn, BoxError, Service, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let (mut combined, handle) = length_svc.pipeline(double_svc).drainable();
+///
+/// let in_flight = combined.ready().await.unwrap().call("rust");
+///
+/// let drained = handle.drain();
+///
+/// // no new calls are accepted
+/// let err = combined.ready().await.unwrap_err();
+/// assert!(err.is::<Draining>());
+///
+/// // but calls in flight complete
+/// assert_eq!(in_flight.await.unwrap(), 8);
+///
+/// drained.await;
+/// # }
+/// ```
+///
+/// [`Pipeline::drainable`]: crate::Pipeline::drainable
+#[derive(Debug)]
+pub struct Drain<S> {
+    inner: S,
+    shared: Arc<Shared>,
+    reserved: Option<InFlight>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    draining: AtomicBool,
+    in_flight: AtomicUsize,
+    idle_wakers: Mutex<Vec<Waker>>,
+}
+
+impl Shared {
+    fn is_idle(&self) -> bool {
+        self.in_flight.load(Ordering::SeqCst) == 0
+    }
+
+    fn wake_idle(&self) {
+        for waker in self.idle_wakers.lock().unwrap().drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+impl<S> Drain<S> {
+    /// Create a new [`Drain`] along with a [`DrainHandle`] for draining it.
+    pub fn new(inner: S) -> (Self, DrainHandle) {
+        let shared = Arc::new(Shared::default());
+        let handle = DrainHandle {
+            shared: Arc::clone(&shared),
+        };
+        let drain = Self {
+            inner,
+            shared,
+            reserved: None,
+        };
+        (drain, handle)
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Clone for Drain<S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        // the reservation isn't cloned since the clone hasn't been polled
+        Self {
+            inner: self.inner.clone(),
+            shared: Arc::clone(&self.shared),
+            reserved: None,
+        }
+    }
+}
+
+impl<R, S> Service<R> for Drain<S>
+where
+    S: Service<R>,
+    S::Error: Into<BoxError>,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = DrainFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let reserved = match self.reserved.take() {
+            Some(reserved) => reserved,
+            None => {
+                // reserve before checking the flag so `Drained` either sees the reservation or
+                // we see that draining has started
+                let reserved = InFlight::new(&self.shared);
+                if self.shared.draining.load(Ordering::SeqCst) {
+                    return Poll::Ready(Err(Box::new(Draining(()))));
+                }
+                reserved
+            }
+        };
+
+        // the reservation is dropped unless the inner service is ready
+        let result = ready!(self.inner.poll_ready(cx)).map_err(Into::into);
+        if result.is_ok() {
+            self.reserved = Some(reserved);
+        }
+        Poll::Ready(result)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        let guard = self
+            .reserved
+            .take()
+            .unwrap_or_else(|| InFlight::new(&self.shared));
+        DrainFuture {
+            future: self.inner.call(req),
+            _guard: guard,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Drain`].
+    #[derive(Debug)]
+    pub struct DrainFuture<F> {
+        #[pin]
+        future: F,
+        _guard: InFlight,
+    }
+}
+
+impl<F, T, E> Future for DrainFuture<F>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Into<BoxError>,
+{
+    type Output = Result<T, BoxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().future.poll(cx).map_err(Into::into)
+    }
+}
+
+/// Decrements the number of calls in flight when dropped, so calls are accounted for even if
+/// their response future is dropped early, or the service is dropped after `poll_ready` without
+/// being called.
+#[derive(Debug)]
+struct InFlight {
+    shared: Arc<Shared>,
+}
+
+impl InFlight {
+    fn new(shared: &Arc<Shared>) -> Self {
+        shared.in_flight.fetch_add(1, Ordering::SeqCst);
+        Self {
+            shared: Arc::clone(shared),
+        }
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.shared.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.shared.wake_idle();
+        }
+    }
+}
+
+/// Handle for draining a [`Drain`].
+#[derive(Debug, Clone)]
+pub struct DrainHandle {
+    shared: Arc<Shared>,
+}
+
+impl DrainHandle {
+    /// Stop accepting new calls.
+    ///
+    /// The returned future resolves once all calls in flight have completed, including calls
+    /// whose `poll_ready` succeeded before draining started:
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError, Service, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let (mut combined, handle) = length_svc.pipeline(double_svc).drainable();
+    ///
+    /// // the call is admitted before draining starts, but made after
+    /// combined.ready().await.unwrap();
+    /// assert_eq!(handle.in_flight(), 1);
+    ///
+    /// let mut drained = handle.drain();
+    /// let in_flight = combined.call("rust");
+    ///
+    /// assert!(futures::poll!(&mut drained).is_pending());
+    ///
+    /// assert_eq!(in_flight.await.unwrap(), 8);
+    /// drained.await;
+    /// # }
+    /// ```
+    pub fn drain(&self) -> Drained {
+        self.shared.draining.store(true, Ordering::SeqCst);
+        Drained {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    /// Returns `true` if the service is being drained.
+    pub fn is_draining(&self) -> bool {
+        self.shared.draining.load(Ordering::SeqCst)
+    }
+
+    /// The number of calls currently in flight.
+    ///
+    /// This includes calls that have been admitted by `poll_ready` but not made yet.
+    pub fn in_flight(&self) -> usize {
+        self.shared.in_flight.load(Ordering::SeqCst)
+    }
+}
+
+/// Future that resolves once a [`Drain`] has no calls in flight.
+///
+/// Created with [`DrainHandle::drain`].
+#[derive(Debug)]
+pub struct Drained {
+    shared: Arc<Shared>,
+}
+
+impl Future for Drained {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.shared.is_idle() {
+            return Poll::Ready(());
+        }
+
+        {
+            let mut idle_wakers = self.shared.idle_wakers.lock().unwrap();
+            if !idle_wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                idle_wakers.push(cx.waker().clone());
+            }
+        }
+
+        // a call might have completed before the waker was registered
+        if self.shared.is_idle() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Error produced by a [`Drain`] that is no longer accepting calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Draining(());
+
+impl fmt::Display for Draining {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("service is draining and not accepting new calls")
+    }
+}
+
+impl Error for Draining {}
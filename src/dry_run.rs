@@ -0,0 +1,78 @@
+use crate::Pipeline;
+use core::task::{Context, Poll};
+use tower_service::Service;
+
+/// A [`Pipeline`] where only the first service is called, responding with the intermediate
+/// value.
+///
+/// The second service is never polled or called, which is useful for shadow testing a new first
+/// stage before enabling the whole pipeline.
+///
+/// Created with [`Pipeline::dry_run`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let unfinished_svc = service_fn(|_: usize| async move {
+///     Err::<usize, BoxError>(BoxError::from("not ready for production"))
+/// });
+///
+/// let dry_run = length_svc.pipeline(unfinished_svc).dry_run();
+///
+/// let result = dry_run.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 4);
+/// # }
+/// ```
+///
+/// [`Pipeline::dry_run`]: crate::Pipeline::dry_run
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DryRun<A, B> {
+    inner: Pipeline<A, B>,
+}
+
+impl<A, B> DryRun<A, B> {
+    /// Create a new [`DryRun`] of a [`Pipeline`].
+    pub fn new(inner: Pipeline<A, B>) -> Self {
+        Self { inner }
+    }
+
+    /// Get a reference to the pipeline.
+    pub fn get_ref(&self) -> &Pipeline<A, B> {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the pipeline.
+    pub fn get_mut(&mut self) -> &mut Pipeline<A, B> {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the pipeline.
+    pub fn into_inner(self) -> Pipeline<A, B> {
+        self.inner
+    }
+}
+
+impl<R, A, B> Service<R> for DryRun<A, B>
+where
+    A: Service<R>,
+{
+    type Response = A::Response;
+    type Error = A::Error;
+    type Future = A::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.first_as_mut().poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        self.inner.first_as_mut().call(req)
+    }
+}
@@ -0,0 +1,179 @@
+use crate::BoxClonePipeline;
+use alloc::{vec, vec::Vec};
+use core::future::Future;
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::{future::BoxFuture, ready};
+use tower_service::Service;
+
+/// A pipeline of any number of stages, determined at runtime.
+///
+/// Every stage has the same request and response type, and the response of each stage is the
+/// request of the next. A [`DynPipeline`] without any stages responds with the request.
+///
+/// ```
+/// use tower_pipeline::{BoxClonePipeline, DynPipeline};
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let increment_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input + 1)
+/// });
+///
+/// // the stages could for example come from configuration
+/// let stages = vec![
+///     BoxClonePipeline::new(double_svc),
+///     BoxClonePipeline::new(increment_svc),
+///     BoxClonePipeline::new(double_svc),
+/// ];
+///
+/// let combined = DynPipeline::from_stages(stages);
+///
+/// let result = combined.oneshot(1).await.unwrap();
+///
+/// assert_eq!(result, 6);
+/// # }
+/// ```
+pub struct DynPipeline<T, E> {
+    stages: Vec<BoxClonePipeline<T, T, E>>,
+}
+
+impl<T, E> DynPipeline<T, E> {
+    /// Create a new [`DynPipeline`] from a list of stages.
+    pub fn from_stages(stages: Vec<BoxClonePipeline<T, T, E>>) -> Self {
+        Self { stages }
+    }
+
+    /// Add a stage to the end of the pipeline.
+    pub fn push(&mut self, stage: BoxClonePipeline<T, T, E>) {
+        self.stages.push(stage);
+    }
+
+    /// The number of stages in the pipeline.
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Returns `true` if the pipeline has no stages.
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Consume `self`, returning the stages.
+    pub fn into_stages(self) -> Vec<BoxClonePipeline<T, T, E>> {
+        self.stages
+    }
+}
+
+impl<T, E> Clone for DynPipeline<T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            stages: self.stages.clone(),
+        }
+    }
+}
+
+impl<T, E> Default for DynPipeline<T, E> {
+    fn default() -> Self {
+        Self { stages: Vec::new() }
+    }
+}
+
+impl<T, E> fmt::Debug for DynPipeline<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynPipeline")
+            .field("stages", &self.stages.len())
+            .finish()
+    }
+}
+
+impl<T, E> Service<T> for DynPipeline<T, E> {
+    type Response = T;
+    type Error = E;
+    type Future = DynPipelineFuture<T, E>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self.stages.first_mut() {
+            Some(first) => first.poll_ready(cx),
+            None => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        let (state, rest) = match self.stages.split_first_mut() {
+            Some((first, rest)) => (State::Pending(first.call(req)), rest.to_vec()),
+            None => (State::Done(Some(req)), Vec::new()),
+        };
+
+        DynPipelineFuture {
+            state,
+            stages: rest.into_iter(),
+        }
+    }
+}
+
+/// Response future of [`DynPipeline`].
+pub struct DynPipelineFuture<T, E> {
+    state: State<T, E>,
+    stages: vec::IntoIter<BoxClonePipeline<T, T, E>>,
+}
+
+enum State<T, E> {
+    Pending(BoxFuture<'static, Result<T, E>>),
+    PollReady {
+        stage: BoxClonePipeline<T, T, E>,
+        req: Option<T>,
+    },
+    Done(Option<T>),
+}
+
+// the only thing that requires pinning is the boxed response future
+impl<T, E> Unpin for DynPipelineFuture<T, E> {}
+
+impl<T, E> Future for DynPipelineFuture<T, E> {
+    type Output = Result<T, E>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+
+        loop {
+            let new_state = match &mut this.state {
+                State::Pending(future) => {
+                    let res = ready!(future.as_mut().poll(cx)?);
+                    match this.stages.next() {
+                        Some(stage) => State::PollReady {
+                            stage,
+                            req: Some(res),
+                        },
+                        None => return Poll::Ready(Ok(res)),
+                    }
+                }
+
+                State::PollReady { stage, req } => {
+                    let _ready: () = ready!(stage.poll_ready(cx)?);
+                    State::Pending(stage.call(req.take().unwrap()))
+                }
+
+                State::Done(req) => return Poll::Ready(Ok(req.take().unwrap())),
+            };
+
+            this.state = new_state;
+        }
+    }
+}
+
+impl<T, E> fmt::Debug for DynPipelineFuture<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynPipelineFuture")
+            .field("remaining_stages", &self.stages.len())
+            .finish()
+    }
+}
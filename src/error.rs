@@ -0,0 +1,180 @@
+use alloc::borrow::Cow;
+#[cfg(not(feature = "std"))]
+use core::error::Error;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+/// Error produced by a pipeline that keeps track of which stage failed.
+///
+/// Used by [`SplitErrors`](crate::SplitErrors), [`CarryIntermediate`](crate::CarryIntermediate),
+/// and [`Validate`](crate::Validate).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PipelineError<E1, E2> {
+    /// The first service failed.
+    First(E1),
+    /// The second service failed.
+    Second(E2),
+    /// The response of the first service was rejected before it was passed to the second.
+    Invalid(ValidationError),
+}
+
+impl<E1, E2> PipelineError<E1, E2> {
+    /// Returns `true` if the first service failed.
+    pub fn is_first(&self) -> bool {
+        matches!(self, PipelineError::First(_))
+    }
+
+    /// Returns `true` if the second service failed.
+    pub fn is_second(&self) -> bool {
+        matches!(self, PipelineError::Second(_))
+    }
+
+    /// Returns `true` if the response of the first service was rejected.
+    pub fn is_invalid(&self) -> bool {
+        matches!(self, PipelineError::Invalid(_))
+    }
+
+    /// Convert into the error of the first service, if that is what failed.
+    pub fn into_first(self) -> Option<E1> {
+        match self {
+            PipelineError::First(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Convert into the error of the second service, if that is what failed.
+    pub fn into_second(self) -> Option<E2> {
+        match self {
+            PipelineError::Second(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Convert into the validation error, if the response of the first service was rejected.
+    pub fn into_invalid(self) -> Option<ValidationError> {
+        match self {
+            PipelineError::Invalid(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl<E1, E2> fmt::Display for PipelineError<E1, E2>
+where
+    E1: fmt::Display,
+    E2: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipelineError::First(err) => write!(f, "first service failed: {}", err),
+            PipelineError::Second(err) => write!(f, "second service failed: {}", err),
+            PipelineError::Invalid(err) => write!(f, "intermediate value is invalid: {}", err),
+        }
+    }
+}
+
+impl<E1, E2> Error for PipelineError<E1, E2>
+where
+    E1: Error + 'static,
+    E2: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PipelineError::First(err) => Some(err),
+            PipelineError::Second(err) => Some(err),
+            PipelineError::Invalid(err) => Some(err),
+        }
+    }
+}
+
+/// Error produced by the second service, along with the intermediate value it was called with.
+///
+/// Used by [`CarryIntermediate`](crate::CarryIntermediate).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SecondError<T, E> {
+    /// The error of the second service.
+    pub error: E,
+    /// The response of the first service the second service was called with.
+    pub intermediate: T,
+}
+
+impl<T, E> SecondError<T, E> {
+    /// Create a new [`SecondError`].
+    pub fn new(error: E, intermediate: T) -> Self {
+        Self {
+            error,
+            intermediate,
+        }
+    }
+
+    /// Consume `self`, returning the error and the intermediate value.
+    pub fn into_parts(self) -> (E, T) {
+        (self.error, self.intermediate)
+    }
+}
+
+impl<T, E> fmt::Display for SecondError<T, E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl<T, E> Error for SecondError<T, E>
+where
+    T: fmt::Debug,
+    E: Error,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
+
+/// Error returned when a value is rejected by a validator.
+///
+/// Used by [`Validate`](crate::Validate).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ValidationError {
+    message: Cow<'static, str>,
+    field: Option<&'static str>,
+}
+
+impl ValidationError {
+    /// Create a new [`ValidationError`] with a message that says why the value was rejected.
+    pub fn new(message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            message: message.into(),
+            field: None,
+        }
+    }
+
+    /// Set the field of the value that was rejected.
+    pub fn with_field(mut self, field: &'static str) -> Self {
+        self.field = Some(field);
+        self
+    }
+
+    /// The message that says why the value was rejected.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The field of the value that was rejected, if set.
+    pub fn field(&self) -> Option<&'static str> {
+        self.field
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.field {
+            Some(field) => write!(f, "`{}` {}", field, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl Error for ValidationError {}
@@ -0,0 +1,196 @@
+use crate::SharedService;
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+/// Two services combined where the response of the first is the request of the second, without
+/// requiring the second service to implement [`Clone`].
+///
+/// The second service is shared between all response futures behind a lock rather than cloned
+/// for every request. The lock is only held while polling the second service for readiness and
+/// calling it, never while its response future runs.
+///
+/// Created with [`Pipeline::exclusive`].
+///
+/// ```
+/// use tower_pipeline::Pipeline;
+/// use tower::{service_fn, BoxError, ServiceExt, Service};
+/// use std::task::{Context, Poll};
+/// use futures_util::future::{ready, Ready};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// // a service that doesn't implement `Clone`
+/// struct Counter {
+///     count: usize,
+/// }
+///
+/// impl Service<usize> for Counter {
+///     type Response = usize;
+///     type Error = BoxError;
+///     type Future = Ready<Result<usize, BoxError>>;
+///
+///     fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), BoxError>> {
+///         Poll::Ready(Ok(()))
+///     }
+///
+///     fn call(&mut self, req: usize) -> Self::Future {
+///         self.count += req;
+///         ready(Ok(self.count))
+///     }
+/// }
+///
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let mut combined = Pipeline::new(length_svc, Counter { count: 0 }).exclusive();
+///
+/// assert_eq!(combined.ready().await.unwrap().call("rust").await.unwrap(), 4);
+/// assert_eq!(combined.ready().await.unwrap().call("rust").await.unwrap(), 8);
+/// # }
+/// ```
+///
+/// [`Pipeline::exclusive`]: crate::Pipeline::exclusive
+#[derive(Debug)]
+pub struct Exclusive<A, B> {
+    first: A,
+    second: SharedService<B>,
+}
+
+impl<A, B> Exclusive<A, B> {
+    /// Create a new [`Exclusive`] from two [`Service`]s.
+    pub fn new(first: A, second: B) -> Self {
+        Self::from_shared(first, SharedService::new(second))
+    }
+
+    /// Create a new [`Exclusive`] from a service and a [`SharedService`].
+    ///
+    /// Other handles to the second service can still be used to call it directly.
+    pub fn from_shared(first: A, second: SharedService<B>) -> Self {
+        Self { first, second }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a handle to the second service.
+    pub fn second_as_shared(&self) -> &SharedService<B> {
+        &self.second
+    }
+}
+
+impl<A, B> Clone for Exclusive<A, B>
+where
+    A: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            first: self.first.clone(),
+            second: self.second.clone(),
+        }
+    }
+}
+
+impl<R, A, B> Service<R> for Exclusive<A, B>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+    A::Error: Into<B::Error>,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = ExclusiveFuture<R, A, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        ExclusiveFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            second: self.second.clone(),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Exclusive`].
+    pub struct ExclusiveFuture<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        #[pin]
+        state: State<R, A, B>,
+        second: SharedService<B>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        PollReadySecond { first_res: Option<A::Response> },
+        SecondFuturePending { #[pin] future: B::Future },
+    }
+}
+
+impl<R, A, B> Future for ExclusiveFuture<R, A, B>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+    A::Error: Into<B::Error>,
+{
+    type Output = Result<B::Response, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    State::PollReadySecond {
+                        first_res: Some(first_res),
+                    }
+                }
+
+                StateProj::PollReadySecond { first_res } => {
+                    let mut second = this.second.lock();
+                    let _ready: () = ready!(second.poll_ready(cx)?);
+                    State::SecondFuturePending {
+                        future: second.call(first_res.take().unwrap()),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
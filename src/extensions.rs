@@ -0,0 +1,278 @@
+use crate::oneshot::Oneshot;
+use alloc::boxed::Box;
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+/// A typed map of request scoped values, shared by every stage of a call.
+///
+/// Cloning an [`Extensions`] is cheap and the clone refers to the same map, so values inserted by
+/// one stage can be read by the next stage and by the caller once the call has completed.
+///
+/// At most one value of each type is stored.
+#[derive(Clone, Default)]
+pub struct Extensions {
+    map: Arc<Mutex<HashMap<TypeId, Box<dyn Any + Send + Sync>>>>,
+}
+
+impl Extensions {
+    /// Create an empty [`Extensions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a value, returning the previous value of the same type, if any.
+    pub fn insert<T>(&self, value: T) -> Option<T>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.map
+            .lock()
+            .unwrap()
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|prev| prev.downcast().ok().map(|prev| *prev))
+    }
+
+    /// Get a clone of the value of type `T`, if any.
+    pub fn get<T>(&self) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.map
+            .lock()
+            .unwrap()
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+    }
+
+    /// Remove the value of type `T`, if any.
+    pub fn remove<T>(&self) -> Option<T>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.map
+            .lock()
+            .unwrap()
+            .remove(&TypeId::of::<T>())
+            .and_then(|value| value.downcast().ok().map(|value| *value))
+    }
+
+    /// Returns `true` if there is a value of type `T`.
+    pub fn contains<T>(&self) -> bool
+    where
+        T: Send + Sync + 'static,
+    {
+        self.map.lock().unwrap().contains_key(&TypeId::of::<T>())
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.map.lock().unwrap().len())
+            .finish()
+    }
+}
+
+/// A request along with the [`Extensions`] of the call it is part of.
+///
+/// This is the request type of both stages of a [`WithExtensions`].
+#[derive(Debug, Clone, Default)]
+pub struct Scoped<T> {
+    /// The request.
+    pub request: T,
+    /// The extensions of the call.
+    pub extensions: Extensions,
+}
+
+impl<T> Scoped<T> {
+    /// Create a new [`Scoped`] request.
+    pub fn new(request: T, extensions: Extensions) -> Self {
+        Self {
+            request,
+            extensions,
+        }
+    }
+
+    /// Consume `self`, returning the request and the extensions.
+    pub fn into_parts(self) -> (T, Extensions) {
+        (self.request, self.extensions)
+    }
+}
+
+/// Two services combined where both receive the [`Extensions`] of the call along with their
+/// request.
+///
+/// The request is a [`Scoped`] request. The response of the first service is wrapped in a
+/// [`Scoped`] with the same extensions before being passed to the second.
+///
+/// Created with [`Pipeline::with_extensions`].
+///
+/// ```
+/// use tower_pipeline::{Extensions, Pipeline, Scoped};
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// #[derive(Clone)]
+/// struct RequestId(u64);
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|req: Scoped<&'static str>| async move {
+///     req.extensions.insert(req.request.len());
+///     Ok::<_, BoxError>(req.request.len())
+/// });
+///
+/// let describe_svc = service_fn(|req: Scoped<usize>| async move {
+///     let RequestId(id) = req.extensions.get::<RequestId>().unwrap();
+///     Ok::<_, BoxError>(format!("request {} has length {}", id, req.request))
+/// });
+///
+/// let combined = Pipeline::new(length_svc, describe_svc).with_extensions();
+///
+/// let extensions = Extensions::new();
+/// extensions.insert(RequestId(1));
+///
+/// let result = combined
+///     .oneshot(Scoped::new("rust", extensions.clone()))
+///     .await
+///     .unwrap();
+///
+/// assert_eq!(result, "request 1 has length 4");
+/// assert_eq!(extensions.get::<usize>(), Some(4));
+/// # }
+/// ```
+///
+/// [`Pipeline::with_extensions`]: crate::Pipeline::with_extensions
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WithExtensions<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> WithExtensions<A, B> {
+    /// Create a new [`WithExtensions`] from two [`Service`]s.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, A, B> Service<Scoped<R>> for WithExtensions<A, B>
+where
+    A: Service<Scoped<R>>,
+    B: Service<Scoped<A::Response>> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = WithExtensionsFuture<R, A, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Scoped<R>) -> Self::Future {
+        let extensions = req.extensions.clone();
+        WithExtensionsFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            extensions: Some(extensions),
+            second: Some(self.second.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`WithExtensions`].
+    pub struct WithExtensionsFuture<R, A, B>
+    where
+        A: Service<Scoped<R>>,
+        B: Service<Scoped<A::Response>>,
+    {
+        #[pin]
+        state: State<R, A, B>,
+        extensions: Option<Extensions>,
+        second: Option<B>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<Scoped<R>>,
+        B: Service<Scoped<A::Response>>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturePending { #[pin] future: Oneshot<B, Scoped<A::Response>> },
+    }
+}
+
+impl<R, A, B> Future for WithExtensionsFuture<R, A, B>
+where
+    A: Service<Scoped<R>>,
+    B: Service<Scoped<A::Response>>,
+    A::Error: Into<B::Error>,
+{
+    type Output = Result<B::Response, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    let req = Scoped::new(first_res, this.extensions.take().unwrap());
+                    State::SecondFuturePending {
+                        future: Oneshot::new(this.second.take().unwrap(), req),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// A service that calls a fallback service with the same request if the inner service fails.
+///
+/// Only the inner service is polled in `poll_ready`. The fallback service is driven to readiness
+/// inside the response future, if it is needed.
+///
+/// Usually created with [`Pipeline::with_fallback`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let cache_svc = service_fn(|_: usize| async move {
+///     Err::<String, BoxError>(BoxError::from("cache miss"))
+/// });
+///
+/// let backend_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(format!("{} from backend", input))
+/// });
+///
+/// let combined = length_svc.pipeline(cache_svc).with_fallback(backend_svc);
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, "4 from backend");
+/// # }
+/// ```
+///
+/// [`Pipeline::with_fallback`]: crate::Pipeline::with_fallback
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fallback<S, F> {
+    inner: S,
+    fallback: F,
+}
+
+impl<S, F> Fallback<S, F> {
+    /// Create a new [`Fallback`].
+    pub fn new(inner: S, fallback: F) -> Self {
+        Self { inner, fallback }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Get a reference to the fallback service.
+    pub fn fallback_as_ref(&self) -> &F {
+        &self.fallback
+    }
+
+    /// Get a mutable reference to the fallback service.
+    pub fn fallback_as_mut(&mut self) -> &mut F {
+        &mut self.fallback
+    }
+}
+
+impl<T, S, F> Service<T> for Fallback<S, F>
+where
+    T: Clone,
+    S: Service<T>,
+    F: Service<T, Response = S::Response, Error = S::Error> + Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = FallbackFuture<T, S, F>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        FallbackFuture {
+            state: State::InnerPending {
+                future: self.inner.call(req.clone()),
+            },
+            req: Some(req),
+            fallback: Some(self.fallback.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Fallback`].
+    pub struct FallbackFuture<T, S, F>
+    where
+        S: Service<T>,
+        F: Service<T>,
+    {
+        #[pin]
+        state: State<T, S, F>,
+        req: Option<T>,
+        fallback: Option<F>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<T, S, F>
+    where
+        S: Service<T>,
+        F: Service<T>,
+    {
+        InnerPending { #[pin] future: S::Future },
+        FallbackPending { #[pin] future: Oneshot<F, T> },
+    }
+}
+
+impl<T, S, F> Future for FallbackFuture<T, S, F>
+where
+    S: Service<T>,
+    F: Service<T, Response = S::Response, Error = S::Error>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::InnerPending { future } => match ready!(future.poll(cx)) {
+                    Ok(res) => return Poll::Ready(Ok(res)),
+                    Err(_) => State::FallbackPending {
+                        future: Oneshot::new(
+                            this.fallback.take().unwrap(),
+                            this.req.take().unwrap(),
+                        ),
+                    },
+                },
+
+                StateProj::FallbackPending { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
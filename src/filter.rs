@@ -0,0 +1,157 @@
+use core::future::Future;
+use core::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// A service that checks requests with an async predicate before calling the inner service.
+///
+/// The predicate receives the request and resolves to either the request, possibly modified, or
+/// an error. If it resolves to an error the inner service is not called and the error is
+/// returned instead.
+///
+/// Usually created with [`Pipeline::filter`], in which case the predicate checks the
+/// intermediate value before it reaches the second service.
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let combined = length_svc.pipeline(double_svc).filter(|len: usize| async move {
+///     if len > 0 {
+///         Ok(len)
+///     } else {
+///         Err(BoxError::from("empty input"))
+///     }
+/// });
+///
+/// assert_eq!(combined.clone().oneshot("rust").await.unwrap(), 8);
+/// assert!(combined.oneshot("").await.is_err());
+/// # }
+/// ```
+///
+/// [`Pipeline::filter`]: crate::Pipeline::filter
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsyncFilter<S, P> {
+    inner: S,
+    predicate: P,
+}
+
+impl<S, P> AsyncFilter<S, P> {
+    /// Create a new [`AsyncFilter`].
+    pub fn new(inner: S, predicate: P) -> Self {
+        Self { inner, predicate }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<T, S, P, Fut, E> Service<T> for AsyncFilter<S, P>
+where
+    S: Service<T> + Clone,
+    P: FnMut(T) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Into<S::Error>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = AsyncFilterFuture<T, S, Fut>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        // the inner service is called once the predicate is done, so take the service that was
+        // driven to readiness and leave a clone in its place
+        let clone = self.inner.clone();
+        let inner = core::mem::replace(&mut self.inner, clone);
+
+        AsyncFilterFuture {
+            state: State::Checking {
+                future: (self.predicate)(req),
+            },
+            inner: Some(inner),
+            _marker: PhantomData,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`AsyncFilter`].
+    pub struct AsyncFilterFuture<T, S, Fut>
+    where
+        S: Service<T>,
+    {
+        #[pin]
+        state: State<T, S, Fut>,
+        inner: Option<S>,
+        _marker: PhantomData<fn(T)>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<T, S, Fut>
+    where
+        S: Service<T>,
+    {
+        Checking { #[pin] future: Fut },
+        Called { #[pin] future: S::Future },
+    }
+}
+
+impl<T, S, Fut, E> Future for AsyncFilterFuture<T, S, Fut>
+where
+    S: Service<T>,
+    Fut: Future<Output = Result<T, E>>,
+    E: Into<S::Error>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::Checking { future } => {
+                    let req = ready!(future.poll(cx).map_err(Into::into)?);
+                    State::Called {
+                        future: this.inner.take().unwrap().call(req),
+                    }
+                }
+
+                StateProj::Called { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{
+    oneshot::Oneshot,
+    ordering::{InFlight, Ordering},
+};
+use alloc::vec::Vec;
+use core::future::Future;
+use core::{
+    iter::Fuse,
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Two services combined where the response of the first is a collection and the second is
+/// called once for each item.
+///
+/// At most `limit` calls to the second service are in flight at a time. Each call drives its own
+/// clone of the second service to readiness so backpressure from the second service is
+/// respected. The responses are collected into a [`Vec`] in the order set with
+/// [`ForEachConcurrent::with_ordering`], which by default is the same order as the items. The
+/// first error fails the whole call.
+///
+/// A `limit` of zero means no limit.
+///
+/// Created with [`Pipeline::for_each_concurrent`].
+///
+/// ```
+/// use tower_pipeline::Pipeline;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let split_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.split(' ').collect::<Vec<_>>())
+/// });
+///
+/// let length_svc = service_fn(|word: &'static str| async move {
+///     Ok::<_, BoxError>(word.len())
+/// });
+///
+/// let combined = Pipeline::new(split_svc, length_svc).for_each_concurrent(2);
+///
+/// let result = combined.oneshot("tower pipelines are neat").await.unwrap();
+///
+/// assert_eq!(result, vec![5, 9, 3, 4]);
+/// # }
+/// ```
+///
+/// [`Pipeline::for_each_concurrent`]: crate::Pipeline::for_each_concurrent
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ForEachConcurrent<A, B> {
+    first: A,
+    second: B,
+    limit: usize,
+    ordering: Ordering,
+}
+
+impl<A, B> ForEachConcurrent<A, B> {
+    /// Create a new [`ForEachConcurrent`] from two [`Service`]s.
+    pub fn new(first: A, second: B, limit: usize) -> Self {
+        Self {
+            first,
+            second,
+            limit,
+            ordering: Ordering::Preserve,
+        }
+    }
+
+    /// Set the order the responses are collected in.
+    ///
+    /// Defaults to [`Ordering::Preserve`].
+    pub fn with_ordering(mut self, ordering: Ordering) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, A, B> Service<R> for ForEachConcurrent<A, B>
+where
+    A: Service<R>,
+    A::Response: IntoIterator,
+    B: Service<<A::Response as IntoIterator>::Item> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Response = Vec<B::Response>;
+    type Error = B::Error;
+    type Future = ForEachConcurrentFuture<R, A, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        ForEachConcurrentFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            second: self.second.clone(),
+            limit: self.limit,
+            ordering: self.ordering,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`ForEachConcurrent`].
+    pub struct ForEachConcurrentFuture<R, A, B>
+    where
+        A: Service<R>,
+        A::Response: IntoIterator,
+        B: Service<<A::Response as IntoIterator>::Item>,
+    {
+        #[pin]
+        state: State<R, A, B>,
+        second: B,
+        limit: usize,
+        ordering: Ordering,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<R>,
+        A::Response: IntoIterator,
+        B: Service<<A::Response as IntoIterator>::Item>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturesPending {
+            items: Fuse<<A::Response as IntoIterator>::IntoIter>,
+            in_flight: InFlight<Oneshot<B, <A::Response as IntoIterator>::Item>>,
+            responses: Vec<B::Response>,
+        },
+    }
+}
+
+impl<R, A, B> Future for ForEachConcurrentFuture<R, A, B>
+where
+    A: Service<R>,
+    A::Response: IntoIterator,
+    B: Service<<A::Response as IntoIterator>::Item> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Output = Result<Vec<B::Response>, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    let items = first_res.into_iter().fuse();
+                    State::SecondFuturesPending {
+                        responses: Vec::with_capacity(items.size_hint().0),
+                        items,
+                        in_flight: InFlight::new(*this.ordering),
+                    }
+                }
+
+                StateProj::SecondFuturesPending {
+                    items,
+                    in_flight,
+                    responses,
+                } => loop {
+                    while *this.limit == 0 || in_flight.len() < *this.limit {
+                        match items.next() {
+                            Some(item) => in_flight.push(Oneshot::new(this.second.clone(), item)),
+                            None => break,
+                        }
+                    }
+
+                    match ready!(in_flight.poll_next(cx)) {
+                        Some(Ok(response)) => responses.push(response),
+                        Some(Err(err)) => return Poll::Ready(Err(err)),
+                        None => return Poll::Ready(Ok(mem::take(responses))),
+                    }
+                },
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{Ordering, TransformStream};
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::{ready, stream::Stream};
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Two services combined where the response of the first is a stream and the second is called
+/// once for each item.
+///
+/// The response is a [`TransformStream`] of the results of the second service. At most `limit`
+/// calls to the second service are in flight at a time and the second service is driven to
+/// readiness before each call, so backpressure from the second service is respected. The results
+/// are produced in the order set with [`ForEachStream::with_ordering`], which by default is the
+/// same order as the items.
+///
+/// A `limit` of zero means no limit.
+///
+/// Created with [`Pipeline::for_each_stream`].
+///
+/// ```
+/// use tower_pipeline::Pipeline;
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use futures_util::stream::{self, StreamExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let rows_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(stream::iter(input.split(' ')))
+/// });
+///
+/// let length_svc = service_fn(|word: &'static str| async move {
+///     Ok::<_, BoxError>(word.len())
+/// });
+///
+/// let combined = Pipeline::new(rows_svc, length_svc).for_each_stream(2);
+///
+/// let results = combined
+///     .oneshot("tower pipelines are neat")
+///     .await
+///     .unwrap()
+///     .map(Result::unwrap)
+///     .collect::<Vec<_>>()
+///     .await;
+///
+/// assert_eq!(results, vec![5, 9, 3, 4]);
+/// # }
+/// ```
+///
+/// [`Pipeline::for_each_stream`]: crate::Pipeline::for_each_stream
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ForEachStream<A, B> {
+    first: A,
+    second: B,
+    limit: usize,
+    ordering: Ordering,
+}
+
+impl<A, B> ForEachStream<A, B> {
+    /// Create a new [`ForEachStream`] from two [`Service`]s.
+    pub fn new(first: A, second: B, limit: usize) -> Self {
+        Self {
+            first,
+            second,
+            limit,
+            ordering: Ordering::Preserve,
+        }
+    }
+
+    /// Set the order the results are produced in.
+    ///
+    /// Defaults to [`Ordering::Preserve`].
+    ///
+    /// ```
+    /// use tower_pipeline::{Ordering, Pipeline};
+    /// use tower::{service_fn, BoxError, ServiceExt};
+    /// use futures_util::stream::{self, StreamExt};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread", start_paused = true)]
+    /// # async fn main() {
+    /// let numbers_svc = service_fn(|numbers: Vec<u64>| async move {
+    ///     Ok::<_, BoxError>(stream::iter(numbers))
+    /// });
+    ///
+    /// // takes longer the larger the number is
+    /// let sleep_svc = service_fn(|n: u64| async move {
+    ///     tokio::time::sleep(Duration::from_millis(n * 50)).await;
+    ///     Ok::<_, BoxError>(n)
+    /// });
+    ///
+    /// let combined = Pipeline::new(numbers_svc, sleep_svc)
+    ///     .for_each_stream(2)
+    ///     .with_ordering(Ordering::Unordered);
+    ///
+    /// let results = combined
+    ///     .oneshot(vec![4, 1, 2])
+    ///     .await
+    ///     .unwrap()
+    ///     .map(Result::unwrap)
+    ///     .collect::<Vec<_>>()
+    ///     .await;
+    ///
+    /// // `2` is only called once `1` is done, but still completes before `4`
+    /// assert_eq!(results, vec![1, 2, 4]);
+    /// # }
+    /// ```
+    pub fn with_ordering(mut self, ordering: Ordering) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, A, B> Service<R> for ForEachStream<A, B>
+where
+    A: Service<R>,
+    A::Response: Stream,
+    B: Service<<A::Response as Stream>::Item> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Response = TransformStream<A::Response, B>;
+    type Error = B::Error;
+    type Future = ForEachStreamFuture<A::Future, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        ForEachStreamFuture {
+            future: self.first.call(req),
+            second: Some(self.second.clone()),
+            limit: self.limit,
+            ordering: self.ordering,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`ForEachStream`].
+    #[derive(Debug)]
+    pub struct ForEachStreamFuture<F, B> {
+        #[pin]
+        future: F,
+        second: Option<B>,
+        limit: usize,
+        ordering: Ordering,
+    }
+}
+
+impl<F, St, E, B> Future for ForEachStreamFuture<F, B>
+where
+    F: Future<Output = Result<St, E>>,
+    St: Stream,
+    B: Service<St::Item>,
+    E: Into<B::Error>,
+{
+    type Output = Result<TransformStream<St, B>, B::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let items = ready!(this.future.poll(cx).map_err(Into::into)?);
+        let second = this.second.take().unwrap();
+        Poll::Ready(Ok(TransformStream::new(
+            second,
+            items,
+            *this.limit,
+            *this.ordering,
+        )))
+    }
+}
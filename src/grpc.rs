@@ -0,0 +1,183 @@
+//! Utilities for using [`tonic`] clients as stages of a pipeline.
+//!
+//! [`tonic`]: ::tonic
+
+use alloc::boxed::Box;
+use futures_util::future::BoxFuture;
+use std::future::Future;
+use std::{
+    fmt,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tonic::{
+    body::BoxBody,
+    client::{Grpc, GrpcService},
+    codec::Codec,
+    codegen::{http::uri::PathAndQuery, Body, StdError},
+    IntoRequest, Status,
+};
+use tower_service::Service;
+
+pub use tonic::codec::ProstCodec;
+
+/// A service that makes unary gRPC calls to a single method.
+///
+/// This lets a tonic transport, such as a `tonic::transport::Channel`, be the second stage of a
+/// pipeline whose first stage produces typed messages. The request can be a message or a
+/// [`tonic::Request`] of the message, if metadata needs to be set. Encoding the message,
+/// setting the path, and decoding the response is handled internally.
+///
+/// `C` is the [`Codec`] used to encode requests and decode responses, usually
+/// [`ProstCodec`].
+///
+/// ```no_run
+/// use tower_pipeline::{grpc::{ProstCodec, Unary}, PipelineExt};
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use tonic::{codegen::http::uri::PathAndQuery, transport::Channel, Status};
+///
+/// #[derive(Clone, PartialEq, prost::Message)]
+/// struct HelloRequest {
+///     #[prost(string, tag = "1")]
+///     name: String,
+/// }
+///
+/// #[derive(Clone, PartialEq, prost::Message)]
+/// struct HelloReply {
+///     #[prost(string, tag = "1")]
+///     message: String,
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let parse_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, Status>(HelloRequest { name: input.to_string() })
+/// });
+///
+/// let channel = Channel::from_static("http://[::1]:50051").connect_lazy();
+/// let say_hello: Unary<_, ProstCodec<HelloRequest, HelloReply>> = Unary::new(
+///     channel,
+///     PathAndQuery::from_static("/helloworld.Greeter/SayHello"),
+/// );
+///
+/// let combined = parse_svc.pipeline(say_hello);
+///
+/// let reply = combined.oneshot("rust").await.unwrap();
+/// println!("{}", reply.message);
+/// # }
+/// ```
+///
+/// [`tonic::Request`]: ::tonic::Request
+pub struct Unary<T, C> {
+    inner: T,
+    path: PathAndQuery,
+    _codec: PhantomData<fn() -> C>,
+}
+
+impl<T, C> Unary<T, C> {
+    /// Create a new [`Unary`] that calls the method at `path`, for example
+    /// `/helloworld.Greeter/SayHello`.
+    pub fn new(inner: T, path: PathAndQuery) -> Self {
+        Self {
+            inner,
+            path,
+            _codec: PhantomData,
+        }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, C> Clone for Unary<T, C>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            path: self.path.clone(),
+            _codec: PhantomData,
+        }
+    }
+}
+
+impl<T, C> fmt::Debug for Unary<T, C>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Unary")
+            .field("inner", &self.inner)
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl<M, T, C> Service<M> for Unary<T, C>
+where
+    M: IntoRequest<C::Encode>,
+    T: GrpcService<BoxBody> + Clone + Send + 'static,
+    T::Future: Send,
+    T::ResponseBody: Body + Send + 'static,
+    <T::ResponseBody as Body>::Error: Into<StdError>,
+    C: Codec + Default + Send + 'static,
+    C::Encode: Send + Sync + 'static,
+    C::Decode: Send + Sync + 'static,
+{
+    type Response = C::Decode;
+    type Error = Status;
+    type Future = UnaryFuture<C::Decode>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner
+            .poll_ready(cx)
+            .map_err(|err| Status::from_error(err.into()))
+    }
+
+    fn call(&mut self, req: M) -> Self::Future {
+        // the service that was driven to readiness is the one used for the call
+        let clone = self.inner.clone();
+        let inner = std::mem::replace(&mut self.inner, clone);
+        let path = self.path.clone();
+        let req = req.into_request();
+        UnaryFuture {
+            inner: Box::pin(async move {
+                let res = Grpc::new(inner).unary(req, path, C::default()).await?;
+                Ok(res.into_inner())
+            }),
+        }
+    }
+}
+
+/// Response future of [`Unary`].
+pub struct UnaryFuture<M> {
+    inner: BoxFuture<'static, Result<M, Status>>,
+}
+
+impl<M> Future for UnaryFuture<M> {
+    type Output = Result<M, Status>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.inner.as_mut().poll(cx)
+    }
+}
+
+impl<M> fmt::Debug for UnaryFuture<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnaryFuture").finish()
+    }
+}
@@ -0,0 +1,294 @@
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// A list of services where the response of each is the request of the next, built one stage at
+/// a time with [`HChain::push_stage`].
+///
+/// Unlike [`Chain`] the number of stages isn't limited, and unlike nesting [`Pipeline`]s the
+/// bounds of each stage only refer to the stage itself and the rest of the list, so they grow
+/// linearly with the number of stages. The list ends with [`HNil`].
+///
+/// Every service's error must convert into the error of the last service and every service but
+/// the first must implement [`Clone`]. Like [`Chain`] only the first service is driven to
+/// readiness in `poll_ready`, the others are driven to readiness in the response future.
+///
+/// ```
+/// use tower_pipeline::HChain;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let increment_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input + 1)
+/// });
+///
+/// let to_string_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input.to_string())
+/// });
+///
+/// let combined = HChain::new(length_svc)
+///     .push_stage(double_svc)
+///     .push_stage(increment_svc)
+///     .push_stage(to_string_svc);
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, "9");
+/// # }
+/// ```
+///
+/// [`Chain`]: crate::Chain
+/// [`Pipeline`]: crate::Pipeline
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HChain<Head, Tail> {
+    head: Head,
+    tail: Tail,
+}
+
+/// The end of an [`HChain`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HNil;
+
+impl<S> HChain<S, HNil> {
+    /// Create a new [`HChain`] with a single service.
+    pub fn new(service: S) -> Self {
+        Self {
+            head: service,
+            tail: HNil,
+        }
+    }
+}
+
+impl<Head, Tail> HChain<Head, Tail> {
+    /// Add a service to the end of the list, which is called with the response of the current
+    /// last service.
+    pub fn push_stage<S>(self, service: S) -> HChain<Head, Tail::Output>
+    where
+        Tail: Push<S>,
+    {
+        HChain {
+            head: self.head,
+            tail: self.tail.push(service),
+        }
+    }
+
+    /// Get a reference to the first service.
+    pub fn head(&self) -> &Head {
+        &self.head
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn head_mut(&mut self) -> &mut Head {
+        &mut self.head
+    }
+
+    /// Get a reference to the rest of the list.
+    pub fn tail(&self) -> &Tail {
+        &self.tail
+    }
+
+    /// Get a mutable reference to the rest of the list.
+    pub fn tail_mut(&mut self) -> &mut Tail {
+        &mut self.tail
+    }
+
+    /// Consume `self`, returning the first service and the rest of the list.
+    pub fn into_parts(self) -> (Head, Tail) {
+        (self.head, self.tail)
+    }
+}
+
+impl<R, Head, Tail> Service<R> for HChain<Head, Tail>
+where
+    Self: Stages<R>,
+{
+    type Response = <Self as Stages<R>>::Response;
+    type Error = <Self as Stages<R>>::Error;
+    type Future = <Self as Stages<R>>::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Stages::poll_ready(self, cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        Stages::call(self, req)
+    }
+}
+
+mod sealed {
+    use super::*;
+
+    /// A list of services that can be driven by an [`HChain`].
+    pub trait Stages<R> {
+        type Response;
+        type Error;
+        type Future: Future<Output = Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>>;
+
+        fn call(&mut self, req: R) -> Self::Future;
+
+        /// Like `call` but moves the rest of the list into the future rather than cloning it.
+        fn call_owned(self, req: R) -> Self::Future;
+    }
+
+    /// Adding a service to the end of a list.
+    pub trait Push<S> {
+        type Output;
+
+        fn push(self, service: S) -> Self::Output;
+    }
+}
+
+use sealed::{Push, Stages};
+
+impl<S> Push<S> for HNil {
+    type Output = HChain<S, HNil>;
+
+    fn push(self, service: S) -> Self::Output {
+        HChain::new(service)
+    }
+}
+
+impl<S, Head, Tail> Push<S> for HChain<Head, Tail>
+where
+    Tail: Push<S>,
+{
+    type Output = HChain<Head, Tail::Output>;
+
+    fn push(self, service: S) -> Self::Output {
+        self.push_stage(service)
+    }
+}
+
+impl<R, S> Stages<R> for HChain<S, HNil>
+where
+    S: Service<R>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.head.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        self.head.call(req)
+    }
+
+    fn call_owned(mut self, req: R) -> Self::Future {
+        self.head.call(req)
+    }
+}
+
+impl<R, Head, Next, Tail> Stages<R> for HChain<Head, HChain<Next, Tail>>
+where
+    Head: Service<R>,
+    HChain<Next, Tail>: Stages<Head::Response> + Clone,
+    Head::Error: Into<<HChain<Next, Tail> as Stages<Head::Response>>::Error>,
+{
+    type Response = <HChain<Next, Tail> as Stages<Head::Response>>::Response;
+    type Error = <HChain<Next, Tail> as Stages<Head::Response>>::Error;
+    type Future = HChainFuture<R, Head, HChain<Next, Tail>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.head.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        HChainFuture {
+            state: State::HeadPending {
+                future: self.head.call(req),
+                tail: Some(self.tail.clone()),
+            },
+        }
+    }
+
+    fn call_owned(mut self, req: R) -> Self::Future {
+        HChainFuture {
+            state: State::HeadPending {
+                future: self.head.call(req),
+                tail: Some(self.tail),
+            },
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`HChain`].
+    pub struct HChainFuture<R, Head, Tail>
+    where
+        Head: Service<R>,
+        Tail: Stages<Head::Response>,
+    {
+        #[pin]
+        state: State<R, Head, Tail>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, Head, Tail>
+    where
+        Head: Service<R>,
+        Tail: Stages<Head::Response>,
+    {
+        HeadPending { #[pin] future: Head::Future, tail: Option<Tail> },
+        TailReady { req: Option<Head::Response>, tail: Option<Tail> },
+        TailPending { #[pin] future: Tail::Future },
+    }
+}
+
+impl<R, Head, Tail> Future for HChainFuture<R, Head, Tail>
+where
+    Head: Service<R>,
+    Tail: Stages<Head::Response>,
+    Head::Error: Into<Tail::Error>,
+{
+    type Output = Result<Tail::Response, Tail::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::HeadPending { future, tail } => {
+                    let res = ready!(future.poll(cx).map_err(Into::into)?);
+                    State::TailReady {
+                        req: Some(res),
+                        tail: tail.take(),
+                    }
+                }
+
+                StateProj::TailReady { req, tail } => {
+                    let _ready: () = ready!(tail.as_mut().unwrap().poll_ready(cx)?);
+                    let tail = tail.take().unwrap();
+                    State::TailPending {
+                        future: tail.call_owned(req.take().unwrap()),
+                    }
+                }
+
+                StateProj::TailPending { future } => {
+                    return future.poll(cx);
+                }
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
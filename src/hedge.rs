@@ -0,0 +1,148 @@
+use crate::oneshot::Oneshot;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::Sleep;
+use tower_service::Service;
+
+/// A service that sends a second, hedged, call if the first hasn't completed within a delay.
+///
+/// The hedged call is made with a clone of the request and a clone of the inner service, which
+/// is driven to readiness first. Whichever call completes first wins and the other is dropped.
+///
+/// `delay` is typically set to a high percentile of the inner service's latency, so only the slow
+/// tail of calls is hedged.
+///
+/// Usually created with [`Pipeline::hedge_second`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// // a service where the first call is very slow
+/// let calls = Arc::new(AtomicUsize::new(0));
+/// let flaky_svc = service_fn(move |input: usize| {
+///     let calls = Arc::clone(&calls);
+///     async move {
+///         if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+///             tokio::time::sleep(Duration::from_secs(10)).await;
+///         }
+///         Ok::<_, BoxError>(input * 2)
+///     }
+/// });
+///
+/// let combined = length_svc
+///     .pipeline(flaky_svc)
+///     .hedge_second(Duration::from_millis(10));
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// # }
+/// ```
+///
+/// [`Pipeline::hedge_second`]: crate::Pipeline::hedge_second
+#[derive(Debug, Clone, Copy)]
+pub struct Hedge<S> {
+    inner: S,
+    delay: Duration,
+}
+
+impl<S> Hedge<S> {
+    /// Create a new [`Hedge`] that sends a hedged call after `delay`.
+    pub fn new(inner: S, delay: Duration) -> Self {
+        Self { inner, delay }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, T> Service<T> for Hedge<S>
+where
+    S: Service<T> + Clone,
+    T: Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = HedgeFuture<S, T>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        let backup = Some((self.inner.clone(), req.clone()));
+        HedgeFuture {
+            primary: self.inner.call(req),
+            sleep: tokio::time::sleep(self.delay),
+            backup,
+            hedge: None,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Hedge`].
+    pub struct HedgeFuture<S, T>
+    where
+        S: Service<T>,
+    {
+        #[pin]
+        primary: S::Future,
+        #[pin]
+        sleep: Sleep,
+        backup: Option<(S, T)>,
+        #[pin]
+        hedge: Option<Oneshot<S, T>>,
+    }
+}
+
+impl<S, T> Future for HedgeFuture<S, T>
+where
+    S: Service<T>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        if let Poll::Ready(result) = this.primary.poll(cx) {
+            return Poll::Ready(result);
+        }
+
+        if this.hedge.is_none() {
+            if this.sleep.poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+            let (svc, req) = this.backup.take().unwrap();
+            this.hedge.set(Some(Oneshot::new(svc, req)));
+        }
+
+        this.hedge.as_pin_mut().unwrap().poll(cx)
+    }
+}
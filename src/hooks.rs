@@ -0,0 +1,249 @@
+use crate::{oneshot::Oneshot, trace::StageSpan, Pipeline, Stage, StageNames};
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Callbacks for the lifecycle of the calls of a pipeline.
+///
+/// Every method does nothing by default, so implementations only override the ones they need.
+/// The hooks are cloned for every call, so they can track per call state, such as when the
+/// call started.
+///
+/// `Req` is the request of the pipeline, `Mid` the response of the first service, and `Res`
+/// the response of the second service.
+///
+/// Used with [`Pipeline::hooks`].
+pub trait PipelineHooks<Req, Mid, Res> {
+    /// Called with the request when the pipeline is called.
+    fn on_call(&mut self, req: &Req) {
+        let _ = req;
+    }
+
+    /// Called with the response of the first service.
+    fn on_first_response(&mut self, res: &Mid) {
+        let _ = res;
+    }
+
+    /// Called when the second service is ready, right before it is called.
+    fn on_second_ready(&mut self) {}
+
+    /// Called with the response of the second service when the call completes successfully.
+    fn on_complete(&mut self, res: &Res) {
+        let _ = res;
+    }
+
+    /// Called when a stage fails, either in its response future or, for the second service,
+    /// while it is driven to readiness.
+    fn on_error(&mut self, stage: Stage) {
+        let _ = stage;
+    }
+}
+
+/// A [`Pipeline`] that calls [`PipelineHooks`] throughout the lifecycle of every call.
+///
+/// Created with [`Pipeline::hooks`].
+///
+/// ```
+/// use tower_pipeline::{PipelineExt, PipelineHooks, Stage};
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use std::sync::{Arc, Mutex};
+///
+/// #[derive(Clone, Default)]
+/// struct Audit {
+///     events: Arc<Mutex<Vec<String>>>,
+/// }
+///
+/// impl PipelineHooks<&'static str, usize, usize> for Audit {
+///     fn on_call(&mut self, req: &&'static str) {
+///         self.events.lock().unwrap().push(format!("call {}", req));
+///     }
+///
+///     fn on_first_response(&mut self, res: &usize) {
+///         self.events.lock().unwrap().push(format!("first {}", res));
+///     }
+///
+///     fn on_complete(&mut self, res: &usize) {
+///         self.events.lock().unwrap().push(format!("complete {}", res));
+///     }
+///
+///     fn on_error(&mut self, stage: Stage) {
+///         self.events.lock().unwrap().push(format!("{} failed", stage));
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let audit = Audit::default();
+///
+/// let combined = length_svc.pipeline(double_svc).hooks(audit.clone());
+///
+/// assert_eq!(combined.oneshot("rust").await.unwrap(), 8);
+///
+/// assert_eq!(
+///     *audit.events.lock().unwrap(),
+///     ["call rust", "first 4", "complete 8"],
+/// );
+/// # }
+/// ```
+///
+/// [`Pipeline::hooks`]: crate::Pipeline::hooks
+#[derive(Debug, Clone, Copy)]
+pub struct Hooked<A, B, H> {
+    inner: Pipeline<A, B>,
+    hooks: H,
+}
+
+impl<A, B, H> Hooked<A, B, H> {
+    /// Create a new [`Hooked`] of a [`Pipeline`].
+    pub fn new(inner: Pipeline<A, B>, hooks: H) -> Self {
+        Self { inner, hooks }
+    }
+
+    /// Get a reference to the hooks.
+    pub fn hooks(&self) -> &H {
+        &self.hooks
+    }
+
+    /// Get a reference to the pipeline.
+    pub fn get_ref(&self) -> &Pipeline<A, B> {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the pipeline.
+    pub fn get_mut(&mut self) -> &mut Pipeline<A, B> {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the pipeline.
+    pub fn into_inner(self) -> Pipeline<A, B> {
+        self.inner
+    }
+}
+
+impl<R, A, B, H> Service<R> for Hooked<A, B, H>
+where
+    A: Service<R>,
+    B: Service<A::Response> + Clone,
+    A::Error: Into<B::Error>,
+    H: PipelineHooks<R, A::Response, B::Response> + Clone,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = HookedFuture<R, A, B, H>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        let mut hooks = self.hooks.clone();
+        hooks.on_call(&req);
+
+        let names = self.inner.names;
+        let second = self.inner.second_for_call();
+        let span = StageSpan::new(Stage::First, names);
+        let future = span.in_scope(|| self.inner.first.call(req));
+
+        HookedFuture {
+            state: State::FirstFuturePending {
+                future,
+                second: Some(second),
+            },
+            hooks,
+            names,
+            span,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Hooked`].
+    pub struct HookedFuture<R, A, B, H>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        #[pin]
+        state: State<R, A, B>,
+        hooks: H,
+        names: StageNames,
+        span: StageSpan,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        FirstFuturePending { #[pin] future: A::Future, second: Option<B> },
+        SecondFuturePending { #[pin] future: Oneshot<B, A::Response> },
+    }
+}
+
+impl<R, A, B, H> Future for HookedFuture<R, A, B, H>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+    A::Error: Into<B::Error>,
+    H: PipelineHooks<R, A::Response, B::Response>,
+{
+    type Output = Result<B::Response, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future, second } => {
+                    let result = ready!(this.span.in_scope(|| future.poll(cx)));
+                    this.span.record_outcome(&result);
+                    let first_res = match result {
+                        Ok(first_res) => first_res,
+                        Err(err) => {
+                            this.hooks.on_error(Stage::First);
+                            return Poll::Ready(Err(err.into()));
+                        }
+                    };
+                    this.hooks.on_first_response(&first_res);
+                    *this.span = StageSpan::new(Stage::Second, *this.names);
+                    State::SecondFuturePending {
+                        future: Oneshot::new(second.take().unwrap(), first_res),
+                    }
+                }
+
+                // `Oneshot` drives the second service to readiness and then calls it, so
+                // readiness errors are reported like errors of the call
+                StateProj::SecondFuturePending { future } => {
+                    let hooks = &mut *this.hooks;
+                    let result = ready!(this
+                        .span
+                        .in_scope(|| { future.poll_notify_ready(cx, || hooks.on_second_ready()) }));
+                    this.span.record_outcome(&result);
+                    match &result {
+                        Ok(res) => this.hooks.on_complete(res),
+                        Err(_) => this.hooks.on_error(Stage::Second),
+                    }
+                    return Poll::Ready(result);
+                }
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use ::http::{Request, Response};
+
+/// Create an adapter that converts the [`Response`] of one stage into the [`Request`] of the
+/// next, mapping the body with `f`.
+///
+/// The headers and extensions of the response are kept. The request has the default method and
+/// URI.
+///
+/// Use it with [`PipelineExt::pipeline_map`] or [`Pipeline::with_adapter`].
+///
+/// ```
+/// use tower_pipeline::{http::response_into_request, PipelineExt};
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use http::{Request, Response};
+///
+/// #[derive(Clone)]
+/// struct RequestId(u64);
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|req: Request<String>| async move {
+///     let mut res = Response::new(req.body().len());
+///     res.extensions_mut().insert(RequestId(1));
+///     Ok::<_, BoxError>(res)
+/// });
+///
+/// let describe_svc = service_fn(|req: Request<String>| async move {
+///     let RequestId(id) = req.extensions().get::<RequestId>().unwrap();
+///     Ok::<_, BoxError>(Response::new(format!("{} from request {}", req.body(), id)))
+/// });
+///
+/// let combined = length_svc.pipeline_map(
+///     response_into_request(|len: usize| format!("length {}", len)),
+///     describe_svc,
+/// );
+///
+/// let res = combined.oneshot(Request::new("rust".to_string())).await.unwrap();
+///
+/// assert_eq!(res.body(), "length 4 from request 1");
+/// # }
+/// ```
+///
+/// [`PipelineExt::pipeline_map`]: crate::PipelineExt::pipeline_map
+/// [`Pipeline::with_adapter`]: crate::Pipeline::with_adapter
+pub fn response_into_request<B1, B2, F>(f: F) -> impl FnOnce(Response<B1>) -> Request<B2> + Clone
+where
+    F: FnOnce(B1) -> B2 + Clone,
+{
+    move |res: Response<B1>| {
+        let (parts, body) = res.into_parts();
+        let mut req = Request::new(f(body));
+        *req.headers_mut() = parts.headers;
+        *req.extensions_mut() = parts.extensions;
+        req
+    }
+}
+
+/// Create an adapter that maps the body of a [`Response`] with `f`.
+///
+/// The status, headers, and extensions of the response are kept.
+///
+/// Use it with [`PipelineExt::pipeline_map`] or [`Pipeline::with_adapter`] when the second stage
+/// expects a response with a different body type.
+///
+/// [`PipelineExt::pipeline_map`]: crate::PipelineExt::pipeline_map
+/// [`Pipeline::with_adapter`]: crate::Pipeline::with_adapter
+pub fn map_response_body<B1, B2, F>(f: F) -> impl FnOnce(Response<B1>) -> Response<B2> + Clone
+where
+    F: FnOnce(B1) -> B2 + Clone,
+{
+    move |res: Response<B1>| res.map(f)
+}
+
+/// Create an adapter that maps the body of a [`Request`] with `f`.
+///
+/// The method, URI, headers, and extensions of the request are kept.
+///
+/// Use it with [`PipelineExt::pipeline_map`] or [`Pipeline::with_adapter`] when the first stage
+/// rewrites a request for a second stage that expects a different body type.
+///
+/// [`PipelineExt::pipeline_map`]: crate::PipelineExt::pipeline_map
+/// [`Pipeline::with_adapter`]: crate::Pipeline::with_adapter
+pub fn map_request_body<B1, B2, F>(f: F) -> impl FnOnce(Request<B1>) -> Request<B2> + Clone
+where
+    F: FnOnce(B1) -> B2 + Clone,
+{
+    move |req: Request<B1>| req.map(f)
+}
@@ -0,0 +1,67 @@
+//! Utilities for pipelines of [`http`] services.
+//!
+//! The most common shape is a first stage that responds with an [`http::Response`] and a
+//! second stage that expects an [`http::Request`]. The adapters in this module, such as
+//! [`response_into_request`], convert between the two while keeping the extensions.
+//!
+//! [`http`]: ::http
+//! [`http::Response`]: ::http::Response
+//! [`http::Request`]: ::http::Request
+
+use ::http::{Extensions, HeaderMap, Request, Response};
+
+pub(crate) mod adapters;
+pub(crate) mod propagate;
+
+pub use self::{
+    adapters::{map_request_body, map_response_body, response_into_request},
+    propagate::PropagateHttp,
+};
+
+/// An HTTP request or response.
+///
+/// This trait is sealed and implemented for [`Request`] and [`Response`].
+pub trait HttpMessage: sealed::Sealed {
+    /// Get a reference to the headers.
+    fn headers(&self) -> &HeaderMap;
+
+    /// Get a mutable reference to the headers.
+    fn headers_mut(&mut self) -> &mut HeaderMap;
+
+    /// Get a reference to the extensions.
+    fn extensions(&self) -> &Extensions;
+
+    /// Get a mutable reference to the extensions.
+    fn extensions_mut(&mut self) -> &mut Extensions;
+}
+
+macro_rules! impl_http_message {
+    ($ty:ident) => {
+        impl<B> sealed::Sealed for $ty<B> {}
+
+        impl<B> HttpMessage for $ty<B> {
+            fn headers(&self) -> &HeaderMap {
+                $ty::headers(self)
+            }
+
+            fn headers_mut(&mut self) -> &mut HeaderMap {
+                $ty::headers_mut(self)
+            }
+
+            fn extensions(&self) -> &Extensions {
+                $ty::extensions(self)
+            }
+
+            fn extensions_mut(&mut self) -> &mut Extensions {
+                $ty::extensions_mut(self)
+            }
+        }
+    };
+}
+
+impl_http_message!(Request);
+impl_http_message!(Response);
+
+mod sealed {
+    pub trait Sealed {}
+}
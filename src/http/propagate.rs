@@ -0,0 +1,238 @@
+use super::HttpMessage;
+use crate::oneshot::Oneshot;
+use ::http::{header::HeaderName, Extensions, HeaderValue, Request};
+use alloc::vec::Vec;
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+/// Two HTTP services combined where the extensions and selected headers of the request are
+/// carried across the stage boundary.
+///
+/// The extensions of the request are added to the response of the first service before it is
+/// passed to the second, and to the response of the second service. Extensions set by the
+/// services themselves take precedence. Headers added with
+/// [`PropagateHttp::header`] are copied from the request to the response of the first service,
+/// unless it already has them.
+///
+/// The response of the first service can be any [`HttpMessage`], so the first service can both
+/// respond with a [`Response`] or rewrite the request into a new [`Request`].
+///
+/// Created with [`Pipeline::propagate_http`].
+///
+/// ```
+/// use tower_pipeline::Pipeline;
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use http::{header::AUTHORIZATION, Request, Response};
+///
+/// #[derive(Clone)]
+/// struct RequestId(u64);
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let rewrite_svc = service_fn(|req: Request<String>| async move {
+///     // the new request doesn't carry over the header or the extensions
+///     let req = Request::post("/lengths").body(req.into_body().len())?;
+///     Ok::<_, BoxError>(req)
+/// });
+///
+/// let describe_svc = service_fn(|req: Request<usize>| async move {
+///     let RequestId(id) = req.extensions().get::<RequestId>().unwrap();
+///     assert!(req.headers().contains_key(AUTHORIZATION));
+///     Ok::<_, BoxError>(Response::new(format!("request {} has length {}", id, req.body())))
+/// });
+///
+/// let combined = Pipeline::new(rewrite_svc, describe_svc)
+///     .propagate_http()
+///     .header(AUTHORIZATION);
+///
+/// let mut req = Request::new("rust".to_string());
+/// req.extensions_mut().insert(RequestId(1));
+/// req.headers_mut().insert(AUTHORIZATION, "secret".parse().unwrap());
+///
+/// let res = combined.oneshot(req).await.unwrap();
+///
+/// assert_eq!(res.body(), "request 1 has length 4");
+/// assert!(res.extensions().get::<RequestId>().is_some());
+/// # }
+/// ```
+///
+/// [`Response`]: ::http::Response
+/// [`Pipeline::propagate_http`]: crate::Pipeline::propagate_http
+#[derive(Debug, Clone, Default)]
+pub struct PropagateHttp<A, B> {
+    first: A,
+    second: B,
+    headers: Arc<Vec<HeaderName>>,
+}
+
+impl<A, B> PropagateHttp<A, B> {
+    /// Create a new [`PropagateHttp`] from two [`Service`]s.
+    pub fn new(first: A, second: B) -> Self {
+        Self {
+            first,
+            second,
+            headers: Arc::new(Vec::new()),
+        }
+    }
+
+    /// Copy the header `name` from the request to the response of the first service.
+    pub fn header(mut self, name: HeaderName) -> Self {
+        Arc::make_mut(&mut self.headers).push(name);
+        self
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<ReqBody, A, B> Service<Request<ReqBody>> for PropagateHttp<A, B>
+where
+    A: Service<Request<ReqBody>>,
+    A::Response: HttpMessage,
+    B: Service<A::Response> + Clone,
+    B::Response: HttpMessage,
+    A::Error: Into<B::Error>,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = PropagateHttpFuture<Request<ReqBody>, A, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let extensions = req.extensions().clone();
+        let headers = self
+            .headers
+            .iter()
+            .flat_map(|name| {
+                req.headers()
+                    .get_all(name)
+                    .iter()
+                    .map(move |value| (name.clone(), value.clone()))
+            })
+            .collect();
+
+        PropagateHttpFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            extensions,
+            headers,
+            second: Some(self.second.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`PropagateHttp`].
+    pub struct PropagateHttpFuture<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        #[pin]
+        state: State<R, A, B>,
+        extensions: Extensions,
+        headers: Vec<(HeaderName, HeaderValue)>,
+        second: Option<B>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturePending { #[pin] future: Oneshot<B, A::Response> },
+    }
+}
+
+impl<R, A, B> Future for PropagateHttpFuture<R, A, B>
+where
+    A: Service<R>,
+    A::Response: HttpMessage,
+    B: Service<A::Response>,
+    B::Response: HttpMessage,
+    A::Error: Into<B::Error>,
+{
+    type Output = Result<B::Response, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let mut first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    merge_extensions(first_res.extensions_mut(), this.extensions.clone());
+                    let headers = std::mem::take(this.headers);
+                    let existing = headers
+                        .iter()
+                        .map(|(name, _)| first_res.headers().contains_key(name))
+                        .collect::<Vec<_>>();
+                    for ((name, value), existing) in headers.into_iter().zip(existing) {
+                        if !existing {
+                            first_res.headers_mut().append(name, value);
+                        }
+                    }
+                    State::SecondFuturePending {
+                        future: Oneshot::new(this.second.take().unwrap(), first_res),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => {
+                    let mut second_res = ready!(future.poll(cx)?);
+                    merge_extensions(second_res.extensions_mut(), std::mem::take(this.extensions));
+                    return Poll::Ready(Ok(second_res));
+                }
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
+
+/// Add the extensions in `from` to `into`, keeping the values already in `into`.
+fn merge_extensions(into: &mut Extensions, mut from: Extensions) {
+    from.extend(std::mem::take(into));
+    *into = from;
+}
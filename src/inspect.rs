@@ -0,0 +1,119 @@
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// A service that calls a closure with a reference to each successful response of the inner
+/// service.
+///
+/// Usually created with [`Pipeline::inspect`], in which case the closure sees the intermediate
+/// value before it is passed to the second service.
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use std::sync::{Arc, Mutex};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let seen = Arc::new(Mutex::new(Vec::new()));
+///
+/// let combined = length_svc.pipeline(double_svc).inspect({
+///     let seen = Arc::clone(&seen);
+///     move |len: &usize| seen.lock().unwrap().push(*len)
+/// });
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// assert_eq!(*seen.lock().unwrap(), vec![4]);
+/// # }
+/// ```
+///
+/// [`Pipeline::inspect`]: crate::Pipeline::inspect
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Inspect<S, F> {
+    inner: S,
+    f: F,
+}
+
+impl<S, F> Inspect<S, F> {
+    /// Create a new [`Inspect`].
+    pub fn new(inner: S, f: F) -> Self {
+        Self { inner, f }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<R, S, F> Service<R> for Inspect<S, F>
+where
+    S: Service<R>,
+    F: FnOnce(&S::Response) + Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = InspectFuture<S::Future, F>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        InspectFuture {
+            future: self.inner.call(req),
+            f: Some(self.f.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Inspect`].
+    pub struct InspectFuture<Fut, F> {
+        #[pin]
+        future: Fut,
+        f: Option<F>,
+    }
+}
+
+impl<Fut, F, T, E> Future for InspectFuture<Fut, F>
+where
+    Fut: Future<Output = Result<T, E>>,
+    F: FnOnce(&T),
+{
+    type Output = Result<T, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let result = ready!(this.future.poll(cx));
+        if let Ok(res) = &result {
+            (this.f.take().unwrap())(res);
+        }
+        Poll::Ready(result)
+    }
+}
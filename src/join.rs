@@ -0,0 +1,222 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::{
+    future::{maybe_done, MaybeDone},
+    ready,
+};
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Three services combined where the same request is sent to the first two concurrently, and
+/// the pair of their responses becomes the request of the third.
+///
+/// ```
+/// use tower_pipeline::Join;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let uppercase_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.to_uppercase())
+/// });
+///
+/// let format_svc = service_fn(|(len, upper): (usize, String)| async move {
+///     Ok::<_, BoxError>(format!("{} has {} characters", upper, len))
+/// });
+///
+/// let combined = Join::new(length_svc, uppercase_svc, format_svc);
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, "RUST has 4 characters");
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Join<A, B, C> {
+    first: A,
+    second: B,
+    consumer: C,
+}
+
+impl<A, B, C> Join<A, B, C> {
+    /// Create a new [`Join`] from two services called concurrently and a consumer of their
+    /// responses.
+    pub fn new(first: A, second: B, consumer: C) -> Self {
+        Self {
+            first,
+            second,
+            consumer,
+        }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+
+    /// Get a reference to the consumer service.
+    pub fn consumer_as_ref(&self) -> &C {
+        &self.consumer
+    }
+
+    /// Get a mutable reference to the consumer service.
+    pub fn consumer_as_mut(&mut self) -> &mut C {
+        &mut self.consumer
+    }
+
+    /// Consume `self`, returning the consumer service
+    pub fn into_consumer(self) -> C {
+        self.consumer
+    }
+}
+
+impl<R, A, B, C> Service<R> for Join<A, B, C>
+where
+    R: Clone,
+    A: Service<R>,
+    B: Service<R>,
+    C: Service<(A::Response, B::Response)> + Clone,
+    A::Error: Into<C::Error>,
+    B::Error: Into<C::Error>,
+{
+    type Response = C::Response;
+    type Error = C::Error;
+    type Future = JoinFuture<R, A, B, C>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let _ready: () = ready!(self.first.poll_ready(cx).map_err(Into::into)?);
+        self.second.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        JoinFuture {
+            state: State::Joining {
+                first: maybe_done(self.first.call(req.clone())),
+                second: maybe_done(self.second.call(req)),
+            },
+            consumer: Some(self.consumer.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Join`].
+    pub struct JoinFuture<R, A, B, C>
+    where
+        A: Service<R>,
+        B: Service<R>,
+        C: Service<(A::Response, B::Response)>,
+    {
+        #[pin]
+        state: State<R, A, B, C>,
+        consumer: Option<C>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B, C>
+    where
+        A: Service<R>,
+        B: Service<R>,
+        C: Service<(A::Response, B::Response)>,
+    {
+        Joining {
+            #[pin]
+            first: MaybeDone<A::Future>,
+            #[pin]
+            second: MaybeDone<B::Future>,
+        },
+        ConsumerPending { #[pin] future: Oneshot<C, (A::Response, B::Response)> },
+    }
+}
+
+impl<R, A, B, C> Future for JoinFuture<R, A, B, C>
+where
+    A: Service<R>,
+    B: Service<R>,
+    C: Service<(A::Response, B::Response)>,
+    A::Error: Into<C::Error>,
+    B::Error: Into<C::Error>,
+{
+    type Output = Result<C::Response, C::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::Joining {
+                    mut first,
+                    mut second,
+                } => {
+                    let first_done = first.as_mut().poll(cx).is_ready();
+                    let second_done = second.as_mut().poll(cx).is_ready();
+
+                    if let Some(Err(_)) = first.as_mut().output_mut() {
+                        if let Some(Err(err)) = first.as_mut().take_output() {
+                            return Poll::Ready(Err(err.into()));
+                        }
+                    }
+
+                    if let Some(Err(_)) = second.as_mut().output_mut() {
+                        if let Some(Err(err)) = second.as_mut().take_output() {
+                            return Poll::Ready(Err(err.into()));
+                        }
+                    }
+
+                    if !(first_done && second_done) {
+                        return Poll::Pending;
+                    }
+
+                    match (first.take_output(), second.take_output()) {
+                        (Some(Ok(first_res)), Some(Ok(second_res))) => State::ConsumerPending {
+                            future: Oneshot::new(
+                                this.consumer.take().unwrap(),
+                                (first_res, second_res),
+                            ),
+                        },
+                        _ => unreachable!(),
+                    }
+                }
+
+                StateProj::ConsumerPending { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Two services combined where the response of the first is the request of the second, and both
+/// responses are returned.
+///
+/// The response of the first service is cloned before it is passed to the second, so it must
+/// implement [`Clone`].
+///
+/// Created with [`Pipeline::keep_intermediate`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let combined = length_svc.pipeline(double_svc).keep_intermediate();
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, (4, 8));
+/// # }
+/// ```
+///
+/// [`Pipeline::keep_intermediate`]: crate::Pipeline::keep_intermediate
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeepIntermediate<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> KeepIntermediate<A, B> {
+    /// Create a new [`KeepIntermediate`] from two [`Service`]s.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, A, B> Service<R> for KeepIntermediate<A, B>
+where
+    A: Service<R>,
+    A::Response: Clone,
+    B: Service<A::Response> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Response = (A::Response, B::Response);
+    type Error = B::Error;
+    type Future = KeepIntermediateFuture<R, A, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        KeepIntermediateFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            intermediate: None,
+            second: Some(self.second.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`KeepIntermediate`].
+    pub struct KeepIntermediateFuture<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        #[pin]
+        state: State<R, A, B>,
+        intermediate: Option<A::Response>,
+        second: Option<B>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturePending { #[pin] future: Oneshot<B, A::Response> },
+    }
+}
+
+impl<R, A, B> Future for KeepIntermediateFuture<R, A, B>
+where
+    A: Service<R>,
+    A::Response: Clone,
+    B: Service<A::Response>,
+    A::Error: Into<B::Error>,
+{
+    type Output = Result<(A::Response, B::Response), B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    let second = this.second.take().unwrap();
+                    *this.intermediate = Some(first_res.clone());
+                    State::SecondFuturePending {
+                        future: Oneshot::new(second, first_res),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => {
+                    let second_res = ready!(future.poll(cx)?);
+                    let first_res = this.intermediate.take().unwrap();
+                    return Poll::Ready(Ok((first_res, second_res)));
+                }
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::Pipeline;
+use tower_layer::Layer;
+
+/// A [`Layer`] that produces [`Pipeline`]s where the wrapped service is the first stage.
+///
+/// ```
+/// use tower_pipeline::PipelineLayer;
+/// use tower::{service_fn, BoxError, ServiceBuilder, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let combined = ServiceBuilder::new()
+///     .layer(PipelineLayer::new(double_svc))
+///     .service(length_svc);
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PipelineLayer<B> {
+    second: B,
+}
+
+impl<B> PipelineLayer<B> {
+    /// Create a new [`PipelineLayer`] that pipelines into `second`.
+    pub fn new(second: B) -> Self {
+        Self { second }
+    }
+}
+
+impl<S, B> Layer<S> for PipelineLayer<B>
+where
+    B: Clone,
+{
+    type Service = Pipeline<S, B>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Pipeline::new(inner, self.second.clone())
+    }
+}
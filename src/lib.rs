@@ -30,6 +30,28 @@
 //! # }
 //! ```
 //!
+//! # Feature flags
+//!
+//! - `blocking`: Enables calling pipelines from synchronous code with
+//!   `Pipeline::call_blocking`.
+//! - `grpc`: Enables the `grpc` module for using tonic clients as stages.
+//! - `http`: Enables the `http` module for pipelines of HTTP services.
+//! - `load`: Implements `tower::load::Load` for [`Pipeline`] and enables `WithLoad` for
+//!   balancing requests between pipelines.
+//! - `registry`: Enables the `registry` module for building pipelines from configuration.
+//! - `std` (enabled by default): Enables the combinators that need the standard library, such
+//!   as [`Exclusive`], [`Drain`], [`Timed`], and [`CircuitBreaker`]. Without it this crate only
+//!   depends on `core` and `alloc`, and requires Rust 1.81 for `core::error::Error`. All other
+//!   features imply `std`.
+//! - `test-util`: Enables the `test_util` module with mock stages for testing pipelines.
+//! - `tokio`: Enables per-stage timeouts, rate limiting, hedging, and running stages on
+//!   spawned tasks.
+//! - `tokio-util`: Enables cancelling calls with a `CancellationToken`.
+//! - `tracing`: Runs each stage of a [`Pipeline`] inside a `pipeline.first` or `pipeline.second`
+//!   span, with the name of the stage in a `name` field and the outcome of the stage recorded in
+//!   an `outcome` field. Also enables `Pipeline::log_intermediate` for logging intermediate
+//!   values.
+//!
 //! [Tower]: https://crates.io/crates/tower
 //! [`Service`]: tower_service::Service
 //! [function composition]: https://en.wikipedia.org/wiki/Function_composition
@@ -45,27 +67,493 @@
 #![allow(elided_lifetimes_in_paths, clippy::type_complexity)]
 #![cfg_attr(test, allow(clippy::float_cmp))]
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![no_std]
 
-use futures_util::ready;
-use pin_project_lite::pin_project;
-use std::future::Future;
-use std::{
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use alloc::{boxed::Box, vec::Vec};
+use call_mut::CallMut;
+use core::future::Future;
+use core::{
+    fmt,
     pin::Pin,
     task::{Context, Poll},
 };
+#[cfg(feature = "std")]
+use futures_util::Sink;
+use futures_util::{
+    future::{self as futures, FusedFuture, FutureExt, TryFutureExt},
+    ready,
+};
+use oneshot::{Oneshot, OneshotFuture, ReadyOneshotFuture};
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
 use tower_service::Service;
+use trace::StageSpan;
+
+mod and_then;
+#[cfg(feature = "tokio")]
+mod batch;
+#[cfg(feature = "blocking")]
+mod blocking;
+mod borrowing;
+mod boxed;
+mod branch;
+#[cfg(feature = "tokio")]
+mod buffer;
+mod builder;
+mod cache;
+mod call_iter;
+mod call_mut;
+mod call_stream;
+#[cfg(feature = "tokio-util")]
+mod cancel;
+mod carry_intermediate;
+mod chain;
+#[cfg(feature = "std")]
+mod circuit_breaker;
+#[cfg(feature = "std")]
+mod concurrency_limit;
+#[cfg(feature = "tokio")]
+mod deadline;
+mod describe;
+#[cfg(feature = "std")]
+mod drain;
+mod dry_run;
+mod dyn_pipeline;
+mod error;
+#[cfg(feature = "std")]
+mod exclusive;
+#[cfg(feature = "std")]
+mod extensions;
+mod fallback;
+mod filter;
+mod for_each;
+mod for_each_stream;
+#[cfg(feature = "grpc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "grpc")))]
+pub mod grpc;
+mod hchain;
+#[cfg(feature = "tokio")]
+mod hedge;
+mod hooks;
+#[cfg(feature = "http")]
+#[cfg_attr(docsrs, doc(cfg(feature = "http")))]
+pub mod http;
+mod inspect;
+mod join;
+mod keep_intermediate;
+mod layer;
+#[cfg(feature = "load")]
+mod load;
+mod load_shed;
+#[cfg(feature = "tracing")]
+mod log_intermediate;
+mod loop_while;
+mod macros;
+mod make;
+mod map_err;
+#[cfg(feature = "std")]
+mod memoize;
+mod oneshot;
+mod or_else;
+mod ordering;
+#[cfg(feature = "tokio")]
+mod parallel;
+#[cfg(feature = "std")]
+mod pause;
+mod pipeline3;
+mod pipeline_map;
+#[cfg(feature = "std")]
+mod pool;
+#[cfg(feature = "tokio")]
+mod priority;
+#[cfg(feature = "tokio")]
+mod queue_metrics;
+#[cfg(feature = "tokio")]
+mod rate_limit;
+mod readiness;
+mod reconnect;
+#[cfg(feature = "std")]
+mod record;
+#[cfg(feature = "registry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "registry")))]
+pub mod registry;
+mod retry;
+#[cfg(feature = "std")]
+mod retry_budget;
+mod scatter_gather;
+#[cfg(feature = "std")]
+mod semaphore;
+#[cfg(feature = "std")]
+mod shared;
+mod short_circuit;
+#[cfg(feature = "std")]
+mod singleflight;
+#[cfg(feature = "std")]
+mod sink;
+#[cfg(feature = "tokio")]
+mod spawn_ready;
+mod split_errors;
+mod stage;
+mod stage_fn;
+mod steer;
+#[cfg(feature = "std")]
+mod swappable;
+mod tee;
+#[cfg(feature = "test-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+pub mod test_util;
+#[cfg(feature = "std")]
+mod timed;
+#[cfg(feature = "tokio")]
+mod timeout;
+mod trace;
+mod transform;
+mod unzip;
+mod validate;
+mod with_context;
+mod with_results;
+
+pub use self::{
+    and_then::AndThen,
+    borrowing::Borrowing,
+    boxed::{BoxClonePipeline, BoxPipeline, LocalBoxPipeline},
+    branch::Branch,
+    builder::PipelineBuilder,
+    cache::{AsyncCache, Cached},
+    call_iter::CallIter,
+    call_stream::CallStream,
+    carry_intermediate::CarryIntermediate,
+    chain::{Chain, IntoPipeline},
+    describe::{Describe, Graph, Labeled, NodeId, Subgraph},
+    dry_run::DryRun,
+    dyn_pipeline::DynPipeline,
+    error::{PipelineError, SecondError, ValidationError},
+    fallback::Fallback,
+    filter::AsyncFilter,
+    for_each::ForEachConcurrent,
+    for_each_stream::ForEachStream,
+    hchain::{HChain, HNil},
+    hooks::{Hooked, PipelineHooks},
+    inspect::Inspect,
+    join::Join,
+    keep_intermediate::KeepIntermediate,
+    layer::PipelineLayer,
+    load_shed::{LoadShed, Overloaded},
+    loop_while::Loop,
+    make::MakePipeline,
+    map_err::MapErr,
+    or_else::OrElse,
+    ordering::Ordering,
+    pipeline3::Pipeline3,
+    pipeline_map::PipelineMap,
+    readiness::ReadinessMode,
+    reconnect::Reconnect,
+    retry::{Attempts, Retry, RetryPolicy},
+    scatter_gather::ScatterGather,
+    short_circuit::ShortCircuit,
+    split_errors::SplitErrors,
+    stage::{ResponseStage, Stage, StageNames},
+    stage_fn::{pipeline_fn, StageFn},
+    steer::Steer,
+    tee::Tee,
+    transform::TransformStream,
+    unzip::Unzip,
+    validate::Validate,
+    with_context::WithContext,
+    with_results::WithResults,
+};
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use self::batch::{Batch, BatchSizeMismatch};
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use self::buffer::{Buffer, Closed};
+#[cfg(feature = "tokio-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-util")))]
+pub use self::cancel::{CancelHook, Cancellable, Cancelled, NoHook};
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use self::deadline::{Deadline, DeadlineCarrier, PropagateDeadline};
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use self::hedge::Hedge;
+#[cfg(feature = "load")]
+#[cfg_attr(docsrs, doc(cfg(feature = "load")))]
+pub use self::load::{LoadFirst, LoadSecond, LoadStrategy, WithLoad};
+#[cfg(feature = "tracing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+pub use self::log_intermediate::LogIntermediate;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use self::parallel::Parallel;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use self::queue_metrics::{QueueMetrics, WaitTimes};
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use self::rate_limit::RateLimit;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use self::spawn_ready::SpawnReady;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use self::tee::Detached;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use self::timeout::{Elapsed, Timeout};
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use self::{
+    circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitOpen},
+    concurrency_limit::ConcurrencyLimit,
+    drain::{Drain, DrainHandle, Drained, Draining},
+    exclusive::Exclusive,
+    extensions::{Extensions, Scoped, WithExtensions},
+    memoize::Memoize,
+    pause::{Pausable, PauseHandle},
+    pool::Pool,
+    record::{NotRecorded, Record, RecordStore, Recording, Replay},
+    retry_budget::{Budgeted, RetryBudget},
+    shared::SharedService,
+    singleflight::{Abandoned, SharedError, Singleflight},
+    sink::SinkStage,
+    swappable::{StageHandle, Swappable},
+    timed::{Instrumented, Timed, Timings},
+};
+
+/// Alias for a type-erased error type.
+#[cfg(feature = "std")]
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Alias for a type-erased error type.
+#[cfg(not(feature = "std"))]
+pub type BoxError = Box<dyn core::error::Error + Send + Sync>;
+
+pub mod future {
+    //! Response futures of the services in this crate.
+
+    pub use super::and_then::AndThenFuture;
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub use super::batch::BatchFuture;
+    pub use super::borrowing::BorrowingFuture;
+    pub use super::branch::BranchFuture;
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub use super::buffer::BufferFuture;
+    pub use super::cache::CachedFuture;
+    pub use super::call_mut::CallMut;
+    #[cfg(feature = "tokio-util")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio-util")))]
+    pub use super::cancel::CancellableFuture;
+    pub use super::carry_intermediate::CarryIntermediateFuture;
+    pub use super::chain::ChainFuture;
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub use super::circuit_breaker::CircuitBreakerFuture;
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub use super::concurrency_limit::ConcurrencyLimitFuture;
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub use super::deadline::{DeadlineFuture, PropagateDeadlineFuture};
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub use super::drain::DrainFuture;
+    pub use super::dyn_pipeline::DynPipelineFuture;
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub use super::exclusive::ExclusiveFuture;
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub use super::extensions::WithExtensionsFuture;
+    pub use super::fallback::FallbackFuture;
+    pub use super::filter::AsyncFilterFuture;
+    pub use super::for_each::ForEachConcurrentFuture;
+    pub use super::for_each_stream::ForEachStreamFuture;
+    #[cfg(feature = "grpc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "grpc")))]
+    pub use super::grpc::UnaryFuture;
+    pub use super::hchain::HChainFuture;
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub use super::hedge::HedgeFuture;
+    pub use super::hooks::HookedFuture;
+    #[cfg(feature = "http")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "http")))]
+    pub use super::http::propagate::PropagateHttpFuture;
+    pub use super::inspect::InspectFuture;
+    pub use super::join::JoinFuture;
+    pub use super::keep_intermediate::KeepIntermediateFuture;
+    pub use super::load_shed::LoadShedFuture;
+    #[cfg(feature = "tracing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+    pub use super::log_intermediate::LogIntermediateFuture;
+    pub use super::loop_while::LoopFuture;
+    pub use super::make::MakePipelineFuture;
+    pub use super::map_err::MapErrFuture;
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub use super::memoize::MemoizeFuture;
+    pub use super::oneshot::{OneshotFuture, ReadyOneshotFuture};
+    pub use super::or_else::OrElseFuture;
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub use super::parallel::ParallelFuture;
+    pub use super::pipeline3::Pipeline3Future;
+    pub use super::pipeline_map::PipelineMapFuture;
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub use super::pool::PoolFuture;
+    pub use super::reconnect::ReconnectFuture;
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub use super::record::RecordFuture;
+    pub use super::retry::RetryFuture;
+    pub use super::scatter_gather::ScatterGatherFuture;
+    pub use super::short_circuit::ShortCircuitFuture;
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub use super::singleflight::SingleflightFuture;
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub use super::sink::SinkStageFuture;
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub use super::spawn_ready::SpawnReadyFuture;
+    pub use super::split_errors::SplitErrorsFuture;
+    pub use super::stage_fn::StageFnFuture;
+    pub use super::steer::SteerFuture;
+    pub use super::tee::TeeFuture;
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub use super::timed::{InstrumentedFuture, TimedFuture};
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub use super::timeout::TimeoutFuture;
+    pub use super::unzip::UnzipFuture;
+    pub use super::validate::ValidateFuture;
+    pub use super::with_context::WithContextFuture;
+    pub use super::with_results::WithResultsFuture;
+    pub use super::ResponseFuture;
+}
 
 /// Two services combined where the response of the first is the request of the second.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Pipeline<A, B> {
     first: A,
     second: B,
+    readiness: ReadinessMode,
+    names: StageNames,
 }
 
 impl<A, B> Pipeline<A, B> {
     /// Create a new [`Pipeline`] from two [`Service`]s.
     pub fn new(first: A, second: B) -> Self {
-        Self { first, second }
+        Self {
+            first,
+            second,
+            readiness: ReadinessMode::Lazy,
+            names: StageNames::default(),
+        }
+    }
+
+    /// Set the [`ReadinessMode`] of the pipeline.
+    ///
+    /// Defaults to [`ReadinessMode::Lazy`].
+    ///
+    /// ```
+    /// use tower_pipeline::{PipelineExt, ReadinessMode};
+    /// use tower::{service_fn, BoxError, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// // `poll_ready` now checks both services
+    /// let combined = length_svc
+    ///     .pipeline(double_svc)
+    ///     .readiness_mode(ReadinessMode::Eager);
+    ///
+    /// assert_eq!(combined.readiness(), ReadinessMode::Eager);
+    ///
+    /// let result = combined.oneshot("rust").await.unwrap();
+    ///
+    /// assert_eq!(result, 8);
+    /// # }
+    /// ```
+    pub fn readiness_mode(mut self, readiness: ReadinessMode) -> Self {
+        self.readiness = readiness;
+        self
+    }
+
+    /// Get the [`ReadinessMode`] of the pipeline.
+    pub fn readiness(&self) -> ReadinessMode {
+        self.readiness
+    }
+
+    /// Set the names of the two stages, used in diagnostics.
+    ///
+    /// With the `tracing` feature the names are recorded in the `name` field of the stage spans,
+    /// and errors from stage aware middleware added afterwards, such as
+    /// `Pipeline::timeout_first`, include them.
+    ///
+    /// Defaults to `"first"` and `"second"`.
+    ///
+    /// ```
+    /// use tower_pipeline::{Elapsed, PipelineExt};
+    /// use tower::{service_fn, BoxError, ServiceExt};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let geo_lookup = service_fn(|ip: &'static str| async move {
+    ///     Ok::<_, BoxError>(ip.len())
+    /// });
+    ///
+    /// let enrichment = service_fn(|input: usize| async move {
+    ///     tokio::time::sleep(Duration::from_secs(10)).await;
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let combined = geo_lookup
+    ///     .pipeline(enrichment)
+    ///     .named("geo-lookup", "enrichment")
+    ///     .timeout_second(Duration::from_millis(10));
+    ///
+    /// let err = combined.oneshot("127.0.0.1").await.unwrap_err();
+    ///
+    /// assert_eq!(err.downcast_ref::<Elapsed>().unwrap().name(), "enrichment");
+    /// assert_eq!(err.to_string(), "enrichment service timed out");
+    /// # }
+    /// ```
+    pub fn named(mut self, first: &'static str, second: &'static str) -> Self {
+        self.names = StageNames::new(first, second);
+        self
+    }
+
+    /// The names of the two stages.
+    pub fn names(&self) -> StageNames {
+        self.names
+    }
+
+    /// Create a new [`SplitErrors`] from two [`Service`]s.
+    ///
+    /// Unlike [`Pipeline::new`] the errors of the two services don't have to be related. Errors
+    /// are reported as a [`PipelineError`] that says which service failed.
+    pub fn with_split_errors(first: A, second: B) -> SplitErrors<A, B> {
+        SplitErrors::new(first, second)
     }
 
     /// Get a reference to the first service.
@@ -97,6 +585,1341 @@ impl<A, B> Pipeline<A, B> {
     pub fn into_second(self) -> B {
         self.second
     }
+
+    /// Get mutable references to both services.
+    pub fn as_parts_mut(&mut self) -> (&mut A, &mut B) {
+        (&mut self.first, &mut self.second)
+    }
+
+    /// Consume `self`, returning both services.
+    ///
+    /// The services can be combined again with [`Pipeline::new`], for example after replacing
+    /// one of them:
+    ///
+    /// ```
+    /// use tower_pipeline::{Pipeline, PipelineExt};
+    /// use tower::{service_fn, BoxError, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let (length_svc, _double_svc) = length_svc.pipeline(double_svc).into_parts();
+    ///
+    /// let triple_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 3)
+    /// });
+    ///
+    /// let combined = Pipeline::new(length_svc, triple_svc);
+    ///
+    /// assert_eq!(combined.oneshot("rust").await.unwrap(), 12);
+    /// # }
+    /// ```
+    pub fn into_parts(self) -> (A, B) {
+        (self.first, self.second)
+    }
+
+    /// Transform the first service with `f`, for example to wrap it in middleware.
+    pub fn map_first<F, A2>(self, f: F) -> Pipeline<A2, B>
+    where
+        F: FnOnce(A) -> A2,
+    {
+        Pipeline {
+            first: f(self.first),
+            second: self.second,
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Transform the second service with `f`, for example to wrap it in middleware.
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let combined = length_svc
+    ///     .pipeline(double_svc)
+    ///     .map_second(|svc| svc.map_response(|res| res + 1));
+    ///
+    /// assert_eq!(combined.oneshot("rust").await.unwrap(), 9);
+    /// # }
+    /// ```
+    pub fn map_second<F, B2>(self, f: F) -> Pipeline<A, B2>
+    where
+        F: FnOnce(B) -> B2,
+    {
+        Pipeline {
+            first: self.first,
+            second: f(self.second),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Wrap the second service in `layer`, so the middleware runs at the stage boundary and
+    /// sees the response of the first service as its request.
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, util::MapRequestLayer, BoxError, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let combined = length_svc
+    ///     .pipeline(double_svc)
+    ///     .layer_between(MapRequestLayer::new(|len: usize| len + 1));
+    ///
+    /// assert_eq!(combined.oneshot("rust").await.unwrap(), 10);
+    /// # }
+    /// ```
+    pub fn layer_between<L>(self, layer: L) -> Pipeline<A, L::Service>
+    where
+        L: Layer<B>,
+    {
+        Pipeline {
+            first: self.first,
+            second: layer.layer(self.second),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Wrap the whole pipeline in `layer`.
+    ///
+    /// This is the same as `layer.layer(pipeline)` but keeps construction in one chain.
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, util::MapResponseLayer, BoxError, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let combined = length_svc
+    ///     .pipeline(double_svc)
+    ///     .with_layer(MapResponseLayer::new(|res: usize| res.to_string()));
+    ///
+    /// assert_eq!(combined.oneshot("rust").await.unwrap(), "8");
+    /// # }
+    /// ```
+    pub fn with_layer<L>(self, layer: L) -> L::Service
+    where
+        L: Layer<Self>,
+    {
+        layer.layer(self)
+    }
+
+    /// Add a third service, producing a [`Pipeline3`].
+    ///
+    /// The response of the second service becomes the request of the third. [`Pipeline3`] doesn't
+    /// support a [`ReadinessMode`] or stage names, so the mode set with
+    /// [`Pipeline::readiness_mode`] and the names set with [`Pipeline::named`] aren't kept. The
+    /// second and third services are always driven to readiness in the response future, like
+    /// with [`ReadinessMode::Lazy`].
+    pub fn then<C>(self, third: C) -> Pipeline3<A, B, C> {
+        Pipeline3::new(self.first, self.second, third)
+    }
+
+    /// Convert into an [`Exclusive`] that shares the second service between calls rather than
+    /// cloning it.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn exclusive(self) -> Exclusive<A, B> {
+        Exclusive::new(self.first, self.second)
+    }
+
+    /// Share the second service between calls behind a [`SharedService`] rather than cloning
+    /// it.
+    ///
+    /// Unlike [`Pipeline::exclusive`] this keeps the [`Pipeline`], so middleware can still be
+    /// added to either service.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn shared_second(self) -> Pipeline<A, SharedService<B>> {
+        Pipeline {
+            first: self.first,
+            second: SharedService::new(self.second),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Share the second service between calls behind a [`Pool`], for second services that pick
+    /// what to call in `poll_ready` such as load balancers.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn pooled_second(self) -> Pipeline<A, Pool<B>> {
+        Pipeline {
+            first: self.first,
+            second: Pool::new(self.second),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Call the pipeline through a mutable borrow, driving both services to readiness in the
+    /// returned future.
+    ///
+    /// Unlike calling the pipeline as a [`Service`] the second service isn't cloned, so it
+    /// doesn't have to implement [`Clone`]. The borrow means only one call can be in flight at a
+    /// time, which suits pipelines with a single owner.
+    ///
+    /// ```
+    /// use tower_pipeline::Pipeline;
+    /// use tower::{service_fn, BoxError};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// // a service that doesn't implement `Clone`
+    /// let not_clone = String::from("not clone");
+    /// let double_svc = service_fn(move |input: usize| {
+    ///     let _ = &not_clone;
+    ///     async move { Ok::<_, BoxError>(input * 2) }
+    /// });
+    ///
+    /// let mut combined = Pipeline::new(length_svc, double_svc);
+    ///
+    /// assert_eq!(combined.call_mut("rust").await.unwrap(), 8);
+    /// assert_eq!(combined.call_mut("tower").await.unwrap(), 10);
+    /// # }
+    /// ```
+    pub fn call_mut<R>(&mut self, req: R) -> CallMut<'_, R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+        A::Error: Into<B::Error>,
+    {
+        CallMut::new(&mut self.first, &mut self.second, req)
+    }
+
+    /// Wait for the pipeline to be ready and call it with `req`, consuming the pipeline.
+    ///
+    /// This is the same as [`ServiceExt::oneshot`] from `tower` but doesn't require depending
+    /// on it.
+    ///
+    /// ```
+    /// use tower_pipeline::Pipeline;
+    /// use tower::{service_fn, BoxError};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let combined = Pipeline::new(length_svc, double_svc);
+    ///
+    /// assert_eq!(combined.oneshot("rust").await.unwrap(), 8);
+    /// # }
+    /// ```
+    ///
+    /// [`ServiceExt::oneshot`]: https://docs.rs/tower/latest/tower/trait.ServiceExt.html#method.oneshot
+    pub fn oneshot<R>(self, req: R) -> OneshotFuture<Self, R>
+    where
+        Self: Service<R>,
+    {
+        OneshotFuture::new(self, req)
+    }
+
+    /// Wait for the pipeline to be ready, consuming the pipeline and yielding it once it is.
+    ///
+    /// This is the same as [`ServiceExt::ready_oneshot`] from `tower` but doesn't require
+    /// depending on it.
+    ///
+    /// [`ServiceExt::ready_oneshot`]: https://docs.rs/tower/latest/tower/trait.ServiceExt.html#method.ready_oneshot
+    pub fn ready_oneshot<R>(self) -> ReadyOneshotFuture<Self, R>
+    where
+        Self: Service<R>,
+    {
+        ReadyOneshotFuture::new(self)
+    }
+
+    /// Send a copy of the response of the first service to `side`, producing a [`Tee`].
+    ///
+    /// Calls wait for `side` to finish as well as the second service, see `Tee::detach_side`
+    /// for not waiting on it.
+    pub fn tee<C>(self, side: C) -> Tee<A, B, C> {
+        Tee::new(self.first, self.second, side)
+    }
+
+    /// Call `fallback` with the response of the first service if the second service fails.
+    ///
+    /// This wraps the second service in a [`Fallback`].
+    pub fn with_fallback<F>(self, fallback: F) -> Pipeline<A, Fallback<B, F>> {
+        Pipeline {
+            first: self.first,
+            second: Fallback::new(self.second, fallback),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Only call the first service, responding with the intermediate value, producing a
+    /// [`DryRun`].
+    pub fn dry_run(self) -> DryRun<A, B> {
+        DryRun::new(self)
+    }
+
+    /// Call `handler` with the error if either service fails, producing an [`OrElse`].
+    ///
+    /// The handler can produce a substitute response.
+    pub fn or_else<H>(self, handler: H) -> OrElse<Self, H> {
+        OrElse::new(self, handler)
+    }
+
+    /// Call `err_svc` with the error if the first service fails, passing its response to the
+    /// second service as if the first service had succeeded.
+    ///
+    /// This wraps the first service in an [`OrElse`].
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let cache_svc = service_fn(|_: &'static str| async move {
+    ///     Err::<usize, BoxError>(BoxError::from("cache miss"))
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let default_svc = service_fn(|_: BoxError| async move {
+    ///     Ok::<_, BoxError>(1)
+    /// });
+    ///
+    /// let combined = cache_svc.pipeline(double_svc).recover_with(default_svc);
+    ///
+    /// let result = combined.oneshot("rust").await.unwrap();
+    ///
+    /// assert_eq!(result, 2);
+    /// # }
+    /// ```
+    pub fn recover_with<H>(self, err_svc: H) -> Pipeline<OrElse<A, H>, B> {
+        Pipeline {
+            first: OrElse::new(self.first, err_svc),
+            second: self.second,
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Retry the second service according to `policy` if it fails.
+    ///
+    /// This wraps the second service in a [`Retry`].
+    pub fn retry_second<P>(self, policy: P) -> Pipeline<A, Retry<B, P>> {
+        Pipeline {
+            first: self.first,
+            second: Retry::new(self.second, policy),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Retry the second service according to `policy` if it fails, as long as `budget`
+    /// allows it.
+    ///
+    /// The budget is shared by every clone of the pipeline, and any other pipeline it is
+    /// passed to. This wraps the second service in a [`Retry`] with a [`Budgeted`] policy.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn retry_second_with_budget<P>(
+        self,
+        policy: P,
+        budget: RetryBudget,
+    ) -> Pipeline<A, Retry<B, Budgeted<P>>> {
+        self.retry_second(Budgeted::new(policy, budget))
+    }
+
+    /// Erase the type of the pipeline, producing a [`BoxPipeline`].
+    pub fn boxed<R>(self) -> BoxPipeline<R, B::Response, B::Error>
+    where
+        Self: Service<R, Response = B::Response, Error = B::Error> + Send + 'static,
+        <Self as Service<R>>::Future: Send + 'static,
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        BoxPipeline::new(self)
+    }
+
+    /// Erase the type of the pipeline without requiring it to implement [`Send`], producing a
+    /// [`LocalBoxPipeline`].
+    pub fn boxed_local<R>(self) -> LocalBoxPipeline<R, B::Response, B::Error>
+    where
+        Self: Service<R, Response = B::Response, Error = B::Error> + 'static,
+        <Self as Service<R>>::Future: 'static,
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        LocalBoxPipeline::new(self)
+    }
+
+    /// Erase the type of the pipeline, producing a [`BoxClonePipeline`].
+    pub fn boxed_clone<R>(self) -> BoxClonePipeline<R, B::Response, B::Error>
+    where
+        Self: Service<R, Response = B::Response, Error = B::Error> + Clone + Send + 'static,
+        <Self as Service<R>>::Future: Send + 'static,
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        BoxClonePipeline::new(self)
+    }
+
+    /// Call `f` with a reference to the response of the first service before it is passed to the
+    /// second.
+    ///
+    /// This wraps the first service in an [`Inspect`].
+    pub fn inspect<F>(self, f: F) -> Pipeline<Inspect<A, F>, B> {
+        Pipeline {
+            first: Inspect::new(self.first, f),
+            second: self.second,
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Log the [`Debug`] representation of the response of the first service at `level`, along
+    /// with the names of the stages, before it is passed to the second.
+    ///
+    /// The names are the ones set when this is called, so call [`Pipeline::named`] first.
+    /// This wraps the first service in a [`LogIntermediate`], whose
+    /// [`with_max_len`](LogIntermediate::with_max_len) truncates long values.
+    ///
+    /// [`Debug`]: core::fmt::Debug
+    #[cfg(feature = "tracing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+    pub fn log_intermediate(self, level: tracing::Level) -> Pipeline<LogIntermediate<A>, B> {
+        Pipeline {
+            first: LogIntermediate::new(self.first, level).with_names(self.names),
+            second: self.second,
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Include the response of the first service in errors of the second, producing a
+    /// [`CarryIntermediate`].
+    ///
+    /// Errors are reported as a [`PipelineError`] where [`PipelineError::Second`] holds a
+    /// [`SecondError`] with the intermediate value. Like [`Pipeline::with_split_errors`] the
+    /// errors of the two services don't have to be related.
+    pub fn carry_intermediate(self) -> CarryIntermediate<A, B> {
+        CarryIntermediate::new(self.first, self.second)
+    }
+
+    /// Check the response of the first service with `validator` before it is passed to the
+    /// second, producing a [`Validate`].
+    ///
+    /// Rejected responses fail with [`PipelineError::Invalid`] without calling the second
+    /// service. Like [`Pipeline::with_split_errors`] the errors of the two services don't have to
+    /// be related.
+    pub fn validate<F>(self, validator: F) -> Validate<A, B, F> {
+        Validate::new(self.first, self.second, validator)
+    }
+
+    /// Cache the responses of the first service, keyed by the request, so it isn't called again
+    /// for requests it has already responded to.
+    ///
+    /// At most `capacity` responses are cached, evicting the least recently used. This wraps the
+    /// first service in a [`Memoize`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn memoize_first<R>(self, capacity: usize) -> Pipeline<Memoize<A, R, A::Response>, B>
+    where
+        A: Service<R>,
+    {
+        Pipeline {
+            first: Memoize::new(self.first, capacity),
+            second: self.second,
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Consult `cache` before calling the first service, and store its responses in `cache`,
+    /// expiring after `ttl` if it is `Some`.
+    ///
+    /// This wraps the first service in a [`Cached`].
+    pub fn cache_first<C>(
+        self,
+        cache: C,
+        ttl: Option<core::time::Duration>,
+    ) -> Pipeline<Cached<A, C>, B> {
+        Pipeline {
+            first: Cached::new(self.first, cache, ttl),
+            second: self.second,
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Share a single call to the second service between concurrent calls whose intermediate
+    /// values have the same key, as extracted by `key`.
+    ///
+    /// This wraps the second service in a [`Singleflight`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn singleflight_second<T, F, K>(
+        self,
+        key: F,
+    ) -> Pipeline<A, Singleflight<B, F, K, B::Response>>
+    where
+        B: Service<T>,
+        F: Fn(&T) -> K,
+    {
+        Pipeline {
+            first: self.first,
+            second: Singleflight::new(self.second, key),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Capture the request and response of each successful call of the first service in
+    /// `store`.
+    ///
+    /// This wraps the first service in a [`Record`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn record_first<St>(self, store: St) -> Pipeline<Record<A, St>, B> {
+        Pipeline {
+            first: Record::new(self.first, store),
+            second: self.second,
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Capture the request and response of each successful call of the second service in
+    /// `store`.
+    ///
+    /// This wraps the second service in a [`Record`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn record_second<St>(self, store: St) -> Pipeline<A, Record<B, St>> {
+        Pipeline {
+            first: self.first,
+            second: Record::new(self.second, store),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Check the response of the first service with an async predicate before it is passed to
+    /// the second.
+    ///
+    /// If the predicate fails the second service isn't called. This wraps the second service in
+    /// an [`AsyncFilter`].
+    pub fn filter<P>(self, predicate: P) -> Pipeline<A, AsyncFilter<B, P>> {
+        Pipeline {
+            first: self.first,
+            second: AsyncFilter::new(self.second, predicate),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Only call the second service when the first service responds with
+    /// [`ControlFlow::Continue`], producing a [`ShortCircuit`].
+    ///
+    /// [`ControlFlow::Break`] is returned without calling the second service.
+    ///
+    /// [`ControlFlow::Continue`]: core::ops::ControlFlow::Continue
+    /// [`ControlFlow::Break`]: core::ops::ControlFlow::Break
+    pub fn short_circuit(self) -> ShortCircuit<A, B> {
+        ShortCircuit::new(self.first, self.second)
+    }
+
+    /// Return the response of the first service along with the response of the second,
+    /// producing a [`KeepIntermediate`].
+    pub fn keep_intermediate(self) -> KeepIntermediate<A, B> {
+        KeepIntermediate::new(self.first, self.second)
+    }
+
+    /// Pass the original request to the second service along with the response of the first,
+    /// producing a [`WithContext`].
+    pub fn with_context(self) -> WithContext<A, B> {
+        WithContext::new(self.first, self.second)
+    }
+
+    /// Pass the result of the first service to the second, rather than failing if the first
+    /// service fails, producing a [`WithResults`].
+    pub fn with_results(self) -> WithResults<A, B> {
+        WithResults::new(self.first, self.second)
+    }
+
+    /// Compute the `tower::load::Load` of the pipeline with `strategy`, producing a
+    /// [`WithLoad`].
+    ///
+    /// Without it the load of a pipeline is the load of the first service.
+    #[cfg(feature = "load")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "load")))]
+    pub fn with_load<L>(self, strategy: L) -> WithLoad<A, B, L> {
+        WithLoad::new(self, strategy)
+    }
+
+    /// Call `hooks` throughout the lifecycle of every call, producing a [`Hooked`].
+    pub fn hooks<H>(self, hooks: H) -> Hooked<A, B, H> {
+        Hooked::new(self, hooks)
+    }
+
+    /// Pass a reference to the response of the first service to the second, rather than moving
+    /// it, producing a [`Borrowing`].
+    pub fn borrowing(self) -> Borrowing<A, B> {
+        Borrowing::new(self.first, self.second)
+    }
+
+    /// Pass the [`Extensions`] of the call to both services, producing a [`WithExtensions`].
+    ///
+    /// Both services receive [`Scoped`] requests.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn with_extensions(self) -> WithExtensions<A, B> {
+        WithExtensions::new(self.first, self.second)
+    }
+
+    /// Carry the extensions and selected headers of an HTTP request across the stage boundary,
+    /// producing a [`PropagateHttp`](http::PropagateHttp).
+    #[cfg(feature = "http")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "http")))]
+    pub fn propagate_http(self) -> http::PropagateHttp<A, B> {
+        http::PropagateHttp::new(self.first, self.second)
+    }
+
+    /// Make each call cancellable with a `CancellationToken`, producing a [`Cancellable`].
+    #[cfg(feature = "tokio-util")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio-util")))]
+    pub fn cancellable(self) -> Cancellable<A, B> {
+        Cancellable::new(self.first, self.second).with_names(self.names)
+    }
+
+    /// Make the pipeline drainable for graceful shutdown, producing a [`Drain`] and a
+    /// [`DrainHandle`] for draining it.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn drainable(self) -> (Drain<Self>, DrainHandle) {
+        Drain::new(self)
+    }
+
+    /// Make the pipeline pausable, producing a [`Pausable`] and a [`PauseHandle`] for pausing
+    /// and resuming it.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn pausable(self) -> (Pausable<Self>, PauseHandle) {
+        Pausable::new(self)
+    }
+
+    /// Make the second service replaceable at runtime, producing a [`Swappable`] and a
+    /// [`StageHandle`] for replacing the second service.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn swappable(self) -> (Swappable<A, B>, StageHandle<B>) {
+        let (swappable, handle) = Swappable::new(self.first, self.second);
+        (swappable.with_names(self.names), handle)
+    }
+
+    /// Call the pipeline from synchronous code, blocking the current thread until the response
+    /// is ready.
+    ///
+    /// The pipeline is driven to readiness and called on a single threaded tokio runtime that is
+    /// created the first time a thread makes a blocking call and reused after that.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within an async runtime.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError};
+    ///
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let mut combined = length_svc.pipeline(double_svc);
+    ///
+    /// assert_eq!(combined.call_blocking("rust").unwrap(), 8);
+    /// ```
+    #[cfg(feature = "blocking")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+    pub fn call_blocking<R>(
+        &mut self,
+        req: R,
+    ) -> Result<<Self as Service<R>>::Response, <Self as Service<R>>::Error>
+    where
+        Self: Service<R>,
+    {
+        blocking::block_on(async move {
+            core::future::poll_fn(|cx| self.poll_ready(cx)).await?;
+            self.call(req).await
+        })
+    }
+
+    /// Call the pipeline with every item of `items`, producing a [`CallIter`] future of the
+    /// results in the same order as the items.
+    ///
+    /// The pipeline is driven to readiness before each call.
+    pub fn call_iter<I>(self, items: I) -> CallIter<I::IntoIter, Self>
+    where
+        I: IntoIterator,
+        Self: Service<I::Item>,
+    {
+        CallIter::new(self, items.into_iter(), Ordering::Preserve)
+    }
+
+    /// Call the pipeline with every item of `items`, producing a [`CallIter`] future of the
+    /// results in the order the calls complete.
+    ///
+    /// The pipeline is driven to readiness before each call.
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread", start_paused = true)]
+    /// # async fn main() {
+    /// let parse_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.parse::<u64>()?)
+    /// });
+    ///
+    /// // takes longer the larger the number is
+    /// let sleep_svc = service_fn(|n: u64| async move {
+    ///     tokio::time::sleep(Duration::from_millis(n * 50)).await;
+    ///     Ok::<_, BoxError>(n)
+    /// });
+    ///
+    /// let results = parse_svc
+    ///     .pipeline(sleep_svc)
+    ///     .call_iter_unordered(vec!["3", "1", "2"])
+    ///     .await
+    ///     .into_iter()
+    ///     .map(Result::unwrap)
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(results, vec![1, 2, 3]);
+    /// # }
+    /// ```
+    pub fn call_iter_unordered<I>(self, items: I) -> CallIter<I::IntoIter, Self>
+    where
+        I: IntoIterator,
+        Self: Service<I::Item>,
+    {
+        CallIter::new(self, items.into_iter(), Ordering::Unordered)
+    }
+
+    /// Call the pipeline with every item of a stream, producing a [`CallStream`] of responses.
+    ///
+    /// The first service processes the next request while the second service processes the
+    /// previous one.
+    pub fn call_stream<St>(self, requests: St) -> CallStream<St, A, B>
+    where
+        St: futures_util::stream::Stream,
+        A: Service<St::Item>,
+        B: Service<A::Response> + Clone,
+        A::Error: Into<B::Error>,
+    {
+        CallStream::new(self.first, self.second, requests)
+    }
+
+    /// Convert the pipeline into a function from a stream of requests to a [`TransformStream`]
+    /// of responses.
+    ///
+    /// Readiness is driven internally and at most `limit` calls are in flight at a time. A
+    /// `limit` of zero means no limit.
+    pub fn into_stream_transform<St>(self, limit: usize) -> impl Fn(St) -> TransformStream<St, Self>
+    where
+        Self: Service<St::Item> + Clone,
+        St: futures_util::stream::Stream,
+    {
+        self.into_stream_transform_with_ordering(limit, Ordering::Preserve)
+    }
+
+    /// Like [`Pipeline::into_stream_transform`] but the responses are produced in the order given
+    /// by `ordering`.
+    ///
+    /// ```
+    /// use tower_pipeline::{Ordering, PipelineExt};
+    /// use tower::{service_fn, BoxError};
+    /// use futures_util::stream::{self, StreamExt};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread", start_paused = true)]
+    /// # async fn main() {
+    /// let parse_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.parse::<u64>()?)
+    /// });
+    ///
+    /// // takes longer the larger the number is
+    /// let sleep_svc = service_fn(|n: u64| async move {
+    ///     tokio::time::sleep(Duration::from_millis(n * 50)).await;
+    ///     Ok::<_, BoxError>(n)
+    /// });
+    ///
+    /// let transform = parse_svc
+    ///     .pipeline(sleep_svc)
+    ///     .into_stream_transform_with_ordering(2, Ordering::Unordered);
+    ///
+    /// let results = transform(stream::iter(vec!["4", "1", "2"]))
+    ///     .map(Result::unwrap)
+    ///     .collect::<Vec<_>>()
+    ///     .await;
+    ///
+    /// // `2` is only called once `1` is done, but still completes before `4`
+    /// assert_eq!(results, vec![1, 2, 4]);
+    /// # }
+    /// ```
+    pub fn into_stream_transform_with_ordering<St>(
+        self,
+        limit: usize,
+        ordering: Ordering,
+    ) -> impl Fn(St) -> TransformStream<St, Self>
+    where
+        Self: Service<St::Item> + Clone,
+        St: futures_util::stream::Stream,
+    {
+        move |requests| TransformStream::new(self.clone(), requests, limit, ordering)
+    }
+
+    /// Measure the latency of each stage, producing a [`Timed`].
+    ///
+    /// `on_timings` is called with the [`Timings`] of each call when it completes.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn timed<F>(self, on_timings: F) -> Timed<A, B, F>
+    where
+        F: FnOnce(Timings) + Clone,
+    {
+        Timed::new(self.first, self.second, on_timings).with_names(self.names)
+    }
+
+    /// Measure the latency of each stage and return the [`Timings`] along with the response,
+    /// producing an [`Instrumented`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn instrumented(self) -> Instrumented<A, B> {
+        Instrumented::new(self.first, self.second).with_names(self.names)
+    }
+
+    /// Run the second service on a spawned task and send calls to it over a channel, producing a
+    /// [`Buffer`].
+    ///
+    /// The second service doesn't have to implement [`Clone`] and at most `capacity` calls wait
+    /// for it to become ready. This sets the [`ReadinessMode`] to [`ReadinessMode::Buffered`],
+    /// so `poll_ready` waits for capacity in the channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or if called outside a tokio runtime.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn buffered<T>(self, capacity: usize) -> Pipeline<A, Buffer<T, B::Future>>
+    where
+        B: Service<T> + Send + 'static,
+        B::Error: Into<BoxError>,
+        B::Future: Send + 'static,
+        T: Send + 'static,
+    {
+        Pipeline {
+            first: self.first,
+            second: Buffer::new(self.second, capacity),
+            readiness: ReadinessMode::Buffered,
+            names: self.names,
+        }
+    }
+
+    /// Collect intermediate values into batches and call the second service with them on a
+    /// spawned task, producing a [`Batch`].
+    ///
+    /// The second service must accept a `Vec` of intermediate values and respond with a `Vec`
+    /// of responses in the same order. A batch is sent once it contains `max_size` values or
+    /// `max_delay` after its first value arrived.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_size` is zero or if called outside a tokio runtime.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn batch_second<T, U>(
+        self,
+        max_size: usize,
+        max_delay: core::time::Duration,
+    ) -> Pipeline<A, Batch<T, U>>
+    where
+        B: Service<Vec<T>, Response = Vec<U>> + Send + 'static,
+        B::Error: Into<BoxError>,
+        B::Future: Send,
+        T: Send + 'static,
+        U: Send + 'static,
+    {
+        Pipeline {
+            first: self.first,
+            second: Batch::new(self.second, max_size, max_delay),
+            readiness: ReadinessMode::Buffered,
+            names: self.names,
+        }
+    }
+
+    /// Like [`Pipeline::buffered`] but waiting intermediate values are sent to the second
+    /// service in order of priority, as extracted by `priority`.
+    ///
+    /// See [`Buffer::new_with_priority`] for more details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or if called outside a tokio runtime.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn buffered_with_priority<T, P, K>(
+        self,
+        capacity: usize,
+        priority: P,
+    ) -> Pipeline<A, Buffer<T, B::Future>>
+    where
+        B: Service<T> + Send + 'static,
+        B::Error: Into<BoxError>,
+        B::Future: Send + 'static,
+        P: Fn(&T) -> K + Send + 'static,
+        K: Ord + Send + 'static,
+        T: Send + 'static,
+    {
+        Pipeline {
+            first: self.first,
+            second: Buffer::new_with_priority(self.second, capacity, priority),
+            readiness: ReadinessMode::Buffered,
+            names: self.names,
+        }
+    }
+
+    /// Run each service on its own spawned task, producing a [`Parallel`].
+    ///
+    /// While the second service handles one call the first service can handle the next, at most
+    /// `capacity` calls are in flight.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or if called outside a tokio runtime.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn parallel<R>(self, capacity: usize) -> Parallel<R, B::Response>
+    where
+        A: Service<R> + Send + 'static,
+        A::Error: Into<BoxError>,
+        A::Future: Send,
+        A::Response: Send + 'static,
+        B: Service<A::Response> + Send + 'static,
+        B::Error: Into<BoxError>,
+        B::Future: Send,
+        B::Response: Send + 'static,
+        R: Send + 'static,
+    {
+        Parallel::new(self.first, self.second, capacity)
+    }
+
+    /// Like [`Pipeline::parallel`] but responses of the first service waiting for the second
+    /// service are sent to it in order of priority, as extracted by `priority`.
+    ///
+    /// See [`Parallel::new_with_priority`] for more details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or if called outside a tokio runtime.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn parallel_with_priority<R, P, K>(
+        self,
+        capacity: usize,
+        priority: P,
+    ) -> Parallel<R, B::Response>
+    where
+        A: Service<R> + Send + 'static,
+        A::Error: Into<BoxError>,
+        A::Future: Send,
+        A::Response: Send + 'static,
+        B: Service<A::Response> + Send + 'static,
+        B::Error: Into<BoxError>,
+        B::Future: Send,
+        B::Response: Send + 'static,
+        P: Fn(&A::Response) -> K + Send + 'static,
+        K: Ord + Send + 'static,
+        R: Send + 'static,
+    {
+        Parallel::new_with_priority(self.first, self.second, capacity, priority)
+    }
+
+    /// Like [`Pipeline::buffered`] but the worker is spawned on the current [`LocalSet`], so
+    /// the second service and its futures don't have to implement [`Send`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or if called outside a [`LocalSet`].
+    ///
+    /// [`LocalSet`]: tokio::task::LocalSet
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn buffered_local<T>(self, capacity: usize) -> Pipeline<A, Buffer<T, B::Future>>
+    where
+        B: Service<T> + 'static,
+        B::Error: Into<BoxError>,
+        B::Future: 'static,
+        T: 'static,
+    {
+        Pipeline {
+            first: self.first,
+            second: Buffer::new_local(self.second, capacity),
+            readiness: ReadinessMode::Buffered,
+            names: self.names,
+        }
+    }
+
+    /// Like [`Pipeline::parallel`] but the workers are spawned on the current [`LocalSet`], so
+    /// the services and their futures don't have to implement [`Send`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or if called outside a [`LocalSet`].
+    ///
+    /// [`LocalSet`]: tokio::task::LocalSet
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn parallel_local<R>(self, capacity: usize) -> Parallel<R, B::Response>
+    where
+        A: Service<R> + 'static,
+        A::Error: Into<BoxError>,
+        A::Response: 'static,
+        B: Service<A::Response> + 'static,
+        B::Error: Into<BoxError>,
+        B::Response: 'static,
+        R: 'static,
+    {
+        Parallel::new_local(self.first, self.second, capacity)
+    }
+
+    /// Fail requests where the first service takes longer than `timeout`.
+    ///
+    /// This wraps the first service in a [`Timeout`].
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn timeout_first(self, timeout: core::time::Duration) -> Pipeline<Timeout<A>, B> {
+        Pipeline {
+            first: Timeout::new(self.first, timeout, Stage::First).with_name(self.names.first()),
+            second: self.second,
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Fail requests where the second service takes longer than `timeout`.
+    ///
+    /// This wraps the second service in a [`Timeout`].
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn timeout_second(self, timeout: core::time::Duration) -> Pipeline<A, Timeout<B>> {
+        Pipeline {
+            first: self.first,
+            second: Timeout::new(self.second, timeout, Stage::Second)
+                .with_name(self.names.second()),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Fail requests where both services together take longer than `budget`, producing a
+    /// [`Deadline`].
+    ///
+    /// Unlike [`Pipeline::timeout_first`] and [`Pipeline::timeout_second`] the time spent in the
+    /// first service counts against the second.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn deadline(self, budget: core::time::Duration) -> Deadline<A, B> {
+        Deadline::new(self, budget)
+    }
+
+    /// Enforce the deadline carried by the response of the first service on the second
+    /// service.
+    ///
+    /// This wraps the second service in a [`PropagateDeadline`].
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn propagate_deadline(self) -> Pipeline<A, PropagateDeadline<B>> {
+        Pipeline {
+            first: self.first,
+            second: PropagateDeadline::new(self.second).with_name(self.names.second()),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Drive the second service to readiness on a spawned task rather than in the poll loop of
+    /// the response future, so slow readiness work doesn't stall it.
+    ///
+    /// This wraps the second service in a [`SpawnReady`].
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn spawn_ready_second(self) -> Pipeline<A, SpawnReady<B>> {
+        Pipeline {
+            first: self.first,
+            second: SpawnReady::new(self.second),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Call the second service once for each item in the response of the first service,
+    /// producing a [`ForEachConcurrent`].
+    ///
+    /// At most `limit` calls to the second service are in flight at a time and the responses are
+    /// collected in order, unless changed with [`ForEachConcurrent::with_ordering`]. A `limit` of
+    /// zero means no limit.
+    pub fn for_each_concurrent(self, limit: usize) -> ForEachConcurrent<A, B> {
+        ForEachConcurrent::new(self.first, self.second, limit)
+    }
+
+    /// Call the second service once for each item of the stream the first service responds
+    /// with, producing a [`ForEachStream`].
+    ///
+    /// The response is a stream of the results of the second service. At most `limit` calls are
+    /// in flight at a time, and a `limit` of zero means no limit.
+    pub fn for_each_stream(self, limit: usize) -> ForEachStream<A, B> {
+        ForEachStream::new(self.first, self.second, limit)
+    }
+
+    /// Feed the response of the pipeline back as the next request while `predicate` returns
+    /// `true`, producing a [`Loop`].
+    ///
+    /// The response type of the pipeline must be the same as its request type.
+    pub fn loop_while<P>(self, predicate: P) -> Loop<Self, P> {
+        Loop::new(self, predicate)
+    }
+
+    /// Send the response of the pipeline to `if_true` or `if_false` depending on `predicate`,
+    /// producing a [`Branch`].
+    ///
+    /// Only the service that is picked is driven to readiness and called.
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let small_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(format!("{} is small", input))
+    /// });
+    ///
+    /// let large_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(format!("{} is large", input))
+    /// });
+    ///
+    /// let combined = length_svc
+    ///     .pipeline(double_svc)
+    ///     .branch(|n: &usize| *n < 10, small_svc, large_svc);
+    ///
+    /// assert_eq!(combined.clone().oneshot("rust").await.unwrap(), "8 is small");
+    /// assert_eq!(combined.oneshot("tower").await.unwrap(), "10 is large");
+    /// # }
+    /// ```
+    pub fn branch<P, T, F>(self, predicate: P, if_true: T, if_false: F) -> Branch<Self, P, T, F> {
+        Branch::new(self, predicate, if_true, if_false)
+    }
+
+    /// Send the response of the pipeline to the service in `seconds` picked by `picker`,
+    /// producing a [`Steer`].
+    ///
+    /// The picker is called with the response and the services and returns the index of the
+    /// service to call.
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let shard_svc = |shard: usize| {
+    ///     service_fn(move |input: usize| async move {
+    ///         Ok::<_, BoxError>(format!("{} handled by shard {}", input, shard))
+    ///     })
+    /// };
+    ///
+    /// let combined = length_svc.pipeline(double_svc).steer(
+    ///     |n: &usize, shards: &[_]| n % shards.len(),
+    ///     vec![shard_svc(0), shard_svc(1), shard_svc(2)],
+    /// );
+    ///
+    /// let result = combined.oneshot("rust").await.unwrap();
+    ///
+    /// assert_eq!(result, "8 handled by shard 2");
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Calls panic if the picker returns an index that is out of bounds.
+    pub fn steer<P, S>(self, picker: P, seconds: Vec<S>) -> Steer<Self, P, S> {
+        Steer::new(self, picker, seconds)
+    }
+
+    /// Limit the number of calls in flight for each stage independently.
+    ///
+    /// At most `first_max` calls are in flight in the first service and at most `second_max` in
+    /// the second service. This wraps both services in a [`ConcurrencyLimit`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn concurrency_limit(
+        self,
+        first_max: usize,
+        second_max: usize,
+    ) -> Pipeline<ConcurrencyLimit<A>, ConcurrencyLimit<B>> {
+        Pipeline {
+            first: ConcurrencyLimit::new(self.first, first_max),
+            second: ConcurrencyLimit::new(self.second, second_max),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Send a second, hedged, call to the second service if it hasn't responded within `delay`.
+    ///
+    /// This wraps the second service in a [`Hedge`], which requires the response of the first
+    /// service to implement [`Clone`].
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn hedge_second(self, delay: core::time::Duration) -> Pipeline<A, Hedge<B>> {
+        Pipeline {
+            first: self.first,
+            second: Hedge::new(self.second, delay),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Stop calling the second service after repeated failures, failing immediately with a
+    /// [`CircuitOpen`] error instead.
+    ///
+    /// This wraps the second service in a [`CircuitBreaker`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn circuit_breaker(self, config: CircuitBreakerConfig) -> Pipeline<A, CircuitBreaker<B>> {
+        Pipeline {
+            first: self.first,
+            second: CircuitBreaker::new(self.second, config, Stage::Second)
+                .with_name(self.names.second()),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Admit at most `num` calls to the second service per `per`.
+    ///
+    /// Calls over the limit wait inside the response future. This wraps the second service in a
+    /// [`RateLimit`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num` is zero.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn rate_limit_second(
+        self,
+        num: u64,
+        per: core::time::Duration,
+    ) -> Pipeline<A, RateLimit<B>> {
+        Pipeline {
+            first: self.first,
+            second: RateLimit::new(self.second, num, per),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Fail calls immediately with an [`Overloaded`] error if the second service isn't ready,
+    /// rather than waiting inside the response future.
+    ///
+    /// This wraps the second service in a [`LoadShed`].
+    pub fn load_shed_second(self) -> Pipeline<A, LoadShed<B>> {
+        Pipeline {
+            first: self.first,
+            second: LoadShed::new(self.second, Stage::Second).with_name(self.names.second()),
+            readiness: self.readiness,
+            names: self.names,
+        }
+    }
+
+    /// Apply a closure to the response of the first service before it is passed to the second,
+    /// producing a [`PipelineMap`].
+    pub fn with_adapter<F>(self, adapter: F) -> PipelineMap<A, F, B> {
+        PipelineMap::new(self.first, adapter, self.second)
+    }
+
+    /// Apply an async closure to the response of the first service before it is passed to the
+    /// second, producing an [`AndThen`].
+    pub fn and_then_async<F>(self, f: F) -> AndThen<A, F, B> {
+        AndThen::new(self.first, f, self.second)
+    }
 }
 
 impl<R, A, B> Service<R> for Pipeline<A, B>
@@ -110,21 +1933,144 @@ where
     type Future = ResponseFuture<R, A, B>;
 
     fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        self.first.poll_ready(cx).map_err(Into::into)
+        let _ready: () = ready!(self.first.poll_ready(cx).map_err(Into::into)?);
+        match self.readiness {
+            ReadinessMode::Lazy => Poll::Ready(Ok(())),
+            ReadinessMode::Eager | ReadinessMode::Buffered => self.second.poll_ready(cx),
+        }
     }
 
     fn call(&mut self, req: R) -> Self::Future {
-        ResponseFuture {
-            state: State::FirstFuturePending {
-                future: self.first.call(req),
-            },
-            second: Some(self.second.clone()),
+        let second = self.second_for_call();
+        ResponseFuture::new(&mut self.first, req, second, self.names)
+    }
+}
+
+impl<A, B> Pipeline<A, B>
+where
+    B: Clone,
+{
+    /// The second service to hand to the response future of a call.
+    pub(crate) fn second_for_call(&mut self) -> B {
+        match self.readiness {
+            ReadinessMode::Lazy => self.second.clone(),
+            // the readiness of `self.second` doesn't carry over to clones, so hand the ready
+            // service to the response future and keep the clone
+            ReadinessMode::Eager | ReadinessMode::Buffered => {
+                let clone = self.second.clone();
+                core::mem::replace(&mut self.second, clone)
+            }
         }
     }
 }
 
 pin_project! {
     /// Response future of [`Pipeline`].
+    ///
+    /// It implements [`FusedFuture`] so it can be used in `select!` loops, which skip futures
+    /// that have already completed.
+    ///
+    /// ```
+    /// use futures_util::future::FusedFuture;
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError, Service, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let mut combined = length_svc.pipeline(double_svc);
+    ///
+    /// let future = combined.ready().await.unwrap().call("rust");
+    /// tokio::pin!(future);
+    /// assert!(!future.is_terminated());
+    ///
+    /// assert_eq!(future.as_mut().await.unwrap(), 8);
+    /// assert!(future.is_terminated());
+    /// # }
+    /// ```
+    ///
+    /// Futures that have completed are skipped on later iterations of a `select!` loop rather
+    /// than polled again:
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError, Service, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let mut combined = length_svc.pipeline(double_svc);
+    ///
+    /// let rust = combined.ready().await.unwrap().call("rust");
+    /// let hello = combined.ready().await.unwrap().call("hello");
+    /// tokio::pin!(rust, hello);
+    ///
+    /// let mut results = Vec::new();
+    /// loop {
+    ///     futures::select! {
+    ///         result = rust => results.push(result.unwrap()),
+    ///         result = hello => results.push(result.unwrap()),
+    ///         complete => break,
+    ///     }
+    /// }
+    ///
+    /// results.sort();
+    /// assert_eq!(results, vec![8, 10]);
+    /// # }
+    /// ```
+    ///
+    /// It is [`Unpin`] if the response futures of both services are, so it can be stored in
+    /// plain structs and awaited through `&mut`:
+    ///
+    /// ```
+    /// use futures_util::future::{ready, Ready};
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError, Service, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| ready(Ok::<_, BoxError>(input.len())));
+    /// let double_svc = service_fn(|input: usize| ready(Ok::<_, BoxError>(input * 2)));
+    ///
+    /// let mut combined = length_svc.pipeline(double_svc);
+    ///
+    /// let mut future = combined.ready().await.unwrap().call("rust");
+    ///
+    /// assert_eq!((&mut future).await.unwrap(), 8);
+    /// # }
+    /// ```
+    ///
+    /// But not if either of them isn't:
+    ///
+    /// ```compile_fail
+    /// use futures_util::future::ready;
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError, Service};
+    ///
+    /// fn assert_unpin<T: Unpin>(_: &T) {}
+    ///
+    /// let length_svc = service_fn(|input: &'static str| ready(Ok::<_, BoxError>(input.len())));
+    /// // `async` blocks aren't `Unpin`
+    /// let double_svc = service_fn(|input: usize| async move { Ok::<_, BoxError>(input * 2) });
+    ///
+    /// let future = length_svc.pipeline(double_svc).call("rust");
+    ///
+    /// assert_unpin(&future);
+    /// ```
     pub struct ResponseFuture<R, A, B>
     where
         A: Service<R>,
@@ -132,7 +2078,8 @@ pin_project! {
     {
         #[pin]
         state: State<R, A, B>,
-        second: Option<B>,
+        names: StageNames,
+        span: StageSpan,
     }
 }
 
@@ -143,9 +2090,134 @@ pin_project! {
         A: Service<R>,
         B: Service<A::Response>,
     {
-        FirstFuturePending { #[pin] future: A::Future },
-        PollReadySecond { first_res: Option<A::Response>, second: B },
-        SecondFuturePending { #[pin] future: B::Future },
+        FirstFuturePending { #[pin] future: A::Future, second: Option<B> },
+        SecondPending { #[pin] future: Oneshot<B, A::Response> },
+        Done,
+    }
+}
+
+impl<R, A, B> ResponseFuture<R, A, B>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+{
+    pub(crate) fn new(first: &mut A, req: R, second: B, names: StageNames) -> Self {
+        let span = StageSpan::new(Stage::First, names);
+        let future = span.in_scope(|| first.call(req));
+
+        ResponseFuture {
+            state: State::FirstFuturePending {
+                future,
+                second: Some(second),
+            },
+            names,
+            span,
+        }
+    }
+
+    /// How far the future has progressed.
+    ///
+    /// Useful for logging calls that are stuck, along with the [`Debug`](fmt::Debug) output of
+    /// the future which includes the stage.
+    ///
+    /// ```
+    /// use tower_pipeline::{PipelineExt, ResponseStage};
+    /// use tower::{service_fn, BoxError, Service, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let mut combined = length_svc.pipeline(double_svc);
+    ///
+    /// let future = combined.ready().await.unwrap().call("rust");
+    /// tokio::pin!(future);
+    /// assert_eq!(future.stage(), ResponseStage::FirstPending);
+    ///
+    /// future.as_mut().await.unwrap();
+    /// assert_eq!(future.stage(), ResponseStage::Done);
+    /// # }
+    /// ```
+    pub fn stage(&self) -> ResponseStage {
+        match self.state {
+            State::FirstFuturePending { .. } => ResponseStage::FirstPending,
+            State::SecondPending {
+                future: Oneshot::NotReady { .. },
+            } => ResponseStage::WaitingSecondReady,
+            State::SecondPending {
+                future: Oneshot::Called { .. },
+            } => ResponseStage::SecondPending,
+            State::Done => ResponseStage::Done,
+        }
+    }
+
+    /// Apply `f` to the response once the future completes successfully.
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError, Service, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let mut combined = length_svc.pipeline(double_svc);
+    ///
+    /// let future = combined.ready().await.unwrap().call("rust");
+    ///
+    /// let result = future.map(|n| n.to_string()).await.unwrap();
+    ///
+    /// assert_eq!(result, "8");
+    /// # }
+    /// ```
+    pub fn map<F, T>(self, f: F) -> futures::MapOk<Self, F>
+    where
+        A::Error: Into<B::Error>,
+        F: FnOnce(B::Response) -> T,
+    {
+        TryFutureExt::map_ok(self, f)
+    }
+
+    /// Apply `f` to the error if the future fails.
+    pub fn map_err<F, E>(self, f: F) -> futures::MapErr<Self, F>
+    where
+        A::Error: Into<B::Error>,
+        F: FnOnce(B::Error) -> E,
+    {
+        TryFutureExt::map_err(self, f)
+    }
+
+    /// Call `f` with a reference to the output once the future completes.
+    pub fn inspect<F>(self, f: F) -> futures::Inspect<Self, F>
+    where
+        A::Error: Into<B::Error>,
+        F: FnOnce(&Result<B::Response, B::Error>),
+    {
+        FutureExt::inspect(self, f)
+    }
+}
+
+impl<R, A, B> fmt::Debug for ResponseFuture<R, A, B>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseFuture")
+            .field("stage", &self.stage())
+            .finish()
     }
 }
 
@@ -158,31 +2230,102 @@ where
     type Output = Result<B::Response, B::Error>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        loop {
+        let output = loop {
             let mut this = self.as_mut().project();
 
             let new_state = match this.state.as_mut().project() {
-                StateProj::FirstFuturePending { future } => {
-                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
-                    let second = this.second.take().unwrap();
-                    State::PollReadySecond {
-                        first_res: Some(first_res),
-                        second,
+                StateProj::FirstFuturePending { future, second } => {
+                    let result = ready!(this.span.in_scope(|| future.poll(cx)));
+                    this.span.record_outcome(&result);
+                    let first_res = match result {
+                        Ok(first_res) => first_res,
+                        Err(err) => break Err(err.into()),
+                    };
+                    let second = second.take().unwrap();
+                    *this.span = StageSpan::new(Stage::Second, *this.names);
+                    State::SecondPending {
+                        future: Oneshot::new(second, first_res),
                     }
                 }
 
-                StateProj::PollReadySecond { first_res, second } => {
-                    let _ready: () = ready!(second.poll_ready(cx)?);
-                    State::SecondFuturePending {
-                        future: second.call(first_res.take().unwrap()),
-                    }
+                // `Oneshot` drives the second service to readiness and then calls it, so
+                // readiness errors and the call are both recorded on the second span
+                StateProj::SecondPending { future } => {
+                    let result = ready!(this.span.in_scope(|| future.poll(cx)));
+                    this.span.record_outcome(&result);
+                    break result;
                 }
 
-                StateProj::SecondFuturePending { future } => return future.poll(cx),
+                StateProj::Done => panic!("`ResponseFuture` polled after completion"),
             };
 
             this.state.set(new_state);
-        }
+        };
+
+        self.project().state.set(State::Done);
+        Poll::Ready(output)
+    }
+}
+
+/// Polling a [`ResponseFuture`] again through `select!` once it has completed doesn't re-enter
+/// it or call the services again:
+///
+/// ```
+/// use futures_util::future::FusedFuture;
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, Service, ServiceExt};
+/// use std::sync::{
+///     atomic::{AtomicUsize, Ordering},
+///     Arc,
+/// };
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let calls = Arc::new(AtomicUsize::new(0));
+///
+/// let length_svc = service_fn({
+///     let calls = Arc::clone(&calls);
+///     move |input: &'static str| {
+///         calls.fetch_add(1, Ordering::SeqCst);
+///         async move { Ok::<_, BoxError>(input.len()) }
+///     }
+/// });
+///
+/// let double_svc = service_fn({
+///     let calls = Arc::clone(&calls);
+///     move |input: usize| {
+///         calls.fetch_add(1, Ordering::SeqCst);
+///         async move { Ok::<_, BoxError>(input * 2) }
+///     }
+/// });
+///
+/// let mut combined = length_svc.pipeline(double_svc);
+///
+/// let future = combined.ready().await.unwrap().call("rust");
+/// tokio::pin!(future);
+///
+/// assert_eq!(future.as_mut().await.unwrap(), 8);
+/// assert!(future.is_terminated());
+///
+/// for _ in 0..2 {
+///     futures::select! {
+///         _ = future => unreachable!("completed future was polled again"),
+///         complete => {}
+///     }
+/// }
+///
+/// assert!(future.is_terminated());
+/// assert_eq!(calls.load(Ordering::SeqCst), 2);
+/// # }
+/// ```
+impl<R, A, B> FusedFuture for ResponseFuture<R, A, B>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+    A::Error: Into<B::Error>,
+{
+    fn is_terminated(&self) -> bool {
+        matches!(self.state, State::Done)
     }
 }
 
@@ -194,6 +2337,105 @@ pub trait PipelineExt<R>: Service<R> {
         Self: Service<R> + Sized,
         B: Service<Self::Response> + Clone,
         Self::Error: Into<B::Error>;
+
+    /// Construct a [`PipelineMap`] that applies `adapter` to the response of `self` before
+    /// passing it to `second`.
+    fn pipeline_map<F, T, B>(self, adapter: F, second: B) -> PipelineMap<Self, F, B>
+    where
+        Self: Service<R> + Sized,
+        F: FnOnce(Self::Response) -> T + Clone,
+        B: Service<T> + Clone,
+        Self::Error: Into<B::Error>;
+
+    /// Construct a [`Pipeline`] that converts the errors of `self` into the error type of
+    /// `second` with `err_fn`.
+    ///
+    /// Useful when the error types aren't related through [`Into`]. `self` is wrapped in a
+    /// [`MapErr`].
+    fn pipeline_map_err<B, F>(self, second: B, err_fn: F) -> Pipeline<MapErr<Self, F>, B>
+    where
+        Self: Service<R> + Sized,
+        B: Service<Self::Response> + Clone,
+        F: FnOnce(Self::Error) -> B::Error + Clone;
+
+    /// Construct a [`Pipeline`] where the second service is an async closure.
+    ///
+    /// The closure returns a plain value rather than a [`Result`] and is wrapped in a
+    /// [`StageFn`] with the same error type as `self`.
+    fn pipeline_fn<G, Fut>(self, second: G) -> Pipeline<Self, StageFn<G, Self::Error>>
+    where
+        Self: Service<R> + Sized,
+        G: FnMut(Self::Response) -> Fut + Clone,
+        Fut: Future;
+
+    /// Construct a [`WithContext`] that passes both the request and the response of `self` to
+    /// `second`.
+    fn pipeline_with_context<B>(self, second: B) -> WithContext<Self, B>
+    where
+        Self: Service<R> + Sized,
+        R: Clone,
+        B: Service<(R, Self::Response)> + Clone,
+        Self::Error: Into<B::Error>;
+
+    /// Construct a [`Branch`] that sends the response of `self` to `if_true` or `if_false`
+    /// depending on `predicate`.
+    fn pipeline_branch<P, B1, B2>(
+        self,
+        predicate: P,
+        if_true: B1,
+        if_false: B2,
+    ) -> Branch<Self, P, B1, B2>
+    where
+        Self: Service<R> + Sized,
+        P: FnOnce(&Self::Response) -> bool + Clone,
+        B1: Service<Self::Response> + Clone,
+        B2: Service<Self::Response, Response = B1::Response, Error = B1::Error> + Clone,
+        Self::Error: Into<B1::Error>;
+
+    /// Construct a [`ScatterGather`] that sends the response of `self` to every service in
+    /// `seconds` and combines their responses with `reducer`.
+    fn pipeline_scatter_gather<B, F, U>(
+        self,
+        seconds: Vec<B>,
+        reducer: F,
+    ) -> ScatterGather<Self, B, F>
+    where
+        Self: Service<R> + Sized,
+        Self::Response: Clone,
+        B: Service<Self::Response> + Clone,
+        F: FnOnce(Vec<B::Response>) -> U + Clone,
+        Self::Error: Into<B::Error>;
+
+    /// Construct a [`Steer`] that sends the response of `self` to the service in `seconds`
+    /// picked by `picker`.
+    fn pipeline_steer<P, B>(self, picker: P, seconds: Vec<B>) -> Steer<Self, P, B>
+    where
+        Self: Service<R> + Sized,
+        P: FnOnce(&Self::Response, &[B]) -> usize + Clone,
+        B: Service<Self::Response> + Clone,
+        Self::Error: Into<B::Error>;
+
+    /// Construct an [`Unzip`] that sends each half of the pair `self` responds with to `left`
+    /// and `right` concurrently.
+    fn pipeline_unzip<X, Y, B1, B2>(self, left: B1, right: B2) -> Unzip<Self, B1, B2>
+    where
+        Self: Service<R, Response = (X, Y)> + Sized,
+        B1: Service<X> + Clone,
+        B2: Service<Y> + Clone,
+        Self::Error: Into<B1::Error>,
+        B2::Error: Into<B1::Error>;
+
+    /// Construct a [`Pipeline`] that writes the responses of `self` into `sink`.
+    ///
+    /// The sink is wrapped in a [`SinkStage`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn pipeline_into_sink<Si>(self, sink: Si) -> Pipeline<Self, SinkStage<Si>>
+    where
+        Self: Service<R> + Sized,
+        Si: Sink<Self::Response>,
+        Si::Error: Into<BoxError>,
+        Self::Error: Into<BoxError>;
 }
 
 impl<R, T> PipelineExt<R> for T
@@ -208,4 +2450,106 @@ where
     {
         Pipeline::new(self, second)
     }
+
+    fn pipeline_map<F, U, B>(self, adapter: F, second: B) -> PipelineMap<Self, F, B>
+    where
+        Self: Service<R> + Sized,
+        F: FnOnce(Self::Response) -> U + Clone,
+        B: Service<U> + Clone,
+        Self::Error: Into<B::Error>,
+    {
+        PipelineMap::new(self, adapter, second)
+    }
+
+    fn pipeline_map_err<B, F>(self, second: B, err_fn: F) -> Pipeline<MapErr<Self, F>, B>
+    where
+        Self: Service<R> + Sized,
+        B: Service<Self::Response> + Clone,
+        F: FnOnce(Self::Error) -> B::Error + Clone,
+    {
+        Pipeline::new(MapErr::new(self, err_fn), second)
+    }
+
+    fn pipeline_fn<G, Fut>(self, second: G) -> Pipeline<Self, StageFn<G, Self::Error>>
+    where
+        Self: Service<R> + Sized,
+        G: FnMut(Self::Response) -> Fut + Clone,
+        Fut: Future,
+    {
+        Pipeline::new(self, StageFn::new(second))
+    }
+
+    fn pipeline_with_context<B>(self, second: B) -> WithContext<Self, B>
+    where
+        Self: Service<R> + Sized,
+        R: Clone,
+        B: Service<(R, Self::Response)> + Clone,
+        Self::Error: Into<B::Error>,
+    {
+        WithContext::new(self, second)
+    }
+
+    fn pipeline_branch<P, B1, B2>(
+        self,
+        predicate: P,
+        if_true: B1,
+        if_false: B2,
+    ) -> Branch<Self, P, B1, B2>
+    where
+        Self: Service<R> + Sized,
+        P: FnOnce(&Self::Response) -> bool + Clone,
+        B1: Service<Self::Response> + Clone,
+        B2: Service<Self::Response, Response = B1::Response, Error = B1::Error> + Clone,
+        Self::Error: Into<B1::Error>,
+    {
+        Branch::new(self, predicate, if_true, if_false)
+    }
+
+    fn pipeline_scatter_gather<B, F, U>(
+        self,
+        seconds: Vec<B>,
+        reducer: F,
+    ) -> ScatterGather<Self, B, F>
+    where
+        Self: Service<R> + Sized,
+        Self::Response: Clone,
+        B: Service<Self::Response> + Clone,
+        F: FnOnce(Vec<B::Response>) -> U + Clone,
+        Self::Error: Into<B::Error>,
+    {
+        ScatterGather::new(self, seconds, reducer)
+    }
+
+    fn pipeline_steer<P, B>(self, picker: P, seconds: Vec<B>) -> Steer<Self, P, B>
+    where
+        Self: Service<R> + Sized,
+        P: FnOnce(&Self::Response, &[B]) -> usize + Clone,
+        B: Service<Self::Response> + Clone,
+        Self::Error: Into<B::Error>,
+    {
+        Steer::new(self, picker, seconds)
+    }
+
+    fn pipeline_unzip<X, Y, B1, B2>(self, left: B1, right: B2) -> Unzip<Self, B1, B2>
+    where
+        Self: Service<R, Response = (X, Y)> + Sized,
+        B1: Service<X> + Clone,
+        B2: Service<Y> + Clone,
+        Self::Error: Into<B1::Error>,
+        B2::Error: Into<B1::Error>,
+    {
+        Unzip::new(self, left, right)
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn pipeline_into_sink<Si>(self, sink: Si) -> Pipeline<Self, SinkStage<Si>>
+    where
+        Self: Service<R> + Sized,
+        Si: Sink<Self::Response>,
+        Si::Error: Into<BoxError>,
+        Self::Error: Into<BoxError>,
+    {
+        Pipeline::new(self, SinkStage::new(sink))
+    }
 }
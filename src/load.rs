@@ -0,0 +1,165 @@
+use crate::{Pipeline, ResponseFuture};
+use core::task::{Context, Poll};
+use tower::load::Load;
+use tower_service::Service;
+
+/// How the load of a pipeline is computed from the loads of its services.
+///
+/// Implemented by [`LoadFirst`], [`LoadSecond`], and closures that take references to both
+/// services.
+pub trait LoadStrategy<A, B> {
+    /// The load metric of the pipeline.
+    type Metric: PartialOrd;
+
+    /// Estimate the load of the pipeline.
+    fn load(&self, first: &A, second: &B) -> Self::Metric;
+}
+
+/// A [`LoadStrategy`] that uses the load of the first service.
+///
+/// This is what [`Load`] for [`Pipeline`] uses.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoadFirst;
+
+impl<A, B> LoadStrategy<A, B> for LoadFirst
+where
+    A: Load,
+{
+    type Metric = A::Metric;
+
+    fn load(&self, first: &A, _second: &B) -> Self::Metric {
+        first.load()
+    }
+}
+
+/// A [`LoadStrategy`] that uses the load of the second service.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoadSecond;
+
+impl<A, B> LoadStrategy<A, B> for LoadSecond
+where
+    B: Load,
+{
+    type Metric = B::Metric;
+
+    fn load(&self, _first: &A, second: &B) -> Self::Metric {
+        second.load()
+    }
+}
+
+impl<A, B, F, M> LoadStrategy<A, B> for F
+where
+    F: Fn(&A, &B) -> M,
+    M: PartialOrd,
+{
+    type Metric = M;
+
+    fn load(&self, first: &A, second: &B) -> Self::Metric {
+        self(first, second)
+    }
+}
+
+impl<A, B> Load for Pipeline<A, B>
+where
+    A: Load,
+{
+    type Metric = A::Metric;
+
+    fn load(&self) -> Self::Metric {
+        LoadFirst.load(&self.first, &self.second)
+    }
+}
+
+/// A [`Pipeline`] whose [`Load`] is computed with a [`LoadStrategy`].
+///
+/// Use it to balance requests between pipelines by the load of another stage than the first,
+/// or by a combination of both.
+///
+/// Created with [`Pipeline::with_load`].
+///
+/// ```
+/// use tower_pipeline::{LoadSecond, Pipeline};
+/// use tower::{load::{Constant, Load}, service_fn, BoxError};
+///
+/// let length_svc = Constant::new(
+///     service_fn(|input: &'static str| async move { Ok::<_, BoxError>(input.len()) }),
+///     1,
+/// );
+///
+/// let double_svc = Constant::new(
+///     service_fn(|input: usize| async move { Ok::<_, BoxError>(input * 2) }),
+///     5,
+/// );
+///
+/// let combined = Pipeline::new(length_svc, double_svc);
+/// assert_eq!(combined.load(), 1);
+///
+/// let combined = combined.with_load(LoadSecond);
+/// assert_eq!(combined.load(), 5);
+///
+/// let combined = combined
+///     .into_inner()
+///     .with_load(|first: &Constant<_, i32>, second: &Constant<_, i32>| {
+///         first.load() + second.load()
+///     });
+/// assert_eq!(combined.load(), 6);
+/// ```
+///
+/// [`Pipeline::with_load`]: crate::Pipeline::with_load
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WithLoad<A, B, L> {
+    inner: Pipeline<A, B>,
+    strategy: L,
+}
+
+impl<A, B, L> WithLoad<A, B, L> {
+    /// Create a new [`WithLoad`] of a [`Pipeline`].
+    pub fn new(inner: Pipeline<A, B>, strategy: L) -> Self {
+        Self { inner, strategy }
+    }
+
+    /// Get a reference to the pipeline.
+    pub fn get_ref(&self) -> &Pipeline<A, B> {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the pipeline.
+    pub fn get_mut(&mut self) -> &mut Pipeline<A, B> {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the pipeline.
+    pub fn into_inner(self) -> Pipeline<A, B> {
+        self.inner
+    }
+}
+
+impl<A, B, L> Load for WithLoad<A, B, L>
+where
+    L: LoadStrategy<A, B>,
+{
+    type Metric = L::Metric;
+
+    fn load(&self) -> Self::Metric {
+        self.strategy.load(&self.inner.first, &self.inner.second)
+    }
+}
+
+impl<R, A, B, L> Service<R> for WithLoad<A, B, L>
+where
+    A: Service<R>,
+    B: Service<A::Response> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = ResponseFuture<R, A, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        self.inner.call(req)
+    }
+}
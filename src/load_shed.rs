@@ -0,0 +1,219 @@
+use crate::{BoxError, Stage};
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use core::error::Error;
+use core::future::Future;
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use pin_project_lite::pin_project;
+#[cfg(feature = "std")]
+use std::error::Error;
+use tower_service::Service;
+
+/// A service that fails calls immediately when the inner service isn't ready, rather than
+/// waiting for it to become ready.
+///
+/// `poll_ready` always succeeds. If the inner service wasn't ready the call fails with an
+/// [`Overloaded`] error that says which stage of the pipeline was overloaded.
+///
+/// Usually created with [`Pipeline::load_shed_second`].
+///
+/// ```
+/// use tower_pipeline::{Overloaded, PipelineExt, Stage};
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use std::task::{Context, Poll};
+///
+/// // a service that is never ready
+/// #[derive(Clone)]
+/// struct Saturated;
+///
+/// impl tower::Service<usize> for Saturated {
+///     type Response = usize;
+///     type Error = BoxError;
+///     type Future = std::future::Ready<Result<usize, BoxError>>;
+///
+///     fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), BoxError>> {
+///         Poll::Pending
+///     }
+///
+///     fn call(&mut self, req: usize) -> Self::Future {
+///         std::future::ready(Ok(req))
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let combined = length_svc.pipeline(Saturated).load_shed_second();
+///
+/// let err = combined.oneshot("rust").await.unwrap_err();
+///
+/// assert_eq!(err.downcast_ref::<Overloaded>().unwrap().stage(), Stage::Second);
+/// # }
+/// ```
+///
+/// [`Pipeline::load_shed_second`]: crate::Pipeline::load_shed_second
+#[derive(Debug)]
+pub struct LoadShed<S> {
+    inner: S,
+    stage: Stage,
+    name: &'static str,
+    is_ready: bool,
+}
+
+impl<S> LoadShed<S> {
+    /// Create a new [`LoadShed`] for the given stage.
+    pub fn new(inner: S, stage: Stage) -> Self {
+        Self {
+            inner,
+            stage,
+            name: stage.default_name(),
+            is_ready: false,
+        }
+    }
+
+    /// Set the name of the stage used in errors.
+    ///
+    /// Defaults to the name of the [`Stage`], `"first"` or `"second"`. [`Pipeline::named`] sets
+    /// this for middleware added to a pipeline afterwards.
+    ///
+    /// [`Pipeline::named`]: crate::Pipeline::named
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Clone for LoadShed<S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        // readiness isn't shared between clones so the clone must be polled again
+        Self::new(self.inner.clone(), self.stage).with_name(self.name)
+    }
+}
+
+impl<S, T> Service<T> for LoadShed<S>
+where
+    S: Service<T>,
+    S::Error: Into<BoxError>,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = LoadShedFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.is_ready = match self.inner.poll_ready(cx) {
+            Poll::Ready(result) => {
+                let _ready: () = result.map_err(Into::into)?;
+                true
+            }
+            Poll::Pending => false,
+        };
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        if core::mem::replace(&mut self.is_ready, false) {
+            LoadShedFuture {
+                state: State::Called {
+                    future: self.inner.call(req),
+                },
+            }
+        } else {
+            LoadShedFuture {
+                state: State::Overloaded {
+                    error: Some(Overloaded {
+                        stage: self.stage,
+                        name: self.name,
+                    }),
+                },
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`LoadShed`].
+    pub struct LoadShedFuture<F> {
+        #[pin]
+        state: State<F>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<F> {
+        Called { #[pin] future: F },
+        Overloaded { error: Option<Overloaded> },
+    }
+}
+
+impl<F, T, E> Future for LoadShedFuture<F>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Into<BoxError>,
+{
+    type Output = Result<T, BoxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().state.project() {
+            StateProj::Called { future } => future.poll(cx).map_err(Into::into),
+            StateProj::Overloaded { error } => {
+                let error = error.take().expect("polled after completion");
+                Poll::Ready(Err(Box::new(error)))
+            }
+        }
+    }
+}
+
+/// Error produced when a stage of a pipeline isn't ready and the call is shed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overloaded {
+    stage: Stage,
+    name: &'static str,
+}
+
+impl Overloaded {
+    /// The stage that was overloaded.
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    /// The name of the stage, as set with [`Pipeline::named`].
+    ///
+    /// [`Pipeline::named`]: crate::Pipeline::named
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl fmt::Display for Overloaded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} service is overloaded", self.name)
+    }
+}
+
+impl Error for Overloaded {}
@@ -0,0 +1,200 @@
+use crate::StageNames;
+use alloc::string::String;
+use core::{
+    fmt::{self, Write},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use tower_service::Service;
+use tracing::Level;
+
+/// A service that logs the [`Debug`] representation of each successful response of the inner
+/// service as a [`tracing`] event.
+///
+/// Usually created with [`Pipeline::log_intermediate`], in which case the intermediate value is
+/// logged before it is passed to the second service, along with the names of both stages. The
+/// event has a `first` and `second` field with the names of the stages and an `intermediate`
+/// field with the value, which can be truncated with [`LogIntermediate::with_max_len`]. The value
+/// is only formatted if an event at the level would be recorded.
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use tracing::Level;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let split_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.split(' ').collect::<Vec<_>>())
+/// });
+///
+/// let count_svc = service_fn(|words: Vec<&'static str>| async move {
+///     Ok::<_, BoxError>(words.len())
+/// });
+///
+/// // logs `["hello", "from", "rust"]` at the debug level
+/// let combined = split_svc
+///     .pipeline(count_svc)
+///     .named("split", "count")
+///     .log_intermediate(Level::DEBUG);
+///
+/// assert_eq!(combined.oneshot("hello from rust").await.unwrap(), 3);
+/// # }
+/// ```
+///
+/// [`Debug`]: core::fmt::Debug
+/// [`tracing`]: https://crates.io/crates/tracing
+/// [`Pipeline::log_intermediate`]: crate::Pipeline::log_intermediate
+#[derive(Debug, Clone, Copy)]
+pub struct LogIntermediate<S> {
+    inner: S,
+    level: Level,
+    max_len: Option<usize>,
+    names: StageNames,
+}
+
+impl<S> LogIntermediate<S> {
+    /// Create a new [`LogIntermediate`] that logs at `level`.
+    pub fn new(inner: S, level: Level) -> Self {
+        Self {
+            inner,
+            level,
+            max_len: None,
+            names: StageNames::default(),
+        }
+    }
+
+    /// Truncate the logged value to at most `max_len` characters.
+    ///
+    /// Values that are truncated end with `...`. By default values aren't truncated.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Set the names of the stages included in the events.
+    ///
+    /// Defaults to `"first"` and `"second"`.
+    pub fn with_names(mut self, names: StageNames) -> Self {
+        self.names = names;
+        self
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<R, S> Service<R> for LogIntermediate<S>
+where
+    S: Service<R>,
+    S::Response: fmt::Debug,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = LogIntermediateFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        LogIntermediateFuture {
+            future: self.inner.call(req),
+            level: self.level,
+            max_len: self.max_len,
+            names: self.names,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`LogIntermediate`].
+    #[derive(Debug)]
+    pub struct LogIntermediateFuture<F> {
+        #[pin]
+        future: F,
+        level: Level,
+        max_len: Option<usize>,
+        names: StageNames,
+    }
+}
+
+impl<F, T, E> Future for LogIntermediateFuture<F>
+where
+    F: Future<Output = Result<T, E>>,
+    T: fmt::Debug,
+{
+    type Output = Result<T, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let result = ready!(this.future.poll(cx));
+        if let Ok(res) = &result {
+            log(*this.level, *this.names, res, *this.max_len);
+        }
+        Poll::Ready(result)
+    }
+}
+
+fn format<T>(value: &T, max_len: Option<usize>) -> String
+where
+    T: fmt::Debug,
+{
+    let mut formatted = String::new();
+    // writing to a `String` never fails
+    let _ = write!(formatted, "{:?}", value);
+
+    if let Some(max_len) = max_len {
+        if let Some((idx, _)) = formatted.char_indices().nth(max_len) {
+            formatted.truncate(idx);
+            formatted.push_str("...");
+        }
+    }
+
+    formatted
+}
+
+fn log<T>(level: Level, names: StageNames, value: &T, max_len: Option<usize>)
+where
+    T: fmt::Debug,
+{
+    // the level of `tracing::event!` has to be a constant
+    macro_rules! event {
+        ($level:expr) => {
+            // formatting the value isn't free, so skip it if the event would be discarded
+            if tracing::enabled!($level) {
+                let intermediate = format(value, max_len);
+                tracing::event!(
+                    $level,
+                    first = names.first(),
+                    second = names.second(),
+                    intermediate = intermediate.as_str(),
+                    "intermediate value"
+                )
+            }
+        };
+    }
+
+    match level {
+        Level::TRACE => event!(Level::TRACE),
+        Level::DEBUG => event!(Level::DEBUG),
+        Level::INFO => event!(Level::INFO),
+        Level::WARN => event!(Level::WARN),
+        _ => event!(Level::ERROR),
+    }
+}
@@ -0,0 +1,149 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// A service whose response is fed back as the next request for as long as a predicate returns
+/// `true`.
+///
+/// The predicate is called with each response. Once it returns `false` that response is
+/// returned. The inner service is driven to readiness before each call after the first.
+///
+/// Created with [`Pipeline::loop_while`] or [`Loop::new`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// // fetch a page and return the token for the next one, along with all items so far
+/// let fetch_svc = service_fn(|(page, mut items): (usize, Vec<usize>)| async move {
+///     items.push(page * 10);
+///     Ok::<_, BoxError>((page + 1, items))
+/// });
+///
+/// // persist the items fetched so far
+/// let store_svc = service_fn(|(page, items): (usize, Vec<usize>)| async move {
+///     Ok::<_, BoxError>((page, items))
+/// });
+///
+/// let combined = fetch_svc
+///     .pipeline(store_svc)
+///     .loop_while(|(page, _): &(usize, Vec<usize>)| *page < 3);
+///
+/// let (_, items) = combined.oneshot((0, Vec::new())).await.unwrap();
+///
+/// assert_eq!(items, vec![0, 10, 20]);
+/// # }
+/// ```
+///
+/// [`Pipeline::loop_while`]: crate::Pipeline::loop_while
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Loop<S, P> {
+    inner: S,
+    predicate: P,
+}
+
+impl<S, P> Loop<S, P> {
+    /// Create a new [`Loop`] that calls `inner` again with its own response while `predicate`
+    /// returns `true`.
+    pub fn new(inner: S, predicate: P) -> Self {
+        Self { inner, predicate }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, P, T> Service<T> for Loop<S, P>
+where
+    S: Service<T, Response = T> + Clone,
+    P: FnMut(&T) -> bool + Clone,
+{
+    type Response = T;
+    type Error = S::Error;
+    type Future = LoopFuture<S, P, T>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        LoopFuture {
+            state: State::FirstCallPending {
+                future: self.inner.call(req),
+            },
+            inner: self.inner.clone(),
+            predicate: self.predicate.clone(),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Loop`].
+    pub struct LoopFuture<S, P, T>
+    where
+        S: Service<T>,
+    {
+        #[pin]
+        state: State<S, T>,
+        inner: S,
+        predicate: P,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<S, T>
+    where
+        S: Service<T>,
+    {
+        FirstCallPending { #[pin] future: S::Future },
+        NextCallPending { #[pin] future: Oneshot<S, T> },
+    }
+}
+
+impl<S, P, T> Future for LoopFuture<S, P, T>
+where
+    S: Service<T, Response = T> + Clone,
+    P: FnMut(&T) -> bool,
+{
+    type Output = Result<T, S::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let res = match this.state.as_mut().project() {
+                StateProj::FirstCallPending { future } => ready!(future.poll(cx)?),
+                StateProj::NextCallPending { future } => ready!(future.poll(cx)?),
+            };
+
+            if !(this.predicate)(&res) {
+                return Poll::Ready(Ok(res));
+            }
+
+            this.state.set(State::NextCallPending {
+                future: Oneshot::new(this.inner.clone(), res),
+            });
+        }
+    }
+}
@@ -0,0 +1,40 @@
+/// Compose any number of services into a [`Pipeline`](crate::Pipeline).
+///
+/// `pipeline!(a, b, c)` expands to `Pipeline::new(a, Pipeline::new(b, c))`. Nesting to the right
+/// means every stage's error only has to convert into the error of the _last_ service, rather
+/// than into the error of the stage directly after it.
+///
+/// ```
+/// use tower_pipeline::pipeline;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let to_string_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input.to_string())
+/// });
+///
+/// let combined = pipeline!(length_svc, double_svc, to_string_svc);
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, "8");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! pipeline {
+    ($svc:expr $(,)?) => {
+        $svc
+    };
+    ($first:expr, $($rest:expr),+ $(,)?) => {
+        $crate::Pipeline::new($first, $crate::pipeline!($($rest),+))
+    };
+}
@@ -0,0 +1,156 @@
+use crate::Pipeline;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::{
+    future::{maybe_done, MaybeDone},
+    ready,
+};
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// A "make service" that produces [`Pipeline`]s from two other make services.
+///
+/// For every target both make services are called concurrently and the services they produce
+/// are combined into a [`Pipeline`]. This makes it possible to use pipelines as per-connection
+/// services.
+///
+/// ```
+/// use tower_pipeline::MakePipeline;
+/// use tower::{service_fn, BoxError, Service, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let make_length_svc = service_fn(|_conn: u32| async move {
+///     Ok::<_, BoxError>(service_fn(|input: &'static str| async move {
+///         Ok::<_, BoxError>(input.len())
+///     }))
+/// });
+///
+/// let make_double_svc = service_fn(|_conn: u32| async move {
+///     Ok::<_, BoxError>(service_fn(|input: usize| async move {
+///         Ok::<_, BoxError>(input * 2)
+///     }))
+/// });
+///
+/// let mut make_pipeline = MakePipeline::new(make_length_svc, make_double_svc);
+///
+/// let pipeline = make_pipeline.ready().await.unwrap().call(1).await.unwrap();
+///
+/// let result = pipeline.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MakePipeline<MA, MB> {
+    make_first: MA,
+    make_second: MB,
+}
+
+impl<MA, MB> MakePipeline<MA, MB> {
+    /// Create a new [`MakePipeline`] from two make services.
+    pub fn new(make_first: MA, make_second: MB) -> Self {
+        Self {
+            make_first,
+            make_second,
+        }
+    }
+
+    /// Get a reference to the make service that produces first services.
+    pub fn first_as_ref(&self) -> &MA {
+        &self.make_first
+    }
+
+    /// Get a mutable reference to the make service that produces first services.
+    pub fn first_as_mut(&mut self) -> &mut MA {
+        &mut self.make_first
+    }
+
+    /// Get a reference to the make service that produces second services.
+    pub fn second_as_ref(&self) -> &MB {
+        &self.make_second
+    }
+
+    /// Get a mutable reference to the make service that produces second services.
+    pub fn second_as_mut(&mut self) -> &mut MB {
+        &mut self.make_second
+    }
+}
+
+impl<T, MA, MB> Service<T> for MakePipeline<MA, MB>
+where
+    T: Clone,
+    MA: Service<T>,
+    MB: Service<T>,
+    MA::Error: Into<MB::Error>,
+{
+    type Response = Pipeline<MA::Response, MB::Response>;
+    type Error = MB::Error;
+    type Future = MakePipelineFuture<MA::Future, MB::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let _ready: () = ready!(self.make_first.poll_ready(cx).map_err(Into::into)?);
+        self.make_second.poll_ready(cx)
+    }
+
+    fn call(&mut self, target: T) -> Self::Future {
+        MakePipelineFuture {
+            first: maybe_done(self.make_first.call(target.clone())),
+            second: maybe_done(self.make_second.call(target)),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`MakePipeline`].
+    pub struct MakePipelineFuture<FA, FB>
+    where
+        FA: Future,
+        FB: Future,
+    {
+        #[pin]
+        first: MaybeDone<FA>,
+        #[pin]
+        second: MaybeDone<FB>,
+    }
+}
+
+impl<FA, FB, A, B, EA, EB> Future for MakePipelineFuture<FA, FB>
+where
+    FA: Future<Output = Result<A, EA>>,
+    FB: Future<Output = Result<B, EB>>,
+    EA: Into<EB>,
+{
+    type Output = Result<Pipeline<A, B>, EB>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        let first_done = this.first.as_mut().poll(cx).is_ready();
+        let second_done = this.second.as_mut().poll(cx).is_ready();
+
+        if let Some(Err(_)) = this.first.as_mut().output_mut() {
+            if let Some(Err(err)) = this.first.as_mut().take_output() {
+                return Poll::Ready(Err(err.into()));
+            }
+        }
+
+        if let Some(Err(_)) = this.second.as_mut().output_mut() {
+            if let Some(Err(err)) = this.second.as_mut().take_output() {
+                return Poll::Ready(Err(err));
+            }
+        }
+
+        if !(first_done && second_done) {
+            return Poll::Pending;
+        }
+
+        match (this.first.take_output(), this.second.take_output()) {
+            (Some(Ok(first)), Some(Ok(second))) => Poll::Ready(Ok(Pipeline::new(first, second))),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// A service that converts the errors of the inner service with a closure.
+///
+/// Usually created with [`PipelineExt::pipeline_map_err`], in which case it converts the errors
+/// of the first service into the error type of the second when they aren't related through
+/// [`Into`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, ServiceExt};
+/// use std::num::ParseIntError;
+///
+/// #[derive(Debug)]
+/// enum AppError {
+///     InvalidInput(ParseIntError),
+///     TooLarge,
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let parse_svc = service_fn(|input: &'static str| async move { input.parse::<usize>() });
+///
+/// let check_svc = service_fn(|input: usize| async move {
+///     if input > 100 {
+///         Err(AppError::TooLarge)
+///     } else {
+///         Ok(input)
+///     }
+/// });
+///
+/// let combined = parse_svc.pipeline_map_err(check_svc, AppError::InvalidInput);
+///
+/// assert!(matches!(
+///     combined.oneshot("rust").await.unwrap_err(),
+///     AppError::InvalidInput(_),
+/// ));
+/// # }
+/// ```
+///
+/// [`PipelineExt::pipeline_map_err`]: crate::PipelineExt::pipeline_map_err
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MapErr<S, F> {
+    inner: S,
+    f: F,
+}
+
+impl<S, F> MapErr<S, F> {
+    /// Create a new [`MapErr`].
+    pub fn new(inner: S, f: F) -> Self {
+        Self { inner, f }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<R, S, F, E> Service<R> for MapErr<S, F>
+where
+    S: Service<R>,
+    F: FnOnce(S::Error) -> E + Clone,
+{
+    type Response = S::Response;
+    type Error = E;
+    type Future = MapErrFuture<S::Future, F>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(self.f.clone())
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        MapErrFuture {
+            future: self.inner.call(req),
+            f: Some(self.f.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`MapErr`].
+    pub struct MapErrFuture<Fut, F> {
+        #[pin]
+        future: Fut,
+        f: Option<F>,
+    }
+}
+
+impl<Fut, F, T, E1, E2> Future for MapErrFuture<Fut, F>
+where
+    Fut: Future<Output = Result<T, E1>>,
+    F: FnOnce(E1) -> E2,
+{
+    type Output = Result<T, E2>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let result = ready!(this.future.poll(cx));
+        Poll::Ready(result.map_err(this.f.take().unwrap()))
+    }
+}
@@ -0,0 +1,259 @@
+use alloc::collections::BTreeMap;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::Hash,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+/// A service that caches the responses of the inner service, keyed by the request.
+///
+/// At most `capacity` responses are cached. When the cache is full the least recently used
+/// response is evicted. Errors aren't cached. Clones share the same cache.
+///
+/// The inner service is still driven to readiness in `poll_ready`, since whether a request is
+/// cached isn't known until it is called, but it isn't called on cache hits.
+///
+/// Usually created with [`Pipeline::memoize_first`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, Service, ServiceExt};
+/// use std::sync::{
+///     atomic::{AtomicUsize, Ordering},
+///     Arc,
+/// };
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let lookups = Arc::new(AtomicUsize::new(0));
+///
+/// let lookup_svc = service_fn({
+///     let lookups = Arc::clone(&lookups);
+///     move |input: &'static str| {
+///         lookups.fetch_add(1, Ordering::SeqCst);
+///         async move { Ok::<_, BoxError>(input.len()) }
+///     }
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let mut combined = lookup_svc.pipeline(double_svc).memoize_first(100);
+///
+/// assert_eq!(combined.ready().await.unwrap().call("rust").await.unwrap(), 8);
+/// assert_eq!(combined.ready().await.unwrap().call("rust").await.unwrap(), 8);
+///
+/// assert_eq!(lookups.load(Ordering::SeqCst), 1);
+/// # }
+/// ```
+///
+/// [`Pipeline::memoize_first`]: crate::Pipeline::memoize_first
+pub struct Memoize<S, K, V> {
+    inner: S,
+    cache: Arc<Mutex<Lru<K, V>>>,
+}
+
+impl<S, K, V> Memoize<S, K, V> {
+    /// Create a new [`Memoize`] that caches at most `capacity` responses.
+    pub fn new(inner: S, capacity: usize) -> Self {
+        Self {
+            inner,
+            cache: Arc::new(Mutex::new(Lru::new(capacity))),
+        }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// The number of cached responses.
+    pub fn len(&self) -> usize {
+        self.cache.lock().unwrap().entries.len()
+    }
+
+    /// Returns `true` if no responses are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all cached responses.
+    pub fn clear(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+}
+
+impl<S, K, V> Clone for Memoize<S, K, V>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            cache: Arc::clone(&self.cache),
+        }
+    }
+}
+
+impl<S, K, V> fmt::Debug for Memoize<S, K, V>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cache = self.cache.lock().unwrap();
+        f.debug_struct("Memoize")
+            .field("inner", &self.inner)
+            .field("len", &cache.entries.len())
+            .field("capacity", &cache.capacity)
+            .finish()
+    }
+}
+
+impl<S, K> Service<K> for Memoize<S, K, S::Response>
+where
+    S: Service<K>,
+    S::Response: Clone,
+    K: Hash + Eq + Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = MemoizeFuture<S::Future, K, S::Response>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: K) -> Self::Future {
+        if let Some(res) = self.cache.lock().unwrap().get(&req) {
+            return MemoizeFuture {
+                state: State::Hit { res: Some(res) },
+            };
+        }
+
+        MemoizeFuture {
+            state: State::Miss {
+                future: self.inner.call(req.clone()),
+                key: Some(req),
+                cache: Arc::clone(&self.cache),
+            },
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Memoize`].
+    pub struct MemoizeFuture<F, K, V> {
+        #[pin]
+        state: State<F, K, V>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<F, K, V> {
+        Hit { res: Option<V> },
+        Miss {
+            #[pin]
+            future: F,
+            key: Option<K>,
+            cache: Arc<Mutex<Lru<K, V>>>,
+        },
+    }
+}
+
+impl<F, K, V, E> Future for MemoizeFuture<F, K, V>
+where
+    F: Future<Output = Result<V, E>>,
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    type Output = Result<V, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().state.project() {
+            StateProj::Hit { res } => Poll::Ready(Ok(res
+                .take()
+                .expect("`MemoizeFuture` polled after completion"))),
+            StateProj::Miss { future, key, cache } => {
+                let result = futures_util::ready!(future.poll(cx));
+                if let (Ok(res), Some(key)) = (&result, key.take()) {
+                    cache.lock().unwrap().insert(key, res.clone());
+                }
+                Poll::Ready(result)
+            }
+        }
+    }
+}
+
+// least recently used cache. `order` maps the tick an entry was last used at to its key, so
+// the least recently used entry is the first one
+struct Lru<K, V> {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<K, (V, u64)>,
+    order: BTreeMap<u64, K>,
+}
+
+impl<K, V> Lru<K, V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+impl<K, V> Lru<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    fn get(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        let (value, last_used) = self.entries.get_mut(key)?;
+        let key = self.order.remove(last_used).unwrap();
+        *last_used = self.tick;
+        self.order.insert(self.tick, key);
+        Some(value.clone())
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, last_used)) = self.entries.remove(&key) {
+            self.order.remove(&last_used);
+        } else if self.entries.len() == self.capacity {
+            if let Some((_, evicted)) = self.order.pop_first() {
+                self.entries.remove(&evicted);
+            }
+        }
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(key, (value, self.tick));
+    }
+}
@@ -0,0 +1,162 @@
+use core::future::Future;
+use core::{
+    fmt,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+pin_project! {
+    /// A future that drives a service to readiness and then calls it with a request.
+    #[project = OneshotProj]
+    pub(crate) enum Oneshot<S, Req>
+    where
+        S: Service<Req>,
+    {
+        NotReady { svc: S, req: Option<Req> },
+        Called { #[pin] future: S::Future },
+    }
+}
+
+impl<S, Req> Oneshot<S, Req>
+where
+    S: Service<Req>,
+{
+    pub(crate) fn new(svc: S, req: Req) -> Self {
+        Oneshot::NotReady {
+            svc,
+            req: Some(req),
+        }
+    }
+}
+
+impl<S, Req> Oneshot<S, Req>
+where
+    S: Service<Req>,
+{
+    /// Poll the future, calling `on_ready` once the service is ready, right before it is called.
+    pub(crate) fn poll_notify_ready<F>(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        mut on_ready: F,
+    ) -> Poll<Result<S::Response, S::Error>>
+    where
+        F: FnMut(),
+    {
+        loop {
+            let new_state = match self.as_mut().project() {
+                OneshotProj::NotReady { svc, req } => {
+                    let _ready: () = ready!(svc.poll_ready(cx)?);
+                    on_ready();
+                    Oneshot::Called {
+                        future: svc.call(req.take().unwrap()),
+                    }
+                }
+                OneshotProj::Called { future } => return future.poll(cx),
+            };
+
+            self.set(new_state);
+        }
+    }
+}
+
+impl<S, Req> Future for Oneshot<S, Req>
+where
+    S: Service<Req>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.poll_notify_ready(cx, || {})
+    }
+}
+
+pin_project! {
+    /// Response future of [`Pipeline::oneshot`].
+    ///
+    /// [`Pipeline::oneshot`]: crate::Pipeline::oneshot
+    pub struct OneshotFuture<S, Req>
+    where
+        S: Service<Req>,
+    {
+        #[pin]
+        inner: Oneshot<S, Req>,
+    }
+}
+
+impl<S, Req> OneshotFuture<S, Req>
+where
+    S: Service<Req>,
+{
+    pub(crate) fn new(svc: S, req: Req) -> Self {
+        Self {
+            inner: Oneshot::new(svc, req),
+        }
+    }
+}
+
+impl<S, Req> fmt::Debug for OneshotFuture<S, Req>
+where
+    S: Service<Req>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self.inner {
+            Oneshot::NotReady { .. } => "NotReady",
+            Oneshot::Called { .. } => "Called",
+        };
+        f.debug_struct("OneshotFuture")
+            .field("state", &state)
+            .finish()
+    }
+}
+
+impl<S, Req> Future for OneshotFuture<S, Req>
+where
+    S: Service<Req>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().inner.poll(cx)
+    }
+}
+
+/// Response future of [`Pipeline::ready_oneshot`].
+///
+/// [`Pipeline::ready_oneshot`]: crate::Pipeline::ready_oneshot
+#[derive(Debug)]
+pub struct ReadyOneshotFuture<S, Req> {
+    svc: Option<S>,
+    _req: PhantomData<fn(Req)>,
+}
+
+impl<S, Req> ReadyOneshotFuture<S, Req> {
+    pub(crate) fn new(svc: S) -> Self {
+        Self {
+            svc: Some(svc),
+            _req: PhantomData,
+        }
+    }
+}
+
+// the service is never pinned
+impl<S, Req> Unpin for ReadyOneshotFuture<S, Req> {}
+
+impl<S, Req> Future for ReadyOneshotFuture<S, Req>
+where
+    S: Service<Req>,
+{
+    type Output = Result<S, S::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let svc = self
+            .svc
+            .as_mut()
+            .expect("`ReadyOneshotFuture` polled after completion");
+        let _ready: () = ready!(svc.poll_ready(cx)?);
+        Poll::Ready(Ok(self.svc.take().unwrap()))
+    }
+}
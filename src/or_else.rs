@@ -0,0 +1,161 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// A service that calls a handler service with the error if the inner service fails.
+///
+/// The handler can produce a substitute response or fail with another error. Only the inner
+/// service is polled in `poll_ready`. The handler is driven to readiness inside the response
+/// future, if it is needed.
+///
+/// When wrapping a [`Pipeline`] errors from either stage reach the handler, since errors from
+/// the first stage are converted into errors of the second. Errors from `poll_ready` are passed
+/// through as is.
+///
+/// Usually created with [`Pipeline::or_else`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let parse_svc = service_fn(|input: &'static str| async move {
+///     input.parse::<usize>().map_err(BoxError::from)
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let default_svc = service_fn(|_: BoxError| async move {
+///     Ok::<_, BoxError>(0)
+/// });
+///
+/// let combined = parse_svc.pipeline(double_svc).or_else(default_svc);
+///
+/// assert_eq!(combined.clone().oneshot("2").await.unwrap(), 4);
+/// assert_eq!(combined.oneshot("two").await.unwrap(), 0);
+/// # }
+/// ```
+///
+/// [`Pipeline`]: crate::Pipeline
+/// [`Pipeline::or_else`]: crate::Pipeline::or_else
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OrElse<S, H> {
+    inner: S,
+    handler: H,
+}
+
+impl<S, H> OrElse<S, H> {
+    /// Create a new [`OrElse`].
+    pub fn new(inner: S, handler: H) -> Self {
+        Self { inner, handler }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Get a reference to the handler service.
+    pub fn handler_as_ref(&self) -> &H {
+        &self.handler
+    }
+
+    /// Get a mutable reference to the handler service.
+    pub fn handler_as_mut(&mut self) -> &mut H {
+        &mut self.handler
+    }
+}
+
+impl<T, S, H> Service<T> for OrElse<S, H>
+where
+    S: Service<T>,
+    H: Service<S::Error, Response = S::Response, Error = S::Error> + Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = OrElseFuture<T, S, H>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        OrElseFuture {
+            state: State::InnerPending {
+                future: self.inner.call(req),
+            },
+            handler: Some(self.handler.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`OrElse`].
+    pub struct OrElseFuture<T, S, H>
+    where
+        S: Service<T>,
+        H: Service<S::Error>,
+    {
+        #[pin]
+        state: State<T, S, H>,
+        handler: Option<H>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<T, S, H>
+    where
+        S: Service<T>,
+        H: Service<S::Error>,
+    {
+        InnerPending { #[pin] future: S::Future },
+        HandlerPending { #[pin] future: Oneshot<H, S::Error> },
+    }
+}
+
+impl<T, S, H> Future for OrElseFuture<T, S, H>
+where
+    S: Service<T>,
+    H: Service<S::Error, Response = S::Response, Error = S::Error>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::InnerPending { future } => match ready!(future.poll(cx)) {
+                    Ok(res) => return Poll::Ready(Ok(res)),
+                    Err(err) => State::HandlerPending {
+                        future: Oneshot::new(this.handler.take().unwrap(), err),
+                    },
+                },
+
+                StateProj::HandlerPending { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
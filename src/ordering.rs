@@ -0,0 +1,103 @@
+use core::{
+    future::Future,
+    task::{Context, Poll},
+};
+use futures_util::stream::{FuturesOrdered, FuturesUnordered, StreamExt};
+
+/// The order in which the results of concurrent calls are produced.
+///
+/// Used by the combinators that call a service once for each item, such as
+/// [`Pipeline::for_each_concurrent`], [`Pipeline::for_each_stream`], and
+/// [`Pipeline::into_stream_transform_with_ordering`].
+///
+/// ```
+/// use tower_pipeline::{Ordering, Pipeline};
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread", start_paused = true)]
+/// # async fn main() {
+/// let split_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.split(' ').map(|n| n.parse().unwrap()).collect::<Vec<u64>>())
+/// });
+///
+/// // takes longer the larger the number is
+/// let sleep_svc = service_fn(|n: u64| async move {
+///     tokio::time::sleep(Duration::from_millis(n * 50)).await;
+///     Ok::<_, BoxError>(n)
+/// });
+///
+/// let combined = Pipeline::new(split_svc, sleep_svc);
+///
+/// let preserved = combined
+///     .clone()
+///     .for_each_concurrent(0)
+///     .with_ordering(Ordering::Preserve)
+///     .oneshot("3 1 2")
+///     .await
+///     .unwrap();
+/// assert_eq!(preserved, vec![3, 1, 2]);
+///
+/// let unordered = combined
+///     .for_each_concurrent(0)
+///     .with_ordering(Ordering::Unordered)
+///     .oneshot("3 1 2")
+///     .await
+///     .unwrap();
+/// assert_eq!(unordered, vec![1, 2, 3]);
+/// # }
+/// ```
+///
+/// [`Pipeline::for_each_concurrent`]: crate::Pipeline::for_each_concurrent
+/// [`Pipeline::for_each_stream`]: crate::Pipeline::for_each_stream
+/// [`Pipeline::into_stream_transform_with_ordering`]: crate::Pipeline::into_stream_transform_with_ordering
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Ordering {
+    /// Produce the results in the same order as the items.
+    #[default]
+    Preserve,
+    /// Produce the results in the order the calls complete.
+    Unordered,
+}
+
+/// Futures in flight, completed in the order given by an [`Ordering`].
+pub(crate) enum InFlight<F>
+where
+    F: Future,
+{
+    Ordered(FuturesOrdered<F>),
+    Unordered(FuturesUnordered<F>),
+}
+
+impl<F> InFlight<F>
+where
+    F: Future,
+{
+    pub(crate) fn new(ordering: Ordering) -> Self {
+        match ordering {
+            Ordering::Preserve => InFlight::Ordered(FuturesOrdered::new()),
+            Ordering::Unordered => InFlight::Unordered(FuturesUnordered::new()),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            InFlight::Ordered(futures) => futures.len(),
+            InFlight::Unordered(futures) => futures.len(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, future: F) {
+        match self {
+            InFlight::Ordered(futures) => futures.push_back(future),
+            InFlight::Unordered(futures) => futures.push(future),
+        }
+    }
+
+    pub(crate) fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<F::Output>> {
+        match self {
+            InFlight::Ordered(futures) => futures.poll_next_unpin(cx),
+            InFlight::Unordered(futures) => futures.poll_next_unpin(cx),
+        }
+    }
+}
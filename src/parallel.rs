@@ -0,0 +1,316 @@
+use crate::{
+    buffer::Closed,
+    priority::PriorityQueue,
+    semaphore::{Permit, Semaphore},
+    BoxError,
+};
+use alloc::boxed::Box;
+use std::future::Future;
+use std::{
+    fmt,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::sync::{mpsc, oneshot};
+use tower_service::Service;
+
+/// Two services combined where each runs on its own spawned task, connected by bounded
+/// channels.
+///
+/// Each stage handles one call at a time, but while the second service handles one call the
+/// first service can already handle the next. So a stream of calls gets pipeline parallelism
+/// across tasks and, on a multi-threaded runtime, across CPU cores.
+///
+/// `poll_ready` returns `Poll::Pending` while `capacity` calls are in flight.
+///
+/// Created with [`Pipeline::parallel`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let combined = length_svc.pipeline(double_svc).parallel(16);
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// # }
+/// ```
+///
+/// [`Pipeline::parallel`]: crate::Pipeline::parallel
+pub struct Parallel<R, U> {
+    tx: mpsc::UnboundedSender<Message<R, U>>,
+    semaphore: Arc<Semaphore>,
+    permit: Option<Permit>,
+}
+
+struct Message<T, U> {
+    req: T,
+    tx: oneshot::Sender<Result<U, BoxError>>,
+    permit: Permit,
+}
+
+impl<R, U> Parallel<R, U> {
+    /// Create a new [`Parallel`] from two [`Service`]s.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or if called outside a tokio runtime since the workers are
+    /// spawned with [`tokio::spawn`].
+    pub fn new<A, B>(first: A, second: B, capacity: usize) -> Self
+    where
+        A: Service<R> + Send + 'static,
+        A::Error: Into<BoxError>,
+        A::Future: Send,
+        A::Response: Send + 'static,
+        B: Service<A::Response, Response = U> + Send + 'static,
+        B::Error: Into<BoxError>,
+        B::Future: Send,
+        R: Send + 'static,
+        U: Send + 'static,
+    {
+        assert!(capacity > 0, "`capacity` must be greater than zero");
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (second_tx, second_rx) = mpsc::channel(capacity);
+        tokio::spawn(run_first(first, rx, second_tx));
+        tokio::spawn(run_second(second, second_rx));
+        Self {
+            tx,
+            semaphore: Semaphore::new(capacity),
+            permit: None,
+        }
+    }
+
+    /// Create a new [`Parallel`] whose workers are spawned on the current [`LocalSet`].
+    ///
+    /// Unlike [`Parallel::new`] neither the services nor their requests, responses, and
+    /// futures have to implement [`Send`]. Both workers run on the same thread, so the stages
+    /// overlap but don't run on separate CPU cores.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or if called outside a [`LocalSet`] since the workers are
+    /// spawned with [`tokio::task::spawn_local`].
+    ///
+    /// [`LocalSet`]: tokio::task::LocalSet
+    pub fn new_local<A, B>(first: A, second: B, capacity: usize) -> Self
+    where
+        A: Service<R> + 'static,
+        A::Error: Into<BoxError>,
+        A::Response: 'static,
+        B: Service<A::Response, Response = U> + 'static,
+        B::Error: Into<BoxError>,
+        R: 'static,
+        U: 'static,
+    {
+        assert!(capacity > 0, "`capacity` must be greater than zero");
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (second_tx, second_rx) = mpsc::channel(capacity);
+        tokio::task::spawn_local(run_first(first, rx, second_tx));
+        tokio::task::spawn_local(run_second(second, second_rx));
+        Self {
+            tx,
+            semaphore: Semaphore::new(capacity),
+            permit: None,
+        }
+    }
+
+    /// Create a new [`Parallel`] where responses of the first service waiting for the second
+    /// service are sent to it in order of priority, as extracted by `priority`.
+    ///
+    /// Responses with the highest priority are sent first. Responses with the same priority are
+    /// sent in the order the first service produced them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or if called outside a tokio runtime since the workers are
+    /// spawned with [`tokio::spawn`].
+    pub fn new_with_priority<A, B, P, K>(first: A, second: B, capacity: usize, priority: P) -> Self
+    where
+        A: Service<R> + Send + 'static,
+        A::Error: Into<BoxError>,
+        A::Future: Send,
+        A::Response: Send + 'static,
+        B: Service<A::Response, Response = U> + Send + 'static,
+        B::Error: Into<BoxError>,
+        B::Future: Send,
+        P: Fn(&A::Response) -> K + Send + 'static,
+        K: Ord + Send + 'static,
+        R: Send + 'static,
+        U: Send + 'static,
+    {
+        assert!(capacity > 0, "`capacity` must be greater than zero");
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (second_tx, second_rx) = mpsc::channel(capacity);
+        tokio::spawn(run_first(first, rx, second_tx));
+        tokio::spawn(run_second_with_priority(second, second_rx, priority));
+        Self {
+            tx,
+            semaphore: Semaphore::new(capacity),
+            permit: None,
+        }
+    }
+}
+
+async fn run_first<A, R, U>(
+    mut first: A,
+    mut rx: mpsc::UnboundedReceiver<Message<R, U>>,
+    second_tx: mpsc::Sender<Message<A::Response, U>>,
+) where
+    A: Service<R>,
+    A::Error: Into<BoxError>,
+{
+    while let Some(Message { req, tx, permit }) = rx.recv().await {
+        if let Err(err) = std::future::poll_fn(|cx| first.poll_ready(cx)).await {
+            // the service is unusable after failing `poll_ready` so the worker stops and
+            // later calls fail with `Closed`
+            let _ = tx.send(Err(err.into()));
+            return;
+        }
+        let req = match first.call(req).await {
+            Ok(req) => req,
+            Err(err) => {
+                let _ = tx.send(Err(err.into()));
+                continue;
+            }
+        };
+        // waiting for capacity here applies backpressure from the second stage
+        if second_tx.send(Message { req, tx, permit }).await.is_err() {
+            return;
+        }
+    }
+}
+
+async fn run_second<B, T>(mut second: B, mut rx: mpsc::Receiver<Message<T, B::Response>>)
+where
+    B: Service<T>,
+    B::Error: Into<BoxError>,
+{
+    while let Some(Message { req, tx, permit }) = rx.recv().await {
+        if let Err(err) = std::future::poll_fn(|cx| second.poll_ready(cx)).await {
+            let _ = tx.send(Err(err.into()));
+            return;
+        }
+        let _ = tx.send(second.call(req).await.map_err(Into::into));
+        drop(permit);
+    }
+}
+
+async fn run_second_with_priority<B, T, P, K>(
+    mut second: B,
+    mut rx: mpsc::Receiver<Message<T, B::Response>>,
+    priority: P,
+) where
+    B: Service<T>,
+    B::Error: Into<BoxError>,
+    P: Fn(&T) -> K,
+    K: Ord,
+{
+    let mut queue = PriorityQueue::new();
+    loop {
+        if queue.is_empty() {
+            match rx.recv().await {
+                Some(msg) => queue.push(priority(&msg.req), msg),
+                None => return,
+            }
+        }
+
+        let ready = std::future::poll_fn(|cx| second.poll_ready(cx))
+            .await
+            .map_err(Into::into);
+
+        // responses produced while waiting for the service compete with the ones already queued
+        while let Ok(msg) = rx.try_recv() {
+            queue.push(priority(&msg.req), msg);
+        }
+        let Message { req, tx, permit } = queue.pop().unwrap();
+
+        if let Err(err) = ready {
+            let _ = tx.send(Err(err));
+            return;
+        }
+        let _ = tx.send(second.call(req).await.map_err(Into::into));
+        drop(permit);
+    }
+}
+
+impl<R, U> Clone for Parallel<R, U> {
+    fn clone(&self) -> Self {
+        // permits aren't cloned since that would allow more calls than the capacity
+        Self {
+            tx: self.tx.clone(),
+            semaphore: Arc::clone(&self.semaphore),
+            permit: None,
+        }
+    }
+}
+
+impl<R, U> fmt::Debug for Parallel<R, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Parallel")
+            .field("tx", &self.tx)
+            .field("semaphore", &self.semaphore)
+            .field("permit", &self.permit)
+            .finish()
+    }
+}
+
+impl<R, U> Service<R> for Parallel<R, U> {
+    type Response = U;
+    type Error = BoxError;
+    type Future = ParallelFuture<U>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.tx.is_closed() {
+            return Poll::Ready(Err(Box::new(Closed(()))));
+        }
+        if self.permit.is_none() {
+            match Semaphore::poll_acquire(&self.semaphore, cx) {
+                Poll::Ready(permit) => self.permit = Some(permit),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        let permit = self
+            .permit
+            .take()
+            .expect("`poll_ready` must be called before `call`");
+        let (tx, rx) = oneshot::channel();
+        // if the worker is gone `rx` is dropped along with the message and the future fails
+        // with `Closed`
+        let _ = self.tx.send(Message { req, tx, permit });
+        ParallelFuture { rx }
+    }
+}
+
+/// Response future of [`Parallel`].
+#[derive(Debug)]
+pub struct ParallelFuture<U> {
+    rx: oneshot::Receiver<Result<U, BoxError>>,
+}
+
+impl<U> Future for ParallelFuture<U> {
+    type Output = Result<U, BoxError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.rx).poll(cx) {
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(Box::new(Closed(())))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
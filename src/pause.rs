@@ -0,0 +1,158 @@
+use alloc::vec::Vec;
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+use tower_service::Service;
+
+/// A service that can be paused, for example during maintenance of a downstream service.
+///
+/// While paused `poll_ready` returns [`Poll::Pending`], so no new calls are made, while calls
+/// already in flight continue. [`PauseHandle::resume`] wakes the tasks waiting for the service
+/// to be ready.
+///
+/// Created with [`Pipeline::pausable`] or [`Pausable::new`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, Service, ServiceExt};
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let (combined, handle) = length_svc.pipeline(double_svc).pausable();
+///
+/// handle.pause();
+///
+/// let call = tokio::spawn(combined.oneshot("rust"));
+///
+/// // the call waits for the pipeline to be resumed
+/// tokio::time::sleep(Duration::from_millis(10)).await;
+/// assert!(!call.is_finished());
+///
+/// handle.resume();
+///
+/// assert_eq!(call.await.unwrap().unwrap(), 8);
+/// # }
+/// ```
+///
+/// [`Pipeline::pausable`]: crate::Pipeline::pausable
+#[derive(Debug)]
+pub struct Pausable<S> {
+    inner: S,
+    shared: Arc<Mutex<Shared>>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    paused: bool,
+    wakers: Vec<Waker>,
+}
+
+impl<S> Pausable<S> {
+    /// Create a new [`Pausable`] along with a [`PauseHandle`] for pausing it.
+    pub fn new(inner: S) -> (Self, PauseHandle) {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let handle = PauseHandle {
+            shared: Arc::clone(&shared),
+        };
+        (Self { inner, shared }, handle)
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Clone for Pausable<S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<R, S> Service<R> for Pausable<S>
+where
+    S: Service<R>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        {
+            let mut shared = self.shared.lock().unwrap();
+            if shared.paused {
+                if !shared
+                    .wakers
+                    .iter()
+                    .any(|waker| waker.will_wake(cx.waker()))
+                {
+                    shared.wakers.push(cx.waker().clone());
+                }
+                return Poll::Pending;
+            }
+        }
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        self.inner.call(req)
+    }
+}
+
+/// Handle for pausing and resuming a [`Pausable`].
+#[derive(Debug, Clone)]
+pub struct PauseHandle {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl PauseHandle {
+    /// Stop accepting new calls until [`PauseHandle::resume`] is called.
+    ///
+    /// Calls that are already in flight continue.
+    pub fn pause(&self) {
+        self.shared.lock().unwrap().paused = true;
+    }
+
+    /// Accept new calls again, waking the tasks waiting for the service to be ready.
+    pub fn resume(&self) {
+        let wakers = {
+            let mut shared = self.shared.lock().unwrap();
+            shared.paused = false;
+            core::mem::take(&mut shared.wakers)
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Returns `true` if the service is paused.
+    pub fn is_paused(&self) -> bool {
+        self.shared.lock().unwrap().paused
+    }
+}
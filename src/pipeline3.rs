@@ -0,0 +1,199 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Three services combined where the response of each is the request of the next.
+///
+/// This is similar to `Pipeline<Pipeline<A, B>, C>` but with a flat type and a single response
+/// future that handles all error conversions in one place.
+///
+/// ```
+/// use tower_pipeline::{Pipeline3, PipelineExt};
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let to_string_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input.to_string())
+/// });
+///
+/// let combined: Pipeline3<_, _, _> = length_svc
+///     .pipeline(double_svc)
+///     .then(to_string_svc);
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, "8");
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pipeline3<A, B, C> {
+    first: A,
+    second: B,
+    third: C,
+}
+
+impl<A, B, C> Pipeline3<A, B, C> {
+    /// Create a new [`Pipeline3`] from three [`Service`]s.
+    pub fn new(first: A, second: B, third: C) -> Self {
+        Self {
+            first,
+            second,
+            third,
+        }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+
+    /// Get a reference to the third service.
+    pub fn third_as_ref(&self) -> &C {
+        &self.third
+    }
+
+    /// Get a mutable reference to the third service.
+    pub fn third_as_mut(&mut self) -> &mut C {
+        &mut self.third
+    }
+
+    /// Consume `self`, returning the third service
+    pub fn into_third(self) -> C {
+        self.third
+    }
+}
+
+impl<R, A, B, C> Service<R> for Pipeline3<A, B, C>
+where
+    A: Service<R>,
+    B: Service<A::Response> + Clone,
+    C: Service<B::Response> + Clone,
+    A::Error: Into<C::Error>,
+    B::Error: Into<C::Error>,
+{
+    type Response = C::Response;
+    type Error = C::Error;
+    type Future = Pipeline3Future<R, A, B, C>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        Pipeline3Future {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            second: Some(self.second.clone()),
+            third: Some(self.third.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Pipeline3`].
+    pub struct Pipeline3Future<R, A, B, C>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+        C: Service<B::Response>,
+    {
+        #[pin]
+        state: State<R, A, B, C>,
+        second: Option<B>,
+        third: Option<C>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B, C>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+        C: Service<B::Response>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        CallingSecond { #[pin] future: Oneshot<B, A::Response> },
+        CallingThird { #[pin] future: Oneshot<C, B::Response> },
+    }
+}
+
+impl<R, A, B, C> Future for Pipeline3Future<R, A, B, C>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+    C: Service<B::Response>,
+    A::Error: Into<C::Error>,
+    B::Error: Into<C::Error>,
+{
+    type Output = Result<C::Response, C::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    let second = this.second.take().unwrap();
+                    State::CallingSecond {
+                        future: Oneshot::new(second, first_res),
+                    }
+                }
+
+                StateProj::CallingSecond { future } => {
+                    let second_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    let third = this.third.take().unwrap();
+                    State::CallingThird {
+                        future: Oneshot::new(third, second_res),
+                    }
+                }
+
+                StateProj::CallingThird { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Two services combined where the response of the first is passed through a closure before
+/// becoming the request of the second.
+///
+/// Created with [`PipelineExt::pipeline_map`] or [`Pipeline::with_adapter`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// // service that expects a `u64` rather than a `usize`
+/// let double_svc = service_fn(|input: u64| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let combined = length_svc.pipeline_map(|len: usize| len as u64, double_svc);
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// # }
+/// ```
+///
+/// [`PipelineExt::pipeline_map`]: crate::PipelineExt::pipeline_map
+/// [`Pipeline::with_adapter`]: crate::Pipeline::with_adapter
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PipelineMap<A, F, B> {
+    first: A,
+    adapter: F,
+    second: B,
+}
+
+impl<A, F, B> PipelineMap<A, F, B> {
+    /// Create a new [`PipelineMap`] from two [`Service`]s and an adapter closure.
+    pub fn new(first: A, adapter: F, second: B) -> Self {
+        Self {
+            first,
+            adapter,
+            second,
+        }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, A, F, T, B> Service<R> for PipelineMap<A, F, B>
+where
+    A: Service<R>,
+    F: FnOnce(A::Response) -> T + Clone,
+    B: Service<T> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = PipelineMapFuture<R, A, F, T, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        PipelineMapFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            adapter: Some(self.adapter.clone()),
+            second: Some(self.second.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`PipelineMap`].
+    pub struct PipelineMapFuture<R, A, F, T, B>
+    where
+        A: Service<R>,
+        B: Service<T>,
+    {
+        #[pin]
+        state: State<R, A, T, B>,
+        adapter: Option<F>,
+        second: Option<B>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, T, B>
+    where
+        A: Service<R>,
+        B: Service<T>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturePending { #[pin] future: Oneshot<B, T> },
+    }
+}
+
+impl<R, A, F, T, B> Future for PipelineMapFuture<R, A, F, T, B>
+where
+    A: Service<R>,
+    F: FnOnce(A::Response) -> T,
+    B: Service<T>,
+    A::Error: Into<B::Error>,
+{
+    type Output = Result<B::Response, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    let adapter = this.adapter.take().unwrap();
+                    let second = this.second.take().unwrap();
+                    State::SecondFuturePending {
+                        future: Oneshot::new(second, adapter(first_res)),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
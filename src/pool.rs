@@ -0,0 +1,162 @@
+use crate::SharedService;
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+/// A cheaply cloneable handle to a pool of services, such as a `tower::balance::p2c::Balance`,
+/// for use as the second service of a [`Pipeline`].
+///
+/// Pools usually don't implement [`Clone`] and pick the endpoint to call in `poll_ready`, so
+/// `call` must follow `poll_ready` on the same pool without another call in between. A
+/// [`Pool`] shares the pool behind a lock and its response future polls the pool for readiness
+/// and calls it while holding the lock once, so this holds even when many calls share the
+/// pool. `poll_ready` of [`Pool`] still polls the pool, so with [`ReadinessMode::Eager`] the
+/// pipeline waits for an endpoint to be ready and fails if the pool does.
+///
+/// Usually created with [`Pipeline::pooled_second`].
+///
+/// ```
+/// use tower_pipeline::{Pipeline, ReadinessMode};
+/// use tower::{
+///     balance::p2c::Balance,
+///     discover::ServiceList,
+///     load::Constant,
+///     service_fn,
+///     BoxError,
+///     ServiceExt,
+/// };
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let backends = (0..3)
+///     .map(|_| {
+///         let backend = service_fn(|input: usize| async move { Ok::<_, BoxError>(input * 2) });
+///         Constant::new(backend, 0)
+///     })
+///     .collect::<Vec<_>>();
+///
+/// let pool = Balance::new(ServiceList::new(backends));
+///
+/// // `Balance` doesn't implement `Clone`
+/// let combined = Pipeline::new(length_svc, pool)
+///     .pooled_second()
+///     .readiness_mode(ReadinessMode::Eager);
+///
+/// assert_eq!(combined.clone().oneshot("rust").await.unwrap(), 8);
+/// assert_eq!(combined.oneshot("tower").await.unwrap(), 10);
+/// # }
+/// ```
+///
+/// [`Pipeline`]: crate::Pipeline
+/// [`Pipeline::pooled_second`]: crate::Pipeline::pooled_second
+/// [`ReadinessMode::Eager`]: crate::ReadinessMode::Eager
+#[derive(Debug)]
+pub struct Pool<S> {
+    inner: SharedService<S>,
+}
+
+impl<S> Pool<S> {
+    /// Create a new [`Pool`].
+    pub fn new(inner: S) -> Self {
+        Self::from_shared(SharedService::new(inner))
+    }
+
+    /// Create a new [`Pool`] from a pool that is already shared.
+    pub fn from_shared(inner: SharedService<S>) -> Self {
+        Self { inner }
+    }
+
+    /// Get a handle to the pool.
+    pub fn as_shared(&self) -> &SharedService<S> {
+        &self.inner
+    }
+}
+
+impl<S> Clone for Pool<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S, R> Service<R> for Pool<S>
+where
+    S: Service<R>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = PoolFuture<S, R>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.lock().poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        PoolFuture {
+            state: State::PollReady { req: Some(req) },
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Pool`].
+    pub struct PoolFuture<S, R>
+    where
+        S: Service<R>,
+    {
+        #[pin]
+        state: State<S, R>,
+        inner: SharedService<S>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<S, R>
+    where
+        S: Service<R>,
+    {
+        PollReady { req: Option<R> },
+        FuturePending { #[pin] future: S::Future },
+    }
+}
+
+impl<S, R> Future for PoolFuture<S, R>
+where
+    S: Service<R>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::PollReady { req } => {
+                    // readiness observed by `Pool::poll_ready` may have been used by another
+                    // call in the meantime, so check again while holding the lock
+                    let mut inner = this.inner.lock();
+                    let _ready: () = ready!(inner.poll_ready(cx)?);
+                    State::FuturePending {
+                        future: inner.call(req.take().unwrap()),
+                    }
+                }
+
+                StateProj::FuturePending { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
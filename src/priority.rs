@@ -0,0 +1,66 @@
+use alloc::collections::BinaryHeap;
+use core::cmp::{Ordering, Reverse};
+
+// queue that pops the item with the highest priority first, and items with the same priority in
+// the order they were pushed
+pub(crate) struct PriorityQueue<P, M> {
+    heap: BinaryHeap<Entry<P, M>>,
+    seq: u64,
+}
+
+struct Entry<P, M> {
+    priority: P,
+    seq: Reverse<u64>,
+    item: M,
+}
+
+impl<P, M> PriorityQueue<P, M>
+where
+    P: Ord,
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            seq: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, priority: P, item: M) {
+        self.seq += 1;
+        self.heap.push(Entry {
+            priority,
+            seq: Reverse(self.seq),
+            item,
+        });
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<M> {
+        self.heap.pop().map(|entry| entry.item)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
+
+impl<P: Ord, M> PartialEq for Entry<P, M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<P: Ord, M> Eq for Entry<P, M> {}
+
+impl<P: Ord, M> PartialOrd for Entry<P, M> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: Ord, M> Ord for Entry<P, M> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| self.seq.cmp(&other.seq))
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// Metrics of the queue of a [`Buffer`] or [`Batch`], shared with its worker.
+///
+/// Calls are queued from when they are made until they are passed to the inner service, which
+/// includes waiting for the inner service to become ready and, for a [`Batch`], for the batch to
+/// fill up.
+///
+/// Returned by [`Buffer::metrics`] and [`Batch::metrics`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let combined = length_svc.pipeline(double_svc).buffered(16);
+/// let metrics = combined.second_as_ref().metrics().clone();
+///
+/// combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(metrics.depth(), 0);
+/// assert_eq!(metrics.high_watermark(), 1);
+/// assert_eq!(metrics.wait_times().count(), 1);
+/// # }
+/// ```
+///
+/// [`Buffer`]: crate::Buffer
+/// [`Batch`]: crate::Batch
+/// [`Buffer::metrics`]: crate::Buffer::metrics
+/// [`Batch::metrics`]: crate::Batch::metrics
+#[derive(Debug, Clone, Default)]
+pub struct QueueMetrics {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    depth: AtomicUsize,
+    high_watermark: AtomicUsize,
+    wait_times: Mutex<WaitTimes>,
+}
+
+impl QueueMetrics {
+    /// The number of calls currently queued.
+    pub fn depth(&self) -> usize {
+        self.inner.depth.load(Ordering::SeqCst)
+    }
+
+    /// The largest number of calls that have been queued at the same time.
+    pub fn high_watermark(&self) -> usize {
+        self.inner.high_watermark.load(Ordering::SeqCst)
+    }
+
+    /// How long calls have been queued for.
+    pub fn wait_times(&self) -> WaitTimes {
+        *self.inner.wait_times.lock().unwrap()
+    }
+
+    /// Reset the high watermark to the current depth and clear the wait times, for example after
+    /// exporting them.
+    pub fn reset(&self) {
+        self.inner
+            .high_watermark
+            .store(self.depth(), Ordering::SeqCst);
+        *self.inner.wait_times.lock().unwrap() = WaitTimes::default();
+    }
+
+    pub(crate) fn enqueue(&self) -> Queued {
+        let depth = self.inner.depth.fetch_add(1, Ordering::SeqCst) + 1;
+        self.inner.high_watermark.fetch_max(depth, Ordering::SeqCst);
+        Queued {
+            inner: Arc::clone(&self.inner),
+            since: Instant::now(),
+        }
+    }
+}
+
+/// A queued call, which is no longer counted towards the depth once dropped.
+#[derive(Debug)]
+pub(crate) struct Queued {
+    inner: Arc<Inner>,
+    since: Instant,
+}
+
+impl Queued {
+    /// Record how long the call was queued for, as it is passed to the inner service.
+    pub(crate) fn dequeue(self) {
+        self.inner
+            .wait_times
+            .lock()
+            .unwrap()
+            .record(self.since.elapsed());
+    }
+}
+
+impl Drop for Queued {
+    fn drop(&mut self) {
+        self.inner.depth.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// How long calls have been queued for.
+///
+/// Returned by [`QueueMetrics::wait_times`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WaitTimes {
+    count: u64,
+    total: Duration,
+    max: Duration,
+}
+
+impl WaitTimes {
+    fn record(&mut self, wait: Duration) {
+        self.count += 1;
+        self.total += wait;
+        self.max = self.max.max(wait);
+    }
+
+    /// The number of calls that have left the queue.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The total time calls have been queued for.
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// The longest time a call has been queued for.
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// The average time calls have been queued for, if any calls have left the queue.
+    pub fn mean(&self) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        Some(self.total.div_f64(self.count as f64))
+    }
+}
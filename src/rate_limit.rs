@@ -0,0 +1,163 @@
+use alloc::boxed::Box;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::{Instant, Sleep};
+use tower_service::Service;
+
+/// A service that admits at most `num` calls per period.
+///
+/// Clones share the same budget, so the limit also holds for the copies of the second service a
+/// [`Pipeline`] hands to each response future. Calls over the limit wait inside the response
+/// future, or in `poll_ready` of the pipeline when using [`ReadinessMode::Eager`].
+///
+/// Usually created with [`Pipeline::rate_limit_second`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let combined = length_svc
+///     .pipeline(double_svc)
+///     .rate_limit_second(10, Duration::from_secs(1));
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// # }
+/// ```
+///
+/// [`Pipeline`]: crate::Pipeline
+/// [`ReadinessMode::Eager`]: crate::ReadinessMode::Eager
+/// [`Pipeline::rate_limit_second`]: crate::Pipeline::rate_limit_second
+#[derive(Debug)]
+pub struct RateLimit<S> {
+    inner: S,
+    rate: Rate,
+    window: Arc<Mutex<Window>>,
+    sleep: Pin<Box<Sleep>>,
+    admitted: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rate {
+    num: u64,
+    per: Duration,
+}
+
+#[derive(Debug)]
+struct Window {
+    until: Instant,
+    remaining: u64,
+}
+
+impl<S> RateLimit<S> {
+    /// Create a new [`RateLimit`] that admits at most `num` calls per `per`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num` is zero.
+    pub fn new(inner: S, num: u64, per: Duration) -> Self {
+        assert!(num > 0, "`num` must be greater than zero");
+        let now = Instant::now();
+        Self {
+            inner,
+            rate: Rate { num, per },
+            window: Arc::new(Mutex::new(Window {
+                until: now + per,
+                remaining: num,
+            })),
+            sleep: Box::pin(tokio::time::sleep_until(now)),
+            admitted: false,
+        }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Clone for RateLimit<S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        // admission isn't cloned since that would allow more calls than the limit
+        Self {
+            inner: self.inner.clone(),
+            rate: self.rate,
+            window: Arc::clone(&self.window),
+            sleep: Box::pin(tokio::time::sleep_until(Instant::now())),
+            admitted: false,
+        }
+    }
+}
+
+impl<S, T> Service<T> for RateLimit<S>
+where
+    S: Service<T>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        while !self.admitted {
+            let until = {
+                let mut window = self.window.lock().unwrap();
+                let now = Instant::now();
+                if now >= window.until {
+                    window.until = now + self.rate.per;
+                    window.remaining = self.rate.num;
+                }
+                if window.remaining > 0 {
+                    window.remaining -= 1;
+                    self.admitted = true;
+                    break;
+                }
+                window.until
+            };
+
+            self.sleep.as_mut().reset(until);
+            if self.sleep.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+        }
+
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        assert!(
+            std::mem::replace(&mut self.admitted, false),
+            "`poll_ready` must be called before `call`"
+        );
+        self.inner.call(req)
+    }
+}
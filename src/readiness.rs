@@ -0,0 +1,28 @@
+/// How a [`Pipeline`] determines whether it is ready to accept a request.
+///
+/// Set with [`Pipeline::readiness_mode`].
+///
+/// [`Pipeline`]: crate::Pipeline
+/// [`Pipeline::readiness_mode`]: crate::Pipeline::readiness_mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ReadinessMode {
+    /// Only the first service is polled in `poll_ready`. The second service is driven to
+    /// readiness inside the response future, after the first service has responded.
+    ///
+    /// This is the default.
+    #[default]
+    Lazy,
+    /// Both services must be ready before the pipeline is ready.
+    ///
+    /// This propagates backpressure from the second service to callers of the pipeline, at the
+    /// cost of reserving capacity in the second service before the first service has run.
+    Eager,
+    /// The second service is a channel to a worker task, such as a `Buffer`, and the pipeline
+    /// is ready when both the first service is ready and the channel has capacity.
+    ///
+    /// This propagates backpressure from the channel to callers of the pipeline while only
+    /// reserving a slot in the channel, not the second service itself. Set by
+    /// `Pipeline::buffered` and the other methods that put the second service behind a
+    /// channel. For other second services it behaves like [`ReadinessMode::Eager`].
+    Buffered,
+}
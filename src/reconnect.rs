@@ -0,0 +1,253 @@
+use crate::BoxError;
+use alloc::boxed::Box;
+use core::future::Future;
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// A service that is built by a make service and rebuilt whenever it fails to become ready.
+///
+/// The service is made for `target` the first time `poll_ready` is called. When it later fails
+/// to become ready, for example because the connection it wraps was dropped, a new service is
+/// made and driven to readiness in its place, rather than every call failing from then on. The
+/// error is only returned if making the service fails or a service that was just made fails to
+/// become ready.
+///
+/// Clones reuse the service that has been made, but not the work of making a new one. A
+/// [`Pipeline`] drives clones of its second service to readiness, so use [`ReadinessMode::Eager`]
+/// or [`Pipeline::shared_second`] to keep the rebuilt service for later calls.
+///
+/// ```
+/// use tower_pipeline::{PipelineExt, Reconnect};
+/// use tower::{service_fn, BoxError, Service, ServiceExt};
+/// use std::sync::{
+///     atomic::{AtomicUsize, Ordering},
+///     Arc,
+/// };
+/// use std::task::{Context, Poll};
+///
+/// // a client whose connection drops after two requests
+/// #[derive(Clone, Default)]
+/// struct Client {
+///     requests: Arc<AtomicUsize>,
+/// }
+///
+/// impl Service<usize> for Client {
+///     type Response = usize;
+///     type Error = BoxError;
+///     type Future = std::future::Ready<Result<usize, BoxError>>;
+///
+///     fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), BoxError>> {
+///         if self.requests.load(Ordering::SeqCst) < 2 {
+///             Poll::Ready(Ok(()))
+///         } else {
+///             Poll::Ready(Err(BoxError::from("connection dropped")))
+///         }
+///     }
+///
+///     fn call(&mut self, input: usize) -> Self::Future {
+///         self.requests.fetch_add(1, Ordering::SeqCst);
+///         std::future::ready(Ok(input * 2))
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let connections = Arc::new(AtomicUsize::new(0));
+///
+/// let connect = service_fn({
+///     let connections = Arc::clone(&connections);
+///     move |_addr: &'static str| {
+///         connections.fetch_add(1, Ordering::SeqCst);
+///         async move { Ok::<_, BoxError>(Client::default()) }
+///     }
+/// });
+///
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let mut combined = length_svc
+///     .pipeline(Reconnect::new(connect, "localhost:3000"))
+///     .shared_second();
+///
+/// for _ in 0..4 {
+///     let result = combined.ready().await.unwrap().call("rust").await.unwrap();
+///     assert_eq!(result, 8);
+/// }
+///
+/// // the client was rebuilt once its connection dropped
+/// assert_eq!(connections.load(Ordering::SeqCst), 2);
+/// # }
+/// ```
+///
+/// [`Pipeline`]: crate::Pipeline
+/// [`ReadinessMode::Eager`]: crate::ReadinessMode::Eager
+/// [`Pipeline::shared_second`]: crate::Pipeline::shared_second
+pub struct Reconnect<M, T>
+where
+    M: Service<T>,
+{
+    make: M,
+    target: T,
+    state: State<M::Future, M::Response>,
+}
+
+enum State<F, S> {
+    Idle,
+    Connecting(Pin<Box<F>>),
+    Connected(S),
+}
+
+impl<M, T> Reconnect<M, T>
+where
+    M: Service<T>,
+{
+    /// Create a new [`Reconnect`] that makes services for `target` with `make`.
+    pub fn new(make: M, target: T) -> Self {
+        Self {
+            make,
+            target,
+            state: State::Idle,
+        }
+    }
+
+    /// Get a reference to the make service.
+    pub fn make_as_ref(&self) -> &M {
+        &self.make
+    }
+
+    /// Get a reference to the target services are made for.
+    pub fn target(&self) -> &T {
+        &self.target
+    }
+
+    /// Get a reference to the service that has been made, if any.
+    pub fn get_ref(&self) -> Option<&M::Response> {
+        match &self.state {
+            State::Connected(svc) => Some(svc),
+            _ => None,
+        }
+    }
+}
+
+impl<M, T> Clone for Reconnect<M, T>
+where
+    M: Service<T> + Clone,
+    M::Response: Clone,
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        // a service that is being made can't be shared, so the clone makes its own
+        let state = match &self.state {
+            State::Connected(svc) => State::Connected(svc.clone()),
+            _ => State::Idle,
+        };
+        Self {
+            make: self.make.clone(),
+            target: self.target.clone(),
+            state,
+        }
+    }
+}
+
+impl<M, T, R> Service<R> for Reconnect<M, T>
+where
+    M: Service<T>,
+    M::Response: Service<R>,
+    M::Error: Into<BoxError>,
+    <M::Response as Service<R>>::Error: Into<BoxError>,
+    T: Clone,
+{
+    type Response = <M::Response as Service<R>>::Response;
+    type Error = BoxError;
+    type Future = ReconnectFuture<<M::Response as Service<R>>::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let mut just_connected = false;
+
+        loop {
+            match &mut self.state {
+                State::Idle => {
+                    let _ready: () = ready!(self.make.poll_ready(cx).map_err(Into::into)?);
+                    let future = self.make.call(self.target.clone());
+                    self.state = State::Connecting(Box::pin(future));
+                }
+                State::Connecting(future) => match ready!(future.as_mut().poll(cx)) {
+                    Ok(svc) => {
+                        self.state = State::Connected(svc);
+                        just_connected = true;
+                    }
+                    Err(err) => {
+                        self.state = State::Idle;
+                        return Poll::Ready(Err(err.into()));
+                    }
+                },
+                State::Connected(svc) => match ready!(svc.poll_ready(cx)) {
+                    Ok(()) => return Poll::Ready(Ok(())),
+                    Err(err) => {
+                        self.state = State::Idle;
+                        // don't keep making services that fail straight away
+                        if just_connected {
+                            return Poll::Ready(Err(err.into()));
+                        }
+                    }
+                },
+            }
+        }
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        match &mut self.state {
+            State::Connected(svc) => ReconnectFuture {
+                inner: svc.call(req),
+            },
+            _ => panic!("`poll_ready` must be called before `call`"),
+        }
+    }
+}
+
+impl<M, T> fmt::Debug for Reconnect<M, T>
+where
+    M: Service<T> + fmt::Debug,
+    M::Response: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Reconnect");
+        debug
+            .field("make", &self.make)
+            .field("target", &self.target);
+        match &self.state {
+            State::Idle => debug.field("state", &"idle"),
+            State::Connecting(_) => debug.field("state", &"connecting"),
+            State::Connected(svc) => debug.field("service", svc),
+        };
+        debug.finish()
+    }
+}
+
+pin_project! {
+    /// Response future of [`Reconnect`].
+    pub struct ReconnectFuture<F> {
+        #[pin]
+        inner: F,
+    }
+}
+
+impl<F, T, E> Future for ReconnectFuture<F>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Into<BoxError>,
+{
+    type Output = Result<T, BoxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().inner.poll(cx).map_err(Into::into)
+    }
+}
@@ -0,0 +1,305 @@
+use crate::BoxError;
+use alloc::{boxed::Box, vec::Vec};
+use futures_util::{
+    future::{self, Ready},
+    ready,
+};
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    error::Error,
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+/// Storage for the request and response pairs captured by [`Record`].
+///
+/// Implemented by [`Recording`], which keeps the pairs in memory, and by closures, for sending
+/// them somewhere else.
+pub trait RecordStore<T, U> {
+    /// Store a request and the response it produced.
+    fn record(&self, req: T, res: U);
+}
+
+impl<T, U, F> RecordStore<T, U> for F
+where
+    F: Fn(T, U),
+{
+    fn record(&self, req: T, res: U) {
+        self(req, res)
+    }
+}
+
+/// In-memory [`RecordStore`].
+///
+/// Clones share the same recordings.
+pub struct Recording<T, U> {
+    pairs: Arc<Mutex<Vec<(T, U)>>>,
+}
+
+impl<T, U> Recording<T, U> {
+    /// Create a new empty [`Recording`].
+    pub fn new() -> Self {
+        Self {
+            pairs: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// The recorded request and response pairs, in the order the calls completed.
+    pub fn pairs(&self) -> Vec<(T, U)>
+    where
+        T: Clone,
+        U: Clone,
+    {
+        self.pairs.lock().unwrap().clone()
+    }
+
+    /// Create a [`Replay`] that serves the recorded responses.
+    pub fn replay(&self) -> Replay<T, U>
+    where
+        T: Clone,
+        U: Clone,
+    {
+        Replay::new(self.pairs())
+    }
+}
+
+impl<T, U> RecordStore<T, U> for Recording<T, U> {
+    fn record(&self, req: T, res: U) {
+        self.pairs.lock().unwrap().push((req, res));
+    }
+}
+
+impl<T, U> Clone for Recording<T, U> {
+    fn clone(&self) -> Self {
+        Self {
+            pairs: Arc::clone(&self.pairs),
+        }
+    }
+}
+
+impl<T, U> Default for Recording<T, U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, U> fmt::Debug for Recording<T, U>
+where
+    T: fmt::Debug,
+    U: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recording")
+            .field("pairs", &self.pairs)
+            .finish()
+    }
+}
+
+/// A service that captures the request and response of each successful call of the inner
+/// service in a [`RecordStore`].
+///
+/// Together with [`Replay`] this makes it possible to reproduce the behavior of a stage, for
+/// example from production traffic, without calling the real service.
+///
+/// Usually created with [`Pipeline::record_first`] or [`Pipeline::record_second`].
+///
+/// ```
+/// use tower_pipeline::{PipelineExt, Recording};
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let recording = Recording::new();
+///
+/// let combined = length_svc
+///     .pipeline(double_svc)
+///     .record_first(recording.clone());
+///
+/// combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(recording.pairs(), vec![("rust", 4)]);
+///
+/// // replay the first stage without calling it
+/// let replayed = recording.replay().pipeline(service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// }));
+///
+/// assert_eq!(replayed.oneshot("rust").await.unwrap(), 8);
+/// # }
+/// ```
+///
+/// [`Pipeline::record_first`]: crate::Pipeline::record_first
+/// [`Pipeline::record_second`]: crate::Pipeline::record_second
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Record<S, St> {
+    inner: S,
+    store: St,
+}
+
+impl<S, St> Record<S, St> {
+    /// Create a new [`Record`].
+    pub fn new(inner: S, store: St) -> Self {
+        Self { inner, store }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Get a reference to the store.
+    pub fn store(&self) -> &St {
+        &self.store
+    }
+}
+
+impl<T, S, St> Service<T> for Record<S, St>
+where
+    T: Clone,
+    S: Service<T>,
+    S::Response: Clone,
+    St: RecordStore<T, S::Response> + Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = RecordFuture<S::Future, St, T>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        RecordFuture {
+            inner: self.inner.call(req.clone()),
+            store: Some(self.store.clone()),
+            req: Some(req),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Record`].
+    pub struct RecordFuture<F, St, T> {
+        #[pin]
+        inner: F,
+        store: Option<St>,
+        req: Option<T>,
+    }
+}
+
+impl<F, St, T, U, E> Future for RecordFuture<F, St, T>
+where
+    F: Future<Output = Result<U, E>>,
+    U: Clone,
+    St: RecordStore<T, U>,
+{
+    type Output = Result<U, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let res = ready!(this.inner.poll(cx))?;
+        let store = this.store.take().unwrap();
+        store.record(this.req.take().unwrap(), res.clone());
+        Poll::Ready(Ok(res))
+    }
+}
+
+/// A service that responds with the responses captured by [`Record`].
+///
+/// Each request is answered with the response of the first recorded pair whose request is
+/// equal to it. Requests that weren't recorded fail with a [`NotRecorded`] error.
+///
+/// Usually created with [`Recording::replay`]. See [`Record`] for an example.
+pub struct Replay<T, U> {
+    pairs: Arc<[(T, U)]>,
+}
+
+impl<T, U> Replay<T, U> {
+    /// Create a new [`Replay`] from request and response pairs.
+    pub fn new<I>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (T, U)>,
+    {
+        Self {
+            pairs: pairs.into_iter().collect(),
+        }
+    }
+}
+
+impl<T, U> Clone for Replay<T, U> {
+    fn clone(&self) -> Self {
+        Self {
+            pairs: Arc::clone(&self.pairs),
+        }
+    }
+}
+
+impl<T, U> fmt::Debug for Replay<T, U>
+where
+    T: fmt::Debug,
+    U: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Replay")
+            .field("pairs", &self.pairs)
+            .finish()
+    }
+}
+
+impl<T, U> Service<T> for Replay<T, U>
+where
+    T: PartialEq,
+    U: Clone,
+{
+    type Response = U;
+    type Error = BoxError;
+    type Future = Ready<Result<U, BoxError>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        let res = self
+            .pairs
+            .iter()
+            .find(|(recorded, _)| *recorded == req)
+            .map(|(_, res)| res.clone())
+            .ok_or_else(|| Box::new(NotRecorded(())) as BoxError);
+        future::ready(res)
+    }
+}
+
+/// Error produced by [`Replay`] when a request wasn't recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotRecorded(());
+
+impl fmt::Display for NotRecorded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("request wasn't recorded")
+    }
+}
+
+impl Error for NotRecorded {}
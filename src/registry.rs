@@ -0,0 +1,182 @@
+//! Building pipelines from configuration.
+//!
+//! A [`Registry`] maps names to factories of stages. A [`PipelineConfig`], usually deserialized
+//! from a configuration file, lists the names of the stages to run in order, so stages can be
+//! reordered or disabled without recompiling.
+//!
+//! ```
+//! use tower_pipeline::registry::{PipelineConfig, Registry};
+//! use tower::{service_fn, BoxError, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let registry = Registry::new()
+//!     .register("double", || {
+//!         service_fn(|input: usize| async move { Ok::<_, BoxError>(input * 2) })
+//!     })
+//!     .register("increment", || {
+//!         service_fn(|input: usize| async move { Ok::<_, BoxError>(input + 1) })
+//!     });
+//!
+//! let config: PipelineConfig = serde_json::from_str(r#"
+//!     {
+//!         "stages": [
+//!             { "name": "double" },
+//!             { "name": "increment" },
+//!             { "name": "double", "enabled": false }
+//!         ]
+//!     }
+//! "#).unwrap();
+//!
+//! let pipeline = registry.build(&config).unwrap();
+//!
+//! assert_eq!(pipeline.oneshot(1).await.unwrap(), 3);
+//! # }
+//! ```
+
+use crate::{BoxClonePipeline, DynPipeline};
+use serde::Deserialize;
+use std::{collections::HashMap, error::Error, fmt, string::String, sync::Arc, vec::Vec};
+use tower_service::Service;
+
+/// Named factories of stages, used to build a [`DynPipeline`] from a [`PipelineConfig`].
+///
+/// Every stage has the same request and response type `T` and error type `E`.
+///
+/// See the [module docs](self) for an example.
+pub struct Registry<T, E> {
+    factories: HashMap<String, Arc<dyn Fn() -> BoxClonePipeline<T, T, E> + Send + Sync>>,
+}
+
+impl<T, E> Registry<T, E> {
+    /// Create a new empty [`Registry`].
+    pub fn new() -> Self {
+        Self {
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Register a factory for the stage called `name`.
+    ///
+    /// The factory is called each time a pipeline containing the stage is built. Registering a
+    /// name again replaces the previous factory.
+    pub fn register<F, S>(mut self, name: impl Into<String>, factory: F) -> Self
+    where
+        F: Fn() -> S + Send + Sync + 'static,
+        S: Service<T, Response = T, Error = E> + Clone + Send + 'static,
+        S::Future: Send + 'static,
+    {
+        self.factories.insert(
+            name.into(),
+            Arc::new(move || BoxClonePipeline::new(factory())),
+        );
+        self
+    }
+
+    /// Returns `true` if a stage called `name` is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// Build a [`DynPipeline`] with the enabled stages of `config`, in order.
+    ///
+    /// Fails if `config` contains a stage that isn't registered, even if that stage is
+    /// disabled, so typos are caught early.
+    pub fn build(&self, config: &PipelineConfig) -> Result<DynPipeline<T, E>, UnknownStage> {
+        let stages = config
+            .stages
+            .iter()
+            .map(|stage| {
+                let factory = self
+                    .factories
+                    .get(&stage.name)
+                    .ok_or_else(|| UnknownStage {
+                        name: stage.name.clone(),
+                    })?;
+                Ok(stage.enabled.then(|| factory()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(DynPipeline::from_stages(
+            stages.into_iter().flatten().collect(),
+        ))
+    }
+}
+
+impl<T, E> Clone for Registry<T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            factories: self.factories.clone(),
+        }
+    }
+}
+
+impl<T, E> Default for Registry<T, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, E> fmt::Debug for Registry<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registry")
+            .field("stages", &self.factories.keys())
+            .finish()
+    }
+}
+
+/// The stages of a pipeline, in order.
+///
+/// Built into a [`DynPipeline`] with [`Registry::build`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct PipelineConfig {
+    /// The stages of the pipeline.
+    pub stages: Vec<StageConfig>,
+}
+
+/// A stage of a [`PipelineConfig`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct StageConfig {
+    /// The name the stage is registered under.
+    pub name: String,
+    /// Whether the stage is part of the pipeline.
+    ///
+    /// Defaults to `true`.
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+impl StageConfig {
+    /// Create a new enabled [`StageConfig`].
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            enabled: true,
+        }
+    }
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// Error produced by [`Registry::build`] when a stage isn't registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownStage {
+    name: String,
+}
+
+impl UnknownStage {
+    /// The name of the stage.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for UnknownStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no stage named `{}` is registered", self.name)
+    }
+}
+
+impl Error for UnknownStage {}
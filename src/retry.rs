@@ -0,0 +1,216 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Decides whether a failed request should be retried.
+///
+/// The policy is cloned for every request so it can track per request state, such as the number
+/// of attempts made so far.
+///
+/// Implemented for closures of the form `FnMut(usize, &T, &E) -> bool` where the first argument
+/// is the number of attempts made so far (starting at `1`).
+pub trait RetryPolicy<T, E> {
+    /// Called with every request before its first attempt.
+    ///
+    /// Does nothing by default.
+    fn on_request(&mut self, req: &T) {
+        let _ = req;
+    }
+
+    /// Returns `true` if the request should be retried.
+    ///
+    /// `attempts` is the number of attempts made so far, including the one that just failed.
+    fn retry(&mut self, attempts: usize, req: &T, error: &E) -> bool;
+}
+
+impl<T, E, F> RetryPolicy<T, E> for F
+where
+    F: FnMut(usize, &T, &E) -> bool,
+{
+    fn retry(&mut self, attempts: usize, req: &T, error: &E) -> bool {
+        self(attempts, req, error)
+    }
+}
+
+/// A [`RetryPolicy`] that retries every error until a number of attempts have been made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Attempts(pub usize);
+
+impl<T, E> RetryPolicy<T, E> for Attempts {
+    fn retry(&mut self, attempts: usize, _req: &T, _error: &E) -> bool {
+        attempts < self.0
+    }
+}
+
+/// A service that retries failed requests according to a [`RetryPolicy`].
+///
+/// Retries are made with clones of the inner service, which are driven to readiness inside the
+/// response future.
+///
+/// Usually created with [`Pipeline::retry_second`], where retrying is cheap because the first
+/// service has already run.
+///
+/// ```
+/// use tower_pipeline::{Attempts, PipelineExt};
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use std::sync::{
+///     atomic::{AtomicUsize, Ordering},
+///     Arc,
+/// };
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// // service that fails the first two times it is called
+/// let calls = Arc::new(AtomicUsize::new(0));
+/// let flaky_svc = service_fn(move |input: usize| {
+///     let calls = Arc::clone(&calls);
+///     async move {
+///         if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+///             Err::<usize, BoxError>(BoxError::from("flaky"))
+///         } else {
+///             Ok(input * 2)
+///         }
+///     }
+/// });
+///
+/// let combined = length_svc.pipeline(flaky_svc).retry_second(Attempts(3));
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// # }
+/// ```
+///
+/// [`Pipeline::retry_second`]: crate::Pipeline::retry_second
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Retry<S, P> {
+    inner: S,
+    policy: P,
+}
+
+impl<S, P> Retry<S, P> {
+    /// Create a new [`Retry`].
+    pub fn new(inner: S, policy: P) -> Self {
+        Self { inner, policy }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Get a reference to the retry policy.
+    pub fn policy(&self) -> &P {
+        &self.policy
+    }
+}
+
+impl<T, S, P> Service<T> for Retry<S, P>
+where
+    T: Clone,
+    S: Service<T> + Clone,
+    P: RetryPolicy<T, S::Error> + Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = RetryFuture<T, S, P>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        let mut policy = self.policy.clone();
+        policy.on_request(&req);
+
+        RetryFuture {
+            state: State::Called {
+                future: self.inner.call(req.clone()),
+            },
+            inner: self.inner.clone(),
+            policy,
+            req,
+            attempts: 1,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Retry`].
+    pub struct RetryFuture<T, S, P>
+    where
+        S: Service<T>,
+    {
+        #[pin]
+        state: State<T, S>,
+        inner: S,
+        policy: P,
+        req: T,
+        attempts: usize,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<T, S>
+    where
+        S: Service<T>,
+    {
+        Called { #[pin] future: S::Future },
+        Retrying { #[pin] future: Oneshot<S, T> },
+    }
+}
+
+impl<T, S, P> Future for RetryFuture<T, S, P>
+where
+    T: Clone,
+    S: Service<T> + Clone,
+    P: RetryPolicy<T, S::Error>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let result = match this.state.as_mut().project() {
+                StateProj::Called { future } => ready!(future.poll(cx)),
+                StateProj::Retrying { future } => ready!(future.poll(cx)),
+            };
+
+            match result {
+                Ok(res) => return Poll::Ready(Ok(res)),
+                Err(err) => {
+                    if !this.policy.retry(*this.attempts, this.req, &err) {
+                        return Poll::Ready(Err(err));
+                    }
+                }
+            }
+
+            *this.attempts += 1;
+            this.state.set(State::Retrying {
+                future: Oneshot::new(this.inner.clone(), this.req.clone()),
+            });
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use crate::RetryPolicy;
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+/// The number of slots the time window of a [`RetryBudget`] is divided into.
+const SLOTS: usize = 10;
+
+/// A budget that limits retries to a percentage of requests, shared between every clone.
+///
+/// Every request deposits into the budget and every retry withdraws from it, so retries can't
+/// amplify the load on a degraded service beyond `retry_percent` of the requests made in the
+/// last `ttl`. `min_per_sec` retries per second are always allowed, so services with little
+/// traffic can still retry.
+///
+/// Used with [`Pipeline::retry_second_with_budget`] or [`Budgeted`].
+///
+/// ```
+/// use tower_pipeline::{Attempts, PipelineExt, RetryBudget};
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let failing_svc = service_fn(|_: usize| async move {
+///     Err::<usize, BoxError>(BoxError::from("degraded"))
+/// });
+///
+/// // no retries beyond 10% of requests
+/// let budget = RetryBudget::new(Duration::from_secs(10), 0, 0.1);
+///
+/// let combined = length_svc
+///     .pipeline(failing_svc)
+///     .retry_second_with_budget(Attempts(3), budget.clone());
+///
+/// for _ in 0..10 {
+///     combined.clone().oneshot("rust").await.unwrap_err();
+/// }
+///
+/// // only one of the failed requests was retried, and only once
+/// assert!(!budget.withdraw());
+/// # }
+/// ```
+///
+/// [`Pipeline::retry_second_with_budget`]: crate::Pipeline::retry_second_with_budget
+#[derive(Debug, Clone)]
+pub struct RetryBudget {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    reserve: f64,
+    retry_percent: f64,
+    slot_len: Duration,
+    window: Mutex<Window>,
+}
+
+#[derive(Debug)]
+struct Window {
+    slots: [Slot; SLOTS],
+    current: usize,
+    slot_started: Instant,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Slot {
+    deposits: u64,
+    withdrawals: u64,
+}
+
+impl RetryBudget {
+    /// Create a new [`RetryBudget`].
+    ///
+    /// - `ttl` is how long deposits count towards the budget.
+    /// - `min_per_sec` is the number of retries per second that are always allowed.
+    /// - `retry_percent` is the ratio of retries to requests allowed on top of that, where `0.1`
+    ///   allows one retry for every ten requests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ttl` is zero or `retry_percent` is negative.
+    pub fn new(ttl: Duration, min_per_sec: u32, retry_percent: f32) -> Self {
+        assert!(ttl > Duration::ZERO, "`ttl` must be greater than zero");
+        assert!(retry_percent >= 0.0, "`retry_percent` must not be negative");
+
+        Self {
+            inner: Arc::new(Inner {
+                reserve: f64::from(min_per_sec) * ttl.as_secs_f64(),
+                retry_percent: f64::from(retry_percent),
+                slot_len: ttl / SLOTS as u32,
+                window: Mutex::new(Window {
+                    slots: [Slot::default(); SLOTS],
+                    current: 0,
+                    slot_started: Instant::now(),
+                }),
+            }),
+        }
+    }
+
+    /// Record a request.
+    pub fn deposit(&self) {
+        let mut window = self.window();
+        let current = window.current;
+        window.slots[current].deposits += 1;
+    }
+
+    /// Try to take a retry out of the budget.
+    ///
+    /// Returns `false` if the budget is used up, in which case the request shouldn't be
+    /// retried.
+    pub fn withdraw(&self) -> bool {
+        let mut window = self.window();
+
+        let deposits: u64 = window.slots.iter().map(|slot| slot.deposits).sum();
+        let withdrawals: u64 = window.slots.iter().map(|slot| slot.withdrawals).sum();
+
+        let balance =
+            self.inner.reserve + deposits as f64 * self.inner.retry_percent - withdrawals as f64;
+
+        if balance >= 1.0 {
+            let current = window.current;
+            window.slots[current].withdrawals += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Lock the window, clearing the slots that are older than `ttl`.
+    fn window(&self) -> MutexGuard<'_, Window> {
+        let mut window = self.inner.window.lock().unwrap();
+        let now = Instant::now();
+
+        for _ in 0..SLOTS {
+            if now.duration_since(window.slot_started) < self.inner.slot_len {
+                return window;
+            }
+            window.current = (window.current + 1) % SLOTS;
+            let current = window.current;
+            window.slots[current] = Slot::default();
+            window.slot_started += self.inner.slot_len;
+        }
+
+        // every slot has expired
+        window.slot_started = now;
+        window
+    }
+}
+
+/// A [`RetryPolicy`] that only retries while a [`RetryBudget`] allows it.
+///
+/// Usually created with [`Pipeline::retry_second_with_budget`].
+///
+/// [`Pipeline::retry_second_with_budget`]: crate::Pipeline::retry_second_with_budget
+#[derive(Debug, Clone)]
+pub struct Budgeted<P> {
+    policy: P,
+    budget: RetryBudget,
+}
+
+impl<P> Budgeted<P> {
+    /// Create a new [`Budgeted`] policy.
+    pub fn new(policy: P, budget: RetryBudget) -> Self {
+        Self { policy, budget }
+    }
+
+    /// Get a reference to the budget.
+    pub fn budget(&self) -> &RetryBudget {
+        &self.budget
+    }
+}
+
+impl<T, E, P> RetryPolicy<T, E> for Budgeted<P>
+where
+    P: RetryPolicy<T, E>,
+{
+    fn on_request(&mut self, req: &T) {
+        self.budget.deposit();
+        self.policy.on_request(req);
+    }
+
+    fn retry(&mut self, attempts: usize, req: &T, error: &E) -> bool {
+        self.policy.retry(attempts, req, error) && self.budget.withdraw()
+    }
+}
@@ -0,0 +1,203 @@
+use crate::oneshot::Oneshot;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::{
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::{ready, stream::FuturesOrdered, StreamExt};
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// A service whose response is sent to several services concurrently, with their responses
+/// combined by a reducer.
+///
+/// Every second service is called with a clone of the response of the first service. Once all of
+/// them have responded `reducer` is called with their responses, in the same order as the
+/// services. The first error fails the whole call.
+///
+/// Created with [`PipelineExt::pipeline_scatter_gather`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let parse_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.parse::<usize>()?)
+/// });
+///
+/// // search a shard, returning the number of hits
+/// let shard_svc = |shard: usize| {
+///     service_fn(move |query: usize| async move {
+///         Ok::<_, BoxError>(query * shard)
+///     })
+/// };
+///
+/// let combined = parse_svc.pipeline_scatter_gather(
+///     vec![shard_svc(1), shard_svc(2), shard_svc(3)],
+///     |hits: Vec<usize>| hits.into_iter().sum::<usize>(),
+/// );
+///
+/// let result = combined.oneshot("10").await.unwrap();
+///
+/// assert_eq!(result, 60);
+/// # }
+/// ```
+///
+/// [`PipelineExt::pipeline_scatter_gather`]: crate::PipelineExt::pipeline_scatter_gather
+#[derive(Debug, Clone, Default)]
+pub struct ScatterGather<A, B, F> {
+    first: A,
+    seconds: Vec<B>,
+    reducer: F,
+}
+
+impl<A, B, F> ScatterGather<A, B, F> {
+    /// Create a new [`ScatterGather`] from a [`Service`], the [`Service`]s its response is sent
+    /// to, and a reducer.
+    pub fn new(first: A, seconds: Vec<B>, reducer: F) -> Self {
+        Self {
+            first,
+            seconds,
+            reducer,
+        }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second services.
+    pub fn seconds_as_ref(&self) -> &[B] {
+        &self.seconds
+    }
+
+    /// Get a mutable reference to the second services.
+    pub fn seconds_as_mut(&mut self) -> &mut Vec<B> {
+        &mut self.seconds
+    }
+
+    /// Consume `self`, returning the second services
+    pub fn into_seconds(self) -> Vec<B> {
+        self.seconds
+    }
+}
+
+impl<R, A, B, F, T> Service<R> for ScatterGather<A, B, F>
+where
+    A: Service<R>,
+    A::Response: Clone,
+    B: Service<A::Response> + Clone,
+    F: FnOnce(Vec<B::Response>) -> T + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Response = T;
+    type Error = B::Error;
+    type Future = ScatterGatherFuture<R, A, B, F>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        ScatterGatherFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            seconds: self.seconds.clone(),
+            reducer: Some(self.reducer.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`ScatterGather`].
+    pub struct ScatterGatherFuture<R, A, B, F>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        #[pin]
+        state: State<R, A, B>,
+        seconds: Vec<B>,
+        reducer: Option<F>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturesPending {
+            in_flight: FuturesOrdered<Oneshot<B, A::Response>>,
+            responses: Vec<B::Response>,
+        },
+    }
+}
+
+impl<R, A, B, F, T> Future for ScatterGatherFuture<R, A, B, F>
+where
+    A: Service<R>,
+    A::Response: Clone,
+    B: Service<A::Response>,
+    F: FnOnce(Vec<B::Response>) -> T,
+    A::Error: Into<B::Error>,
+{
+    type Output = Result<T, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    let seconds = mem::take(this.seconds);
+                    let responses = Vec::with_capacity(seconds.len());
+                    let in_flight = seconds
+                        .into_iter()
+                        .map(|second| Oneshot::new(second, first_res.clone()))
+                        .collect();
+                    State::SecondFuturesPending {
+                        in_flight,
+                        responses,
+                    }
+                }
+
+                StateProj::SecondFuturesPending {
+                    in_flight,
+                    responses,
+                } => loop {
+                    match ready!(in_flight.poll_next_unpin(cx)) {
+                        Some(Ok(response)) => responses.push(response),
+                        Some(Err(err)) => return Poll::Ready(Err(err)),
+                        None => {
+                            let reducer = this.reducer.take().unwrap();
+                            return Poll::Ready(Ok(reducer(mem::take(responses))));
+                        }
+                    }
+                },
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
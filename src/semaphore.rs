@@ -0,0 +1,63 @@
+use alloc::vec::Vec;
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+/// A minimal async semaphore shared by the services that limit how many calls are in flight.
+#[derive(Debug)]
+pub(crate) struct Semaphore {
+    state: Mutex<SemaphoreState>,
+}
+
+#[derive(Debug)]
+struct SemaphoreState {
+    available: usize,
+    wakers: Vec<Waker>,
+}
+
+impl Semaphore {
+    pub(crate) fn new(permits: usize) -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(SemaphoreState {
+                available: permits,
+                wakers: Vec::new(),
+            }),
+        })
+    }
+
+    pub(crate) fn poll_acquire(this: &Arc<Self>, cx: &mut Context<'_>) -> Poll<Permit> {
+        let mut state = this.state.lock().unwrap();
+        if state.available > 0 {
+            state.available -= 1;
+            Poll::Ready(Permit {
+                semaphore: Arc::clone(this),
+            })
+        } else {
+            if !state.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                state.wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        }
+    }
+}
+
+/// Returns a slot to the semaphore when dropped, so calls are accounted for even if their
+/// response future is dropped early.
+#[derive(Debug)]
+pub(crate) struct Permit {
+    semaphore: Arc<Semaphore>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let wakers = {
+            let mut state = self.semaphore.state.lock().unwrap();
+            state.available += 1;
+            std::mem::take(&mut state.wakers)
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+/// A cheaply cloneable handle to a service shared behind a lock.
+///
+/// Cloning a [`SharedService`] clones the handle, not the service, so it can be used as the
+/// second service of a [`Pipeline`] when the service doesn't implement [`Clone`] or is expensive
+/// to clone. The lock is only held while polling the service for readiness and calling it,
+/// never while its response future runs.
+///
+/// Since every handle shares the same service, readiness observed through one handle can be
+/// used up by a call through another before the first handle is called. This is fine for
+/// services that are always ready. For others, [`Exclusive::from_shared`] polls the service for
+/// readiness and calls it while holding the lock once.
+///
+/// ```
+/// use tower_pipeline::{PipelineExt, SharedService};
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = SharedService::new(service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// }));
+///
+/// let combined = length_svc.pipeline(double_svc.clone());
+///
+/// assert_eq!(combined.oneshot("rust").await.unwrap(), 8);
+/// # }
+/// ```
+///
+/// [`Pipeline`]: crate::Pipeline
+/// [`Exclusive::from_shared`]: crate::Exclusive::from_shared
+#[derive(Debug, Default)]
+pub struct SharedService<S> {
+    inner: Arc<Mutex<S>>,
+}
+
+impl<S> SharedService<S> {
+    /// Create a new [`SharedService`].
+    pub fn new(inner: S) -> Self {
+        Self::from_arc(Arc::new(Mutex::new(inner)))
+    }
+
+    /// Create a new [`SharedService`] from a service that is already shared.
+    pub fn from_arc(inner: Arc<Mutex<S>>) -> Self {
+        Self { inner }
+    }
+
+    /// Returns `true` if both handles share the same service.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    /// Consume `self`, returning the shared service.
+    pub fn into_arc(self) -> Arc<Mutex<S>> {
+        self.inner
+    }
+
+    pub(crate) fn lock(&self) -> MutexGuard<'_, S> {
+        self.inner.lock().unwrap()
+    }
+}
+
+impl<S> Clone for SharedService<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<S, R> Service<R> for SharedService<S>
+where
+    S: Service<R>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.lock().poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        self.lock().call(req)
+    }
+}
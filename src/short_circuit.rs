@@ -0,0 +1,174 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    ops::ControlFlow,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Two services combined where the first service decides whether the second service is called.
+///
+/// The first service responds with a [`ControlFlow`]. [`ControlFlow::Continue`] passes the
+/// value on to the second service, while [`ControlFlow::Break`] is the response of the
+/// pipeline and the second service isn't driven to readiness or called. This makes it possible
+/// to return cache hits or reject requests early.
+///
+/// Created with [`Pipeline::short_circuit`].
+///
+/// ```
+/// use tower_pipeline::Pipeline;
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use std::ops::ControlFlow;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let cache_svc = service_fn(|input: &'static str| async move {
+///     if input == "cached" {
+///         Ok::<_, BoxError>(ControlFlow::Break("from cache".to_owned()))
+///     } else {
+///         Ok(ControlFlow::Continue(input))
+///     }
+/// });
+///
+/// let fetch_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(format!("fetched {}", input))
+/// });
+///
+/// let combined = Pipeline::new(cache_svc, fetch_svc).short_circuit();
+///
+/// let result = combined.clone().oneshot("cached").await.unwrap();
+/// assert_eq!(result, "from cache");
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+/// assert_eq!(result, "fetched rust");
+/// # }
+/// ```
+///
+/// [`Pipeline::short_circuit`]: crate::Pipeline::short_circuit
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShortCircuit<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> ShortCircuit<A, B> {
+    /// Create a new [`ShortCircuit`] from two [`Service`]s.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, T, A, B> Service<R> for ShortCircuit<A, B>
+where
+    A: Service<R, Response = ControlFlow<B::Response, T>>,
+    B: Service<T> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = ShortCircuitFuture<R, T, A, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        ShortCircuitFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            second: Some(self.second.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`ShortCircuit`].
+    pub struct ShortCircuitFuture<R, T, A, B>
+    where
+        A: Service<R>,
+        B: Service<T>,
+    {
+        #[pin]
+        state: State<R, T, A, B>,
+        second: Option<B>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, T, A, B>
+    where
+        A: Service<R>,
+        B: Service<T>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturePending { #[pin] future: Oneshot<B, T> },
+    }
+}
+
+impl<R, T, A, B> Future for ShortCircuitFuture<R, T, A, B>
+where
+    A: Service<R, Response = ControlFlow<B::Response, T>>,
+    B: Service<T>,
+    A::Error: Into<B::Error>,
+{
+    type Output = Result<B::Response, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    match ready!(future.poll(cx).map_err(Into::into)?) {
+                        ControlFlow::Break(res) => return Poll::Ready(Ok(res)),
+                        ControlFlow::Continue(first_res) => {
+                            let second = this.second.take().unwrap();
+                            State::SecondFuturePending {
+                                future: Oneshot::new(second, first_res),
+                            }
+                        }
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use crate::BoxError;
+use alloc::{boxed::Box, vec::Vec};
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    hash::Hash,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+use tower_service::Service;
+
+/// A service where concurrent calls with the same key share a single call to the inner service.
+///
+/// The key of each request is extracted with a closure. The first call with a key calls the
+/// inner service and calls made with the same key while it is in flight wait for its result,
+/// rather than calling the inner service again. Once the call completes the next call with the
+/// key calls the inner service again, so nothing is cached.
+///
+/// Since the result is shared errors are wrapped in a [`SharedError`]. If the future of the
+/// call that is shared is dropped before completing the calls waiting for it fail with an
+/// [`Abandoned`] error.
+///
+/// Usually created with [`Pipeline::singleflight_second`], which prevents a thundering herd on
+/// the second service when many requests produce the same intermediate value.
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, Service, ServiceExt};
+/// use std::sync::{
+///     atomic::{AtomicUsize, Ordering},
+///     Arc,
+/// };
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let calls = Arc::new(AtomicUsize::new(0));
+///
+/// let expensive_svc = service_fn({
+///     let calls = Arc::clone(&calls);
+///     move |input: usize| {
+///         calls.fetch_add(1, Ordering::SeqCst);
+///         async move {
+///             tokio::task::yield_now().await;
+///             Ok::<_, BoxError>(input * 2)
+///         }
+///     }
+/// });
+///
+/// let mut combined = length_svc
+///     .pipeline(expensive_svc)
+///     .singleflight_second(|len: &usize| *len);
+///
+/// let a = combined.ready().await.unwrap().call("rust");
+/// let b = combined.ready().await.unwrap().call("tide");
+///
+/// let (a, b) = tokio::join!(a, b);
+///
+/// assert_eq!(a.unwrap(), 8);
+/// assert_eq!(b.unwrap(), 8);
+/// assert_eq!(calls.load(Ordering::SeqCst), 1);
+/// # }
+/// ```
+///
+/// [`Pipeline::singleflight_second`]: crate::Pipeline::singleflight_second
+pub struct Singleflight<S, F, K, V> {
+    inner: S,
+    key: F,
+    flights: Arc<Mutex<HashMap<K, Arc<Mutex<Flight<V>>>>>>,
+}
+
+struct Flight<V> {
+    result: Option<Result<V, SharedError>>,
+    abandoned: bool,
+    wakers: Vec<Waker>,
+}
+
+impl<S, F, K, V> Singleflight<S, F, K, V> {
+    /// Create a new [`Singleflight`] that extracts the key of each request with `key`.
+    pub fn new(inner: S, key: F) -> Self {
+        Self {
+            inner,
+            key,
+            flights: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, F, K, V> Clone for Singleflight<S, F, K, V>
+where
+    S: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            key: self.key.clone(),
+            flights: Arc::clone(&self.flights),
+        }
+    }
+}
+
+impl<S, F, K, V> fmt::Debug for Singleflight<S, F, K, V>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Singleflight")
+            .field("inner", &self.inner)
+            .field("in_flight", &self.flights.lock().unwrap().len())
+            .finish()
+    }
+}
+
+impl<T, S, F, K> Service<T> for Singleflight<S, F, K, S::Response>
+where
+    S: Service<T>,
+    S::Error: Into<BoxError>,
+    S::Response: Clone,
+    F: Fn(&T) -> K,
+    K: Hash + Eq + Clone,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = SingleflightFuture<S::Future, K, S::Response>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        let key = (self.key)(&req);
+        let mut flights = self.flights.lock().unwrap();
+
+        if let Some(flight) = flights.get(&key) {
+            return SingleflightFuture {
+                state: State::Waiting {
+                    flight: Arc::clone(flight),
+                },
+            };
+        }
+
+        let flight = Arc::new(Mutex::new(Flight {
+            result: None,
+            abandoned: false,
+            wakers: Vec::new(),
+        }));
+        flights.insert(key.clone(), Arc::clone(&flight));
+        drop(flights);
+
+        SingleflightFuture {
+            state: State::Calling {
+                future: self.inner.call(req),
+                guard: FlightGuard {
+                    key,
+                    flight,
+                    flights: Arc::clone(&self.flights),
+                    done: false,
+                },
+            },
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Singleflight`].
+    pub struct SingleflightFuture<Fut, K, V>
+    where
+        K: Hash,
+        K: Eq,
+    {
+        #[pin]
+        state: State<Fut, K, V>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<Fut, K, V>
+    where
+        K: Hash,
+        K: Eq,
+    {
+        Calling { #[pin] future: Fut, guard: FlightGuard<K, V> },
+        Waiting { flight: Arc<Mutex<Flight<V>>> },
+    }
+}
+
+// removes the flight once the call completes and tells the waiting calls if it doesn't
+struct FlightGuard<K, V>
+where
+    K: Hash + Eq,
+{
+    key: K,
+    flight: Arc<Mutex<Flight<V>>>,
+    flights: Arc<Mutex<HashMap<K, Arc<Mutex<Flight<V>>>>>>,
+    done: bool,
+}
+
+impl<K, V> FlightGuard<K, V>
+where
+    K: Hash + Eq,
+{
+    fn finish(&mut self, result: Option<Result<V, SharedError>>) {
+        self.done = true;
+
+        let mut flights = self.flights.lock().unwrap();
+        if flights
+            .get(&self.key)
+            .is_some_and(|flight| Arc::ptr_eq(flight, &self.flight))
+        {
+            flights.remove(&self.key);
+        }
+        drop(flights);
+
+        let mut flight = self.flight.lock().unwrap();
+        flight.abandoned = result.is_none();
+        flight.result = result;
+        for waker in flight.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+impl<K, V> Drop for FlightGuard<K, V>
+where
+    K: Hash + Eq,
+{
+    fn drop(&mut self) {
+        if !self.done {
+            self.finish(None);
+        }
+    }
+}
+
+impl<Fut, K, V, E> Future for SingleflightFuture<Fut, K, V>
+where
+    Fut: Future<Output = Result<V, E>>,
+    E: Into<BoxError>,
+    K: Hash + Eq,
+    V: Clone,
+{
+    type Output = Result<V, BoxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().state.project() {
+            StateProj::Calling { future, guard } => {
+                let result = futures_util::ready!(future.poll(cx))
+                    .map_err(|err| SharedError::new(err.into()));
+                guard.finish(Some(result.clone()));
+                Poll::Ready(result.map_err(|err| Box::new(err) as BoxError))
+            }
+            StateProj::Waiting { flight } => {
+                let mut flight = flight.lock().unwrap();
+                if let Some(result) = &flight.result {
+                    return Poll::Ready(result.clone().map_err(|err| Box::new(err) as BoxError));
+                }
+                if flight.abandoned {
+                    return Poll::Ready(Err(Box::new(Abandoned(()))));
+                }
+                if !flight
+                    .wakers
+                    .iter()
+                    .any(|waker| waker.will_wake(cx.waker()))
+                {
+                    flight.wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Error produced by [`Singleflight`] and `Batch` when a call shared between several callers
+/// fails.
+///
+/// The original error is available with [`SharedError::inner`] or [`Error::source`].
+#[derive(Debug, Clone)]
+pub struct SharedError {
+    inner: Arc<BoxError>,
+}
+
+impl SharedError {
+    pub(crate) fn new(err: BoxError) -> Self {
+        Self {
+            inner: Arc::new(err),
+        }
+    }
+
+    /// The error produced by the inner service.
+    pub fn inner(&self) -> &(dyn Error + Send + Sync + 'static) {
+        &**self.inner
+    }
+}
+
+impl fmt::Display for SharedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl Error for SharedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&**self.inner)
+    }
+}
+
+/// Error produced by [`Singleflight`] when the shared call was dropped before completing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Abandoned(());
+
+impl fmt::Display for Abandoned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("shared call was dropped before completing")
+    }
+}
+
+impl Error for Abandoned {}
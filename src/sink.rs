@@ -0,0 +1,141 @@
+use crate::BoxError;
+use alloc::boxed::Box;
+use futures_util::Sink;
+use std::future::Future;
+use std::{
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+/// A service that writes each request into a [`Sink`].
+///
+/// This lets a sink, such as a channel or a socket writer, be the last stage of a pipeline.
+/// The response future resolves once the item has been written and the sink flushed.
+///
+/// `poll_ready` reflects readiness of the sink, so sink backpressure becomes backpressure on
+/// the service. Clones write into the same sink.
+///
+/// Usually created with [`PipelineExt::pipeline_into_sink`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use tokio_util::sync::PollSender;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+///
+/// let combined = length_svc.pipeline_into_sink(PollSender::new(tx));
+///
+/// combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(rx.recv().await, Some(4));
+/// # }
+/// ```
+///
+/// [`PipelineExt::pipeline_into_sink`]: crate::PipelineExt::pipeline_into_sink
+pub struct SinkStage<Si> {
+    sink: Arc<Mutex<Pin<Box<Si>>>>,
+}
+
+impl<Si> SinkStage<Si> {
+    /// Create a new [`SinkStage`] that writes into `sink`.
+    pub fn new(sink: Si) -> Self {
+        Self {
+            sink: Arc::new(Mutex::new(Box::pin(sink))),
+        }
+    }
+}
+
+impl<Si> Clone for SinkStage<Si> {
+    fn clone(&self) -> Self {
+        Self {
+            sink: Arc::clone(&self.sink),
+        }
+    }
+}
+
+impl<Si> fmt::Debug for SinkStage<Si> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SinkStage").finish()
+    }
+}
+
+impl<Si, T> Service<T> for SinkStage<Si>
+where
+    Si: Sink<T>,
+    Si::Error: Into<BoxError>,
+{
+    type Response = ();
+    type Error = BoxError;
+    type Future = SinkStageFuture<Si, T>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.sink
+            .lock()
+            .unwrap()
+            .as_mut()
+            .poll_ready(cx)
+            .map_err(Into::into)
+    }
+
+    fn call(&mut self, item: T) -> Self::Future {
+        // clones share the sink, so another clone might have used the capacity reserved by
+        // `poll_ready`. the future therefore waits for readiness again before sending
+        SinkStageFuture {
+            sink: Arc::clone(&self.sink),
+            item: Some(item),
+        }
+    }
+}
+
+/// Response future of [`SinkStage`].
+pub struct SinkStageFuture<Si, T> {
+    sink: Arc<Mutex<Pin<Box<Si>>>>,
+    item: Option<T>,
+}
+
+impl<Si, T> Unpin for SinkStageFuture<Si, T> {}
+
+impl<Si, T> fmt::Debug for SinkStageFuture<Si, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SinkStageFuture")
+            .field("sent", &self.item.is_none())
+            .finish()
+    }
+}
+
+impl<Si, T> Future for SinkStageFuture<Si, T>
+where
+    Si: Sink<T>,
+    Si::Error: Into<BoxError>,
+{
+    type Output = Result<(), BoxError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut sink = this.sink.lock().unwrap();
+
+        if this.item.is_some() {
+            match sink.as_mut().poll_ready(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err.into())),
+                Poll::Pending => return Poll::Pending,
+            }
+            let item = this.item.take().unwrap();
+            if let Err(err) = sink.as_mut().start_send(item) {
+                return Poll::Ready(Err(err.into()));
+            }
+        }
+
+        sink.as_mut().poll_flush(cx).map_err(Into::into)
+    }
+}
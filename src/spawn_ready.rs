@@ -0,0 +1,161 @@
+use crate::{oneshot::ReadyOneshotFuture, BoxError};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::task::JoinHandle;
+use tower_service::Service;
+
+/// A service that drives the inner service to readiness on a spawned task.
+///
+/// When the inner service isn't ready it is moved to a task spawned with [`tokio::spawn`] that
+/// polls it until it is, so slow readiness work, such as establishing a connection, makes
+/// progress without the caller polling it. A clone of the service takes its place until the
+/// task is done.
+///
+/// Usually created with [`Pipeline::spawn_ready_second`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let combined = length_svc.pipeline(double_svc).spawn_ready_second();
+///
+/// assert_eq!(combined.oneshot("rust").await.unwrap(), 8);
+/// # }
+/// ```
+///
+/// [`Pipeline::spawn_ready_second`]: crate::Pipeline::spawn_ready_second
+#[derive(Debug)]
+pub struct SpawnReady<S> {
+    inner: S,
+    pending: Option<Pending<S>>,
+}
+
+/// The task driving the inner service to readiness, aborted when dropped.
+#[derive(Debug)]
+struct Pending<S>(JoinHandle<Result<S, BoxError>>);
+
+impl<S> Drop for Pending<S> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+impl<S> SpawnReady<S> {
+    /// Create a new [`SpawnReady`].
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            pending: None,
+        }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    ///
+    /// If the inner service is being driven to readiness on a spawned task, the task is aborted
+    /// and the clone that took its place is returned.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Clone for SpawnReady<S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        // the clone hasn't been driven to readiness
+        Self::new(self.inner.clone())
+    }
+}
+
+impl<S, R> Service<R> for SpawnReady<S>
+where
+    S: Service<R> + Clone + Send + 'static,
+    S::Error: Into<BoxError>,
+    R: 'static,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = SpawnReadyFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        loop {
+            if let Some(pending) = &mut self.pending {
+                let result = match ready!(Pin::new(&mut pending.0).poll(cx)) {
+                    Ok(result) => result,
+                    Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+                    Err(err) => Err(err.into()),
+                };
+                self.pending = None;
+                self.inner = result?;
+                return Poll::Ready(Ok(()));
+            }
+
+            match self.inner.poll_ready(cx) {
+                Poll::Ready(result) => return Poll::Ready(result.map_err(Into::into)),
+                Poll::Pending => {
+                    // move the service that isn't ready to a task that drives it and leave a clone
+                    // in its place
+                    let clone = self.inner.clone();
+                    let inner = std::mem::replace(&mut self.inner, clone);
+                    self.pending = Some(Pending(tokio::spawn(async move {
+                        ReadyOneshotFuture::<S, R>::new(inner)
+                            .await
+                            .map_err(Into::into)
+                    })));
+                }
+            }
+        }
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        SpawnReadyFuture {
+            inner: self.inner.call(req),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`SpawnReady`].
+    pub struct SpawnReadyFuture<F> {
+        #[pin]
+        inner: F,
+    }
+}
+
+impl<F, T, E> Future for SpawnReadyFuture<F>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Into<BoxError>,
+{
+    type Output = Result<T, BoxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().inner.poll(cx).map_err(Into::into)
+    }
+}
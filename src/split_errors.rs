@@ -0,0 +1,166 @@
+use crate::{oneshot::Oneshot, PipelineError};
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Two services combined where the response of the first is the request of the second, and
+/// errors are reported as a [`PipelineError`].
+///
+/// Unlike [`Pipeline`] the error types of the two services are unrelated.
+///
+/// Created with [`Pipeline::with_split_errors`].
+///
+/// ```
+/// use tower_pipeline::{Pipeline, PipelineError};
+/// use tower::{service_fn, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let parse_svc = service_fn(|input: &'static str| async move {
+///     input.parse::<u32>()
+/// });
+///
+/// let double_svc = service_fn(|input: u32| async move {
+///     input.checked_mul(2).ok_or("overflow")
+/// });
+///
+/// let combined = Pipeline::with_split_errors(parse_svc, double_svc);
+///
+/// let result = combined.clone().oneshot("21").await;
+/// assert_eq!(result, Ok(42));
+///
+/// let result = combined.clone().oneshot("rust").await;
+/// assert!(matches!(result, Err(PipelineError::First(_))));
+///
+/// let result = combined.oneshot("4294967295").await;
+/// assert_eq!(result, Err(PipelineError::Second("overflow")));
+/// # }
+/// ```
+///
+/// [`Pipeline`]: crate::Pipeline
+/// [`Pipeline::with_split_errors`]: crate::Pipeline::with_split_errors
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SplitErrors<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> SplitErrors<A, B> {
+    /// Create a new [`SplitErrors`] from two [`Service`]s.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, A, B> Service<R> for SplitErrors<A, B>
+where
+    A: Service<R>,
+    B: Service<A::Response> + Clone,
+{
+    type Response = B::Response;
+    type Error = PipelineError<A::Error, B::Error>;
+    type Future = SplitErrorsFuture<R, A, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(PipelineError::First)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        SplitErrorsFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            second: Some(self.second.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`SplitErrors`].
+    pub struct SplitErrorsFuture<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        #[pin]
+        state: State<R, A, B>,
+        second: Option<B>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturePending { #[pin] future: Oneshot<B, A::Response> },
+    }
+}
+
+impl<R, A, B> Future for SplitErrorsFuture<R, A, B>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+{
+    type Output = Result<B::Response, PipelineError<A::Error, B::Error>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(PipelineError::First)?);
+                    let second = this.second.take().unwrap();
+                    State::SecondFuturePending {
+                        future: Oneshot::new(second, first_res),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => {
+                    return future.poll(cx).map_err(PipelineError::Second)
+                }
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
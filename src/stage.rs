@@ -0,0 +1,101 @@
+use core::fmt;
+
+/// One of the two stages of a pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    /// The first service.
+    First,
+    /// The second service.
+    Second,
+}
+
+impl Stage {
+    pub(crate) fn default_name(self) -> &'static str {
+        match self {
+            Stage::First => "first",
+            Stage::Second => "second",
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.default_name())
+    }
+}
+
+/// The names of the two stages of a pipeline.
+///
+/// The names are used in diagnostics, such as the spans created with the `tracing` feature and
+/// the errors of stage aware middleware like `Timeout` and [`LoadShed`], so logs can say
+/// `geo-lookup` and `enrichment` rather than `first` and `second`.
+///
+/// Set with [`Pipeline::named`]. Defaults to `"first"` and `"second"`.
+///
+/// [`LoadShed`]: crate::LoadShed
+/// [`Pipeline::named`]: crate::Pipeline::named
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StageNames {
+    first: &'static str,
+    second: &'static str,
+}
+
+impl StageNames {
+    /// Create a new [`StageNames`].
+    pub fn new(first: &'static str, second: &'static str) -> Self {
+        Self { first, second }
+    }
+
+    /// The name of the first stage.
+    pub fn first(&self) -> &'static str {
+        self.first
+    }
+
+    /// The name of the second stage.
+    pub fn second(&self) -> &'static str {
+        self.second
+    }
+
+    /// The name of the given stage.
+    pub fn get(&self, stage: Stage) -> &'static str {
+        match stage {
+            Stage::First => self.first,
+            Stage::Second => self.second,
+        }
+    }
+}
+
+impl Default for StageNames {
+    fn default() -> Self {
+        Self::new(Stage::First.default_name(), Stage::Second.default_name())
+    }
+}
+
+/// How far a [`ResponseFuture`] has progressed.
+///
+/// Returned by [`ResponseFuture::stage`].
+///
+/// [`ResponseFuture`]: crate::future::ResponseFuture
+/// [`ResponseFuture::stage`]: crate::future::ResponseFuture::stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResponseStage {
+    /// Waiting for the response of the first service.
+    FirstPending,
+    /// The first service has responded and the second service is being driven to readiness.
+    WaitingSecondReady,
+    /// Waiting for the response of the second service.
+    SecondPending,
+    /// The future has completed.
+    Done,
+}
+
+impl fmt::Display for ResponseStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponseStage::FirstPending => f.write_str("first pending"),
+            ResponseStage::WaitingSecondReady => f.write_str("waiting for second to be ready"),
+            ResponseStage::SecondPending => f.write_str("second pending"),
+            ResponseStage::Done => f.write_str("done"),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::Pipeline;
+use core::future::Future;
+use core::{
+    convert::Infallible,
+    fmt,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Create a [`Pipeline`] from two async closures.
+///
+/// The closures return plain values rather than [`Result`]s, so no error types have to be
+/// spelled out. The resulting pipeline never fails.
+///
+/// ```
+/// use tower_pipeline::pipeline_fn;
+/// use tower::ServiceExt;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let combined = pipeline_fn(
+///     |input: &'static str| async move { input.len() },
+///     |len: usize| async move { len * 2 },
+/// );
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// # }
+/// ```
+pub fn pipeline_fn<F, G>(first: F, second: G) -> Pipeline<StageFn<F>, StageFn<G>> {
+    Pipeline::new(StageFn::new(first), StageFn::new(second))
+}
+
+/// A service built from an async closure that can't fail.
+///
+/// The service has the error type `E`, which defaults to [`Infallible`], but never produces
+/// errors. That allows it to be pipelined with services of any error type.
+///
+/// Created with [`pipeline_fn`] or [`PipelineExt::pipeline_fn`].
+///
+/// [`PipelineExt::pipeline_fn`]: crate::PipelineExt::pipeline_fn
+pub struct StageFn<F, E = Infallible> {
+    f: F,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<F, E> StageFn<F, E> {
+    /// Create a new [`StageFn`].
+    pub fn new(f: F) -> Self {
+        Self {
+            f,
+            _error: PhantomData,
+        }
+    }
+}
+
+impl<F, E> Clone for StageFn<F, E>
+where
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self::new(self.f.clone())
+    }
+}
+
+impl<F, E> Copy for StageFn<F, E> where F: Copy {}
+
+impl<F, E> fmt::Debug for StageFn<F, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StageFn")
+            .field("f", &format_args!("{}", core::any::type_name::<F>()))
+            .finish()
+    }
+}
+
+impl<R, F, Fut, E> Service<R> for StageFn<F, E>
+where
+    F: FnMut(R) -> Fut,
+    Fut: Future,
+{
+    type Response = Fut::Output;
+    type Error = E;
+    type Future = StageFnFuture<Fut, E>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        StageFnFuture {
+            future: (self.f)(req),
+            _error: PhantomData,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`StageFn`].
+    pub struct StageFnFuture<Fut, E> {
+        #[pin]
+        future: Fut,
+        _error: PhantomData<fn() -> E>,
+    }
+}
+
+impl<Fut, E> Future for StageFnFuture<Fut, E>
+where
+    Fut: Future,
+{
+    type Output = Result<Fut::Output, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().future.poll(cx).map(Ok)
+    }
+}
@@ -0,0 +1,194 @@
+use crate::oneshot::Oneshot;
+use alloc::{sync::Arc, vec::Vec};
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// A service whose response is routed to one of several second services, picked by a closure.
+///
+/// The picker is called with the response of the first service and the second services and
+/// returns the index of the service to call. Only the service that is picked is driven to
+/// readiness and called.
+///
+/// The second services are kept behind an [`Arc`] so they aren't all cloned for each call.
+///
+/// Created with [`Pipeline::steer`] or [`PipelineExt::pipeline_steer`].
+///
+/// # Panics
+///
+/// Calls panic if the picker returns an index that is out of bounds.
+///
+/// # Example
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let shard_svc = |shard: usize| {
+///     service_fn(move |input: usize| async move {
+///         Ok::<_, BoxError>(format!("{} handled by shard {}", input, shard))
+///     })
+/// };
+///
+/// let combined = length_svc.pipeline_steer(
+///     |len: &usize, shards: &[_]| len % shards.len(),
+///     vec![shard_svc(0), shard_svc(1), shard_svc(2)],
+/// );
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, "4 handled by shard 1");
+/// # }
+/// ```
+///
+/// [`Pipeline::steer`]: crate::Pipeline::steer
+/// [`PipelineExt::pipeline_steer`]: crate::PipelineExt::pipeline_steer
+#[derive(Debug, Clone, Default)]
+pub struct Steer<A, P, B> {
+    first: A,
+    picker: P,
+    seconds: Arc<Vec<B>>,
+}
+
+impl<A, P, B> Steer<A, P, B> {
+    /// Create a new [`Steer`] from a [`Service`], a picker, and the [`Service`]s to pick from.
+    pub fn new(first: A, picker: P, seconds: Vec<B>) -> Self {
+        Self {
+            first,
+            picker,
+            seconds: Arc::new(seconds),
+        }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second services.
+    pub fn seconds_as_ref(&self) -> &[B] {
+        &self.seconds
+    }
+}
+
+impl<A, P, B> Steer<A, P, B>
+where
+    B: Clone,
+{
+    /// Get a mutable reference to the second services.
+    ///
+    /// The services are cloned if response futures of calls in flight still refer to them.
+    pub fn seconds_as_mut(&mut self) -> &mut Vec<B> {
+        Arc::make_mut(&mut self.seconds)
+    }
+
+    /// Consume `self`, returning the second services
+    pub fn into_seconds(self) -> Vec<B> {
+        Arc::try_unwrap(self.seconds).unwrap_or_else(|seconds| (*seconds).clone())
+    }
+}
+
+impl<R, A, P, B> Service<R> for Steer<A, P, B>
+where
+    A: Service<R>,
+    P: FnOnce(&A::Response, &[B]) -> usize + Clone,
+    B: Service<A::Response> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = SteerFuture<R, A, P, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        SteerFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            picker: Some(self.picker.clone()),
+            seconds: Arc::clone(&self.seconds),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Steer`].
+    pub struct SteerFuture<R, A, P, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        #[pin]
+        state: State<R, A, B>,
+        picker: Option<P>,
+        seconds: Arc<Vec<B>>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturePending { #[pin] future: Oneshot<B, A::Response> },
+    }
+}
+
+impl<R, A, P, B> Future for SteerFuture<R, A, P, B>
+where
+    A: Service<R>,
+    P: FnOnce(&A::Response, &[B]) -> usize,
+    B: Service<A::Response> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Output = Result<B::Response, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    let picker = this.picker.take().unwrap();
+                    let idx = picker(&first_res, this.seconds);
+                    let second = this.seconds[idx].clone();
+                    State::SecondFuturePending {
+                        future: Oneshot::new(second, first_res),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
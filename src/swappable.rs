@@ -0,0 +1,148 @@
+use crate::{ResponseFuture, StageNames};
+use std::{
+    sync::{Arc, RwLock},
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+/// Two services combined where the response of the first is the request of the second, and the
+/// second service can be replaced at runtime through a [`StageHandle`].
+///
+/// Calls made after the second service has been replaced use the new service. Calls already in
+/// flight keep using the service they started with.
+///
+/// Created with [`Pipeline::swappable`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt, util::BoxCloneService};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = BoxCloneService::new(service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// }));
+///
+/// let (combined, handle) = length_svc.pipeline(double_svc).swappable();
+///
+/// assert_eq!(combined.clone().oneshot("rust").await.unwrap(), 8);
+///
+/// // for example after reloading configuration
+/// handle.replace(BoxCloneService::new(service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 3)
+/// })));
+///
+/// assert_eq!(combined.oneshot("rust").await.unwrap(), 12);
+/// # }
+/// ```
+///
+/// [`Pipeline::swappable`]: crate::Pipeline::swappable
+#[derive(Debug)]
+pub struct Swappable<A, B> {
+    first: A,
+    second: Arc<RwLock<B>>,
+    names: StageNames,
+}
+
+impl<A, B> Swappable<A, B> {
+    /// Create a new [`Swappable`] from two [`Service`]s, along with a [`StageHandle`] for
+    /// replacing the second service.
+    pub fn new(first: A, second: B) -> (Self, StageHandle<B>) {
+        let second = Arc::new(RwLock::new(second));
+        let handle = StageHandle {
+            second: Arc::clone(&second),
+        };
+        let swappable = Self {
+            first,
+            second,
+            names: StageNames::default(),
+        };
+        (swappable, handle)
+    }
+
+    pub(crate) fn with_names(mut self, names: StageNames) -> Self {
+        self.names = names;
+        self
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+}
+
+impl<A, B> Clone for Swappable<A, B>
+where
+    A: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            first: self.first.clone(),
+            second: Arc::clone(&self.second),
+            names: self.names,
+        }
+    }
+}
+
+impl<R, A, B> Service<R> for Swappable<A, B>
+where
+    A: Service<R>,
+    B: Service<A::Response> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = ResponseFuture<R, A, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        let second = self.second.read().unwrap().clone();
+        ResponseFuture::new(&mut self.first, req, second, self.names)
+    }
+}
+
+/// Handle for replacing the second service of a [`Swappable`].
+#[derive(Debug)]
+pub struct StageHandle<B> {
+    second: Arc<RwLock<B>>,
+}
+
+impl<B> StageHandle<B> {
+    /// Replace the second service, returning the previous one.
+    pub fn replace(&self, second: B) -> B {
+        std::mem::replace(&mut *self.second.write().unwrap(), second)
+    }
+
+    /// Get a clone of the current second service.
+    pub fn get(&self) -> B
+    where
+        B: Clone,
+    {
+        self.second.read().unwrap().clone()
+    }
+}
+
+impl<B> Clone for StageHandle<B> {
+    fn clone(&self) -> Self {
+        Self {
+            second: Arc::clone(&self.second),
+        }
+    }
+}
@@ -0,0 +1,356 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::{
+    future::{maybe_done, MaybeDone},
+    ready,
+};
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Two services combined where the response of the first is the request of the second, and a
+/// copy of it is also sent to a side service.
+///
+/// The side service is called concurrently with the second service and its response is
+/// discarded. By default errors from the side service are discarded as well, use
+/// [`Tee::propagate_side_errors`] to fail the whole call instead.
+///
+/// The response future doesn't complete until both the second and the side service are done, so
+/// a slow side service delays every response and one that never completes makes every call hang.
+/// Use `Tee::detach_side` to call the side service on a spawned task instead.
+///
+/// Created with [`Pipeline::tee`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use std::sync::{Arc, Mutex};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// // service that records the lengths it has seen
+/// let seen = Arc::new(Mutex::new(Vec::new()));
+/// let audit_svc = service_fn({
+///     let seen = Arc::clone(&seen);
+///     move |input: usize| {
+///         let seen = Arc::clone(&seen);
+///         async move {
+///             seen.lock().unwrap().push(input);
+///             Ok::<_, BoxError>(())
+///         }
+///     }
+/// });
+///
+/// let combined = length_svc.pipeline(double_svc).tee(audit_svc);
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// assert_eq!(*seen.lock().unwrap(), vec![4]);
+/// # }
+/// ```
+///
+/// [`Pipeline::tee`]: crate::Pipeline::tee
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tee<A, B, C> {
+    first: A,
+    second: B,
+    side: C,
+    propagate_side_errors: bool,
+}
+
+impl<A, B, C> Tee<A, B, C> {
+    /// Create a new [`Tee`] from a first, second, and side [`Service`].
+    pub fn new(first: A, second: B, side: C) -> Self {
+        Self {
+            first,
+            second,
+            side,
+            propagate_side_errors: false,
+        }
+    }
+
+    /// Fail the call if the side service fails.
+    ///
+    /// Errors from the side service are discarded by default.
+    pub fn propagate_side_errors(mut self, propagate: bool) -> Self {
+        self.propagate_side_errors = propagate;
+        self
+    }
+
+    /// Call the side service on a task spawned with [`tokio::spawn`], so the response is
+    /// returned as soon as the second service is done.
+    ///
+    /// Errors of the side service are always discarded once it is detached, regardless of
+    /// [`Tee::propagate_side_errors`].
+    ///
+    /// # Panics
+    ///
+    /// Calling the returned service panics if called outside a tokio runtime.
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError, ServiceExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// // a side service that never completes
+    /// let audit_svc = service_fn(|_input: usize| async move {
+    ///     std::future::pending::<Result<(), BoxError>>().await
+    /// });
+    ///
+    /// let combined = length_svc.pipeline(double_svc).tee(audit_svc).detach_side();
+    ///
+    /// assert_eq!(combined.oneshot("rust").await.unwrap(), 8);
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn detach_side(self) -> Tee<A, B, Detached<C>> {
+        Tee {
+            first: self.first,
+            second: self.second,
+            side: Detached { inner: self.side },
+            propagate_side_errors: self.propagate_side_errors,
+        }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+
+    /// Get a reference to the side service.
+    pub fn side_as_ref(&self) -> &C {
+        &self.side
+    }
+
+    /// Get a mutable reference to the side service.
+    pub fn side_as_mut(&mut self) -> &mut C {
+        &mut self.side
+    }
+
+    /// Consume `self`, returning the side service
+    pub fn into_side(self) -> C {
+        self.side
+    }
+}
+
+impl<R, A, B, C> Service<R> for Tee<A, B, C>
+where
+    A: Service<R>,
+    A::Response: Clone,
+    B: Service<A::Response> + Clone,
+    C: Service<A::Response> + Clone,
+    A::Error: Into<B::Error>,
+    C::Error: Into<B::Error>,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = TeeFuture<R, A, B, C>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        TeeFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            second: Some(self.second.clone()),
+            side: Some(self.side.clone()),
+            propagate_side_errors: self.propagate_side_errors,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Tee`].
+    pub struct TeeFuture<R, A, B, C>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+        C: Service<A::Response>,
+    {
+        #[pin]
+        state: State<R, A, B, C>,
+        second: Option<B>,
+        side: Option<C>,
+        propagate_side_errors: bool,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B, C>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+        C: Service<A::Response>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondAndSidePending {
+            #[pin]
+            second: MaybeDone<Oneshot<B, A::Response>>,
+            #[pin]
+            side: MaybeDone<Oneshot<C, A::Response>>,
+        },
+    }
+}
+
+impl<R, A, B, C> Future for TeeFuture<R, A, B, C>
+where
+    A: Service<R>,
+    A::Response: Clone,
+    B: Service<A::Response>,
+    C: Service<A::Response>,
+    A::Error: Into<B::Error>,
+    C::Error: Into<B::Error>,
+{
+    type Output = Result<B::Response, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    let second = this.second.take().unwrap();
+                    let side = this.side.take().unwrap();
+                    State::SecondAndSidePending {
+                        side: maybe_done(Oneshot::new(side, first_res.clone())),
+                        second: maybe_done(Oneshot::new(second, first_res)),
+                    }
+                }
+
+                StateProj::SecondAndSidePending {
+                    mut second,
+                    mut side,
+                } => {
+                    let second_done = second.as_mut().poll(cx).is_ready();
+                    let side_done = side.as_mut().poll(cx).is_ready();
+
+                    if *this.propagate_side_errors {
+                        if let Some(Err(_)) = side.as_mut().output_mut() {
+                            if let Some(Err(err)) = side.take_output() {
+                                return Poll::Ready(Err(err.into()));
+                            }
+                        }
+                    }
+
+                    if second_done {
+                        if let Some(Err(_)) = second.as_mut().output_mut() {
+                            return Poll::Ready(second.take_output().unwrap());
+                        }
+                    }
+
+                    if second_done && side_done {
+                        return Poll::Ready(second.take_output().unwrap());
+                    }
+
+                    return Poll::Pending;
+                }
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
+
+/// A side service of a [`Tee`] that is called on a spawned task.
+///
+/// Created with [`Tee::detach_side`].
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Detached<S> {
+    inner: S,
+}
+
+#[cfg(feature = "tokio")]
+impl<S> Detached<S> {
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<S, R> Service<R> for Detached<S>
+where
+    S: Service<R> + Clone + Send + 'static,
+    S::Future: Send,
+    R: Send + 'static,
+{
+    type Response = ();
+    type Error = S::Error;
+    type Future = futures_util::future::Ready<Result<(), S::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // the spawned task drives the service to readiness
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        use futures_util::FutureExt;
+
+        tokio::spawn(Oneshot::new(self.inner.clone(), req).map(drop));
+        futures_util::future::ready(Ok(()))
+    }
+}
@@ -0,0 +1,330 @@
+use crate::BoxError;
+use alloc::{boxed::Box, collections::VecDeque};
+use futures_util::{ready, task::ArcWake};
+use pin_project_lite::pin_project;
+use proptest::{collection::vec, prelude::*, test_runner::TestRunner};
+use std::future::Future;
+use std::{
+    fmt,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+// how many times a call is polled before it is considered stuck. Every pending step wakes the
+// task immediately so a well-behaved stack completes long before this
+const MAX_POLLS: usize = 1_000;
+
+/// Check that a stack built from two stages upholds the [`Service`] contract, for randomized
+/// sequences of readiness and responses of the stages.
+///
+/// `make_stack` is called with two [`ContractStage`]s and should combine them the same way the
+/// real stages are combined. The stages add one to and double their request. For each test
+/// case they are scripted to be ready, pending, or fail in `poll_ready`, and to respond
+/// immediately, after being pending, or with an error. The stack is then called with a series of
+/// requests.
+///
+/// The stages panic if the stack calls them before `poll_ready` returned `Poll::Ready(Ok(()))`
+/// or polls their response futures after completion. The harness panics if a call never
+/// completes, if the stack returns `Poll::Pending` without arranging for the task to be woken,
+/// or if the response future of the stack is polled after completion. Failing cases are shrunk by [`proptest`](mod@proptest) before panicking.
+///
+/// The stack is driven on the current thread without a runtime, so it can't contain middleware
+/// that spawns tasks or uses timers, such as [`Pipeline::buffered`] or
+/// [`Pipeline::timeout_first`].
+///
+/// ```
+/// use tower_pipeline::{test_util, PipelineExt};
+///
+/// test_util::assert_pipeline_contract(|first, second| {
+///     first.pipeline(second).load_shed_second()
+/// });
+/// ```
+///
+/// # Panics
+///
+/// Panics if the stack violates the contract.
+///
+/// [`Pipeline::buffered`]: crate::Pipeline::buffered
+/// [`Pipeline::timeout_first`]: crate::Pipeline::timeout_first
+pub fn assert_pipeline_contract<F, S>(make_stack: F)
+where
+    F: Fn(ContractStage, ContractStage) -> S,
+    S: Service<u32>,
+{
+    let strategy = (
+        vec(any::<u32>(), 1..8),
+        script_strategy(),
+        script_strategy(),
+    );
+
+    let result = TestRunner::default().run(&strategy, |(requests, first, second)| {
+        let first = ContractStage::new("first", |n| n.wrapping_add(1), first);
+        let second = ContractStage::new("second", |n| n.wrapping_mul(2), second);
+        let mut stack = make_stack(first, second);
+        for req in requests {
+            if drive(&mut stack, req).is_err() {
+                // the stack is unusable after `poll_ready` fails
+                break;
+            }
+        }
+        Ok(())
+    });
+
+    if let Err(err) = result {
+        panic!("pipeline violated the service contract: {}", err);
+    }
+}
+
+fn drive<S>(stack: &mut S, req: u32) -> Result<(), S::Error>
+where
+    S: Service<u32>,
+{
+    let wakes = Arc::new(Wakes::default());
+    let waker = futures_util::task::waker(Arc::clone(&wakes));
+    let mut cx = Context::from_waker(&waker);
+
+    let mut polls = 0;
+    let mut tick = || {
+        polls += 1;
+        assert!(polls <= MAX_POLLS, "call didn't complete");
+    };
+
+    loop {
+        tick();
+        match stack.poll_ready(&mut cx) {
+            Poll::Ready(result) => {
+                result?;
+                break;
+            }
+            Poll::Pending => wakes.assert_woken("`poll_ready`"),
+        }
+    }
+
+    let mut future = Box::pin(Fused {
+        future: stack.call(req),
+        done: false,
+    });
+    loop {
+        tick();
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(_) => return Ok(()),
+            Poll::Pending => wakes.assert_woken("the response future"),
+        }
+    }
+}
+
+/// Records whether the task was woken since the last check.
+#[derive(Debug, Default)]
+struct Wakes {
+    woken: AtomicBool,
+}
+
+impl Wakes {
+    // nothing else can wake the task, so pending without a wake means the call would hang
+    fn assert_woken(&self, what: &str) {
+        assert!(
+            self.woken.swap(false, Ordering::SeqCst),
+            "{} returned `Poll::Pending` without waking the task",
+            what
+        );
+    }
+}
+
+impl ArcWake for Wakes {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.woken.store(true, Ordering::SeqCst);
+    }
+}
+
+pin_project! {
+    /// The response future of the stack, checked for being polled after completion.
+    struct Fused<F> {
+        #[pin]
+        future: F,
+        done: bool,
+    }
+}
+
+impl<F> Future for Fused<F>
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        assert!(!*this.done, "stack response future polled after completion");
+        let output = ready!(this.future.poll(cx));
+        *this.done = true;
+        Poll::Ready(output)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Readiness {
+    Ready,
+    Pending,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Outcome {
+    Ok,
+    Pending,
+    Error,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Script {
+    readiness: VecDeque<Readiness>,
+    outcomes: VecDeque<Outcome>,
+}
+
+fn script_strategy() -> impl Strategy<Value = Script> {
+    let readiness = prop_oneof![
+        4 => Just(Readiness::Ready),
+        2 => Just(Readiness::Pending),
+        1 => Just(Readiness::Error),
+    ];
+    let outcome = prop_oneof![
+        4 => Just(Outcome::Ok),
+        2 => Just(Outcome::Pending),
+        1 => Just(Outcome::Error),
+    ];
+    (vec(readiness, 0..16), vec(outcome, 0..16)).prop_map(|(readiness, outcomes)| Script {
+        readiness: readiness.into(),
+        outcomes: outcomes.into(),
+    })
+}
+
+/// A stage used by [`assert_pipeline_contract`] that checks it is called according to the
+/// [`Service`] contract.
+///
+/// Clones share the script of the stage but not readiness, so each clone has to be driven to
+/// readiness before being called.
+pub struct ContractStage {
+    name: &'static str,
+    f: fn(u32) -> u32,
+    script: Arc<Mutex<Script>>,
+    ready: bool,
+}
+
+impl ContractStage {
+    fn new(name: &'static str, f: fn(u32) -> u32, script: Script) -> Self {
+        Self {
+            name,
+            f,
+            script: Arc::new(Mutex::new(script)),
+            ready: false,
+        }
+    }
+}
+
+impl Clone for ContractStage {
+    fn clone(&self) -> Self {
+        // readiness isn't cloned since the clone hasn't been polled
+        Self {
+            name: self.name,
+            f: self.f,
+            script: Arc::clone(&self.script),
+            ready: false,
+        }
+    }
+}
+
+impl fmt::Debug for ContractStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContractStage")
+            .field("name", &self.name)
+            .field("ready", &self.ready)
+            .finish()
+    }
+}
+
+impl Service<u32> for ContractStage {
+    type Response = u32;
+    type Error = BoxError;
+    type Future = ContractFuture;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.ready {
+            return Poll::Ready(Ok(()));
+        }
+        let readiness = self.script.lock().unwrap().readiness.pop_front();
+        match readiness.unwrap_or(Readiness::Ready) {
+            Readiness::Ready => {
+                self.ready = true;
+                Poll::Ready(Ok(()))
+            }
+            Readiness::Pending => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            Readiness::Error => Poll::Ready(Err(BoxError::from(ScriptedError(self.name)))),
+        }
+    }
+
+    fn call(&mut self, req: u32) -> Self::Future {
+        assert!(
+            self.ready,
+            "{} stage was called before `poll_ready` returned `Poll::Ready(Ok(()))`",
+            self.name
+        );
+        self.ready = false;
+
+        let outcome = self.script.lock().unwrap().outcomes.pop_front();
+        let outcome = outcome.unwrap_or(Outcome::Ok);
+        let result = match outcome {
+            Outcome::Ok | Outcome::Pending => Ok((self.f)(req)),
+            Outcome::Error => Err(BoxError::from(ScriptedError(self.name))),
+        };
+        ContractFuture {
+            name: self.name,
+            pending: matches!(outcome, Outcome::Pending),
+            result: Some(result),
+        }
+    }
+}
+
+/// Response future of [`ContractStage`].
+#[derive(Debug)]
+pub struct ContractFuture {
+    name: &'static str,
+    pending: bool,
+    result: Option<Result<u32, BoxError>>,
+}
+
+impl Future for ContractFuture {
+    type Output = Result<u32, BoxError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.pending {
+            self.pending = false;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        match self.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => panic!(
+                "{} stage response future polled after completion",
+                self.name
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ScriptedError(&'static str);
+
+impl fmt::Display for ScriptedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "scripted error from {} stage", self.0)
+    }
+}
+
+impl std::error::Error for ScriptedError {}
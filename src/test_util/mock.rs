@@ -0,0 +1,88 @@
+use crate::{BoxError, Pipeline};
+use std::fmt;
+use tower_test::mock::{self, Mock, SendResponse};
+
+/// A [`Pipeline`] of two mock services.
+///
+/// Created with [`pipeline`].
+pub type MockPipeline<R, T, U> = Pipeline<Mock<R, T>, Mock<T, U>>;
+
+/// Create a [`MockPipeline`] along with a [`MockHandle`] for each of its stages.
+///
+/// The stages are ready by default.
+pub fn pipeline<R, T, U>() -> (MockPipeline<R, T, U>, MockHandle<R, T>, MockHandle<T, U>) {
+    let (first, first_handle) = stage();
+    let (second, second_handle) = stage();
+    (Pipeline::new(first, second), first_handle, second_handle)
+}
+
+/// Create a mock service for a single stage along with its [`MockHandle`].
+pub fn stage<T, U>() -> (Mock<T, U>, MockHandle<T, U>) {
+    let (mock, handle) = mock::pair();
+    (mock, MockHandle { handle })
+}
+
+/// Handle for receiving the requests of a mock stage and controlling its readiness.
+///
+/// Created with [`pipeline`] or [`stage`].
+pub struct MockHandle<T, U> {
+    handle: mock::Handle<T, U>,
+}
+
+impl<T, U> MockHandle<T, U> {
+    /// Allow the stage to accept `num` more calls.
+    ///
+    /// Once they have been used up `poll_ready` returns `Poll::Pending` until more calls are
+    /// allowed. `allow(0)` makes the stage pending.
+    pub fn allow(&mut self, num: u64) {
+        self.handle.allow(num);
+    }
+
+    /// Make the next call to `poll_ready` fail with `err`.
+    pub fn send_error(&mut self, err: impl Into<BoxError>) {
+        self.handle.send_error(err);
+    }
+
+    /// Wait for the next request received by the stage.
+    ///
+    /// Returns `None` if the mock service has been dropped.
+    pub async fn next_request(&mut self) -> Option<(T, SendResponse<U>)> {
+        self.handle.next_request().await
+    }
+
+    /// Wait for the next request and assert that it equals `expected`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the request doesn't equal `expected` or the mock service has been dropped.
+    pub async fn expect_request(&mut self, expected: T) -> SendResponse<U>
+    where
+        T: PartialEq + fmt::Debug,
+    {
+        let (req, send_response) = self
+            .next_request()
+            .await
+            .expect("mock stage was dropped before receiving a request");
+        assert_eq!(req, expected, "mock stage received an unexpected request");
+        send_response
+    }
+
+    /// Wait for the next request, assert that it equals `expected`, and respond with
+    /// `response`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the request doesn't equal `expected` or the mock service has been dropped.
+    pub async fn respond(&mut self, expected: T, response: U)
+    where
+        T: PartialEq + fmt::Debug,
+    {
+        self.expect_request(expected).await.send_response(response);
+    }
+}
+
+impl<T, U> fmt::Debug for MockHandle<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockHandle").finish()
+    }
+}
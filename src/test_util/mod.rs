@@ -0,0 +1,37 @@
+//! Utilities for testing pipelines.
+//!
+//! [`pipeline`] creates a [`Pipeline`] of two mock services along with a [`MockHandle`] for
+//! each stage. The handles receive the requests each stage is called with, so tests can assert
+//! exactly which intermediate value reached the second stage, and they control the readiness and
+//! responses of the stages. The mocks are built on [`tower_test::mock`].
+//!
+//! ```
+//! use tower_pipeline::test_util;
+//! use tower::ServiceExt;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let (pipeline, mut first, mut second) = test_util::pipeline::<&'static str, usize, String>();
+//!
+//! let call = tokio::spawn(pipeline.oneshot("rust"));
+//!
+//! first.respond("rust", 4).await;
+//! second.respond(4, "four".to_string()).await;
+//!
+//! assert_eq!(call.await.unwrap().unwrap(), "four");
+//! # }
+//! ```
+//!
+//! [`assert_pipeline_contract`] checks that a stack built from two stages upholds the
+//! [`Service`] contract for randomized sequences of readiness and responses.
+//!
+//! [`Pipeline`]: crate::Pipeline
+//! [`Service`]: tower_service::Service
+
+pub(crate) mod contract;
+pub(crate) mod mock;
+
+pub use self::{
+    contract::{assert_pipeline_contract, ContractFuture, ContractStage},
+    mock::{pipeline, stage, MockHandle, MockPipeline},
+};
@@ -0,0 +1,421 @@
+use crate::{oneshot::Oneshot, StageNames};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tower_service::Service;
+
+/// How long each stage of a call to a pipeline took.
+///
+/// Produced by [`Timed`] and [`Instrumented`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Timings {
+    first: Duration,
+    second_ready: Option<Duration>,
+    second: Option<Duration>,
+    names: StageNames,
+}
+
+impl Timings {
+    /// How long the response future of the first service took.
+    pub fn first(&self) -> Duration {
+        self.first
+    }
+
+    /// How long it took for the second service to become ready.
+    ///
+    /// `None` if the first service failed.
+    pub fn second_ready(&self) -> Option<Duration> {
+        self.second_ready
+    }
+
+    /// How long the response future of the second service took.
+    ///
+    /// `None` if the first service failed or the second service failed to become ready.
+    pub fn second(&self) -> Option<Duration> {
+        self.second
+    }
+
+    /// The names of the stages, as set with [`Pipeline::named`].
+    ///
+    /// Useful for labelling metrics.
+    ///
+    /// [`Pipeline::named`]: crate::Pipeline::named
+    pub fn names(&self) -> StageNames {
+        self.names
+    }
+
+    /// The sum of all the durations.
+    pub fn total(&self) -> Duration {
+        self.first + self.second_ready.unwrap_or_default() + self.second.unwrap_or_default()
+    }
+}
+
+/// Two services combined where the response of the first is the request of the second, and the
+/// latency of each stage is measured.
+///
+/// When a call completes, successfully or not, the callback is called with the [`Timings`] of
+/// that call.
+///
+/// Created with [`Pipeline::timed`].
+///
+/// ```
+/// use tower_pipeline::{PipelineExt, Timings};
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use std::sync::{Arc, Mutex};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let recorded = Arc::new(Mutex::new(Vec::<Timings>::new()));
+///
+/// let combined = length_svc.pipeline(double_svc).timed({
+///     let recorded = Arc::clone(&recorded);
+///     move |timings| recorded.lock().unwrap().push(timings)
+/// });
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// assert!(recorded.lock().unwrap()[0].second().is_some());
+/// # }
+/// ```
+///
+/// [`Pipeline::timed`]: crate::Pipeline::timed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timed<A, B, F> {
+    first: A,
+    second: B,
+    on_timings: F,
+    names: StageNames,
+}
+
+impl<A, B, F> Timed<A, B, F> {
+    /// Create a new [`Timed`] from two [`Service`]s and a callback that receives the
+    /// [`Timings`] of each call.
+    pub fn new(first: A, second: B, on_timings: F) -> Self {
+        Self {
+            first,
+            second,
+            on_timings,
+            names: StageNames::default(),
+        }
+    }
+
+    pub(crate) fn with_names(mut self, names: StageNames) -> Self {
+        self.names = names;
+        self
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, A, B, F> Service<R> for Timed<A, B, F>
+where
+    A: Service<R>,
+    B: Service<A::Response> + Clone,
+    A::Error: Into<B::Error>,
+    F: FnOnce(Timings) + Clone,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = TimedFuture<R, A, B, F>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        let start = Instant::now();
+        TimedFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            second: Some(self.second.clone()),
+            on_timings: Some(self.on_timings.clone()),
+            timings: Timings {
+                names: self.names,
+                ..Timings::default()
+            },
+            start,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Timed`].
+    pub struct TimedFuture<R, A, B, F>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        #[pin]
+        state: State<R, A, B>,
+        second: Option<B>,
+        on_timings: Option<F>,
+        timings: Timings,
+        start: Instant,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturePending { #[pin] future: Oneshot<B, A::Response> },
+    }
+}
+
+impl<R, A, B, F> TimedFuture<R, A, B, F>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+    A::Error: Into<B::Error>,
+{
+    fn poll_timed(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<(Result<B::Response, B::Error>, Timings)> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let result = ready!(future.poll(cx));
+                    this.timings.first = this.start.elapsed();
+                    *this.start = Instant::now();
+
+                    let first_res = match result {
+                        Ok(res) => res,
+                        Err(err) => return Poll::Ready((Err(err.into()), *this.timings)),
+                    };
+
+                    let second = this.second.take().unwrap();
+                    State::SecondFuturePending {
+                        future: Oneshot::new(second, first_res),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => {
+                    let timings = &mut *this.timings;
+                    let start = &mut *this.start;
+                    let result = ready!(future.poll_notify_ready(cx, || {
+                        timings.second_ready = Some(start.elapsed());
+                        *start = Instant::now();
+                    }));
+                    if timings.second_ready.is_none() {
+                        // the second service failed to become ready
+                        timings.second_ready = Some(start.elapsed());
+                    } else {
+                        timings.second = Some(start.elapsed());
+                    }
+                    return Poll::Ready((result, *timings));
+                }
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
+
+impl<R, A, B, F> Future for TimedFuture<R, A, B, F>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+    A::Error: Into<B::Error>,
+    F: FnOnce(Timings),
+{
+    type Output = Result<B::Response, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let (result, timings) = ready!(self.as_mut().poll_timed(cx));
+        (self.project().on_timings.take().unwrap())(timings);
+        Poll::Ready(result)
+    }
+}
+
+/// Two services combined where the response of the first is the request of the second, and
+/// the [`Timings`] of each call are returned along with its response.
+///
+/// Unlike [`Timed`] the timings are only available for successful calls.
+///
+/// Created with [`Pipeline::instrumented`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let double_svc = service_fn(|input: usize| async move {
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let combined = length_svc.pipeline(double_svc).instrumented();
+///
+/// let (result, timings) = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, 8);
+/// assert!(timings.second().is_some());
+/// # }
+/// ```
+///
+/// [`Pipeline::instrumented`]: crate::Pipeline::instrumented
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Instrumented<A, B> {
+    first: A,
+    second: B,
+    names: StageNames,
+}
+
+impl<A, B> Instrumented<A, B> {
+    /// Create a new [`Instrumented`] from two [`Service`]s.
+    pub fn new(first: A, second: B) -> Self {
+        Self {
+            first,
+            second,
+            names: StageNames::default(),
+        }
+    }
+
+    pub(crate) fn with_names(mut self, names: StageNames) -> Self {
+        self.names = names;
+        self
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, A, B> Service<R> for Instrumented<A, B>
+where
+    A: Service<R>,
+    B: Service<A::Response> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Response = (B::Response, Timings);
+    type Error = B::Error;
+    type Future = InstrumentedFuture<R, A, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        let start = Instant::now();
+        InstrumentedFuture {
+            inner: TimedFuture {
+                state: State::FirstFuturePending {
+                    future: self.first.call(req),
+                },
+                second: Some(self.second.clone()),
+                on_timings: None,
+                timings: Timings {
+                    names: self.names,
+                    ..Timings::default()
+                },
+                start,
+            },
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Instrumented`].
+    pub struct InstrumentedFuture<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        #[pin]
+        inner: TimedFuture<R, A, B, ()>,
+    }
+}
+
+impl<R, A, B> Future for InstrumentedFuture<R, A, B>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+    A::Error: Into<B::Error>,
+{
+    type Output = Result<(B::Response, Timings), B::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let (result, timings) = ready!(self.project().inner.poll_timed(cx));
+        Poll::Ready(result.map(|res| (res, timings)))
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{BoxError, Stage};
+use alloc::boxed::Box;
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::{
+    error::Error,
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::Sleep;
+use tower_service::Service;
+
+/// A service that fails requests that take longer than a timeout.
+///
+/// Requests that time out fail with an [`Elapsed`] error that says which stage of the pipeline
+/// timed out.
+///
+/// Usually created with [`Pipeline::timeout_first`] or [`Pipeline::timeout_second`].
+///
+/// ```
+/// use tower_pipeline::{Elapsed, PipelineExt, Stage};
+/// use tower::{service_fn, BoxError, ServiceExt};
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let slow_svc = service_fn(|input: usize| async move {
+///     tokio::time::sleep(Duration::from_secs(10)).await;
+///     Ok::<_, BoxError>(input * 2)
+/// });
+///
+/// let combined = length_svc
+///     .pipeline(slow_svc)
+///     .timeout_second(Duration::from_millis(10));
+///
+/// let err = combined.oneshot("rust").await.unwrap_err();
+///
+/// assert_eq!(err.downcast_ref::<Elapsed>().unwrap().stage(), Stage::Second);
+/// # }
+/// ```
+///
+/// [`Pipeline::timeout_first`]: crate::Pipeline::timeout_first
+/// [`Pipeline::timeout_second`]: crate::Pipeline::timeout_second
+#[derive(Debug, Clone, Copy)]
+pub struct Timeout<S> {
+    inner: S,
+    timeout: Duration,
+    stage: Stage,
+    name: &'static str,
+}
+
+impl<S> Timeout<S> {
+    /// Create a new [`Timeout`] for the given stage.
+    pub fn new(inner: S, timeout: Duration, stage: Stage) -> Self {
+        Self {
+            inner,
+            timeout,
+            stage,
+            name: stage.default_name(),
+        }
+    }
+
+    /// Set the name of the stage used in errors.
+    ///
+    /// Defaults to the name of the [`Stage`], `"first"` or `"second"`. [`Pipeline::named`] sets
+    /// this for middleware added to a pipeline afterwards.
+    ///
+    /// [`Pipeline::named`]: crate::Pipeline::named
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner service.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consume `self`, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, T> Service<T> for Timeout<S>
+where
+    S: Service<T>,
+    S::Error: Into<BoxError>,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = TimeoutFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: T) -> Self::Future {
+        TimeoutFuture {
+            inner: self.inner.call(req),
+            sleep: tokio::time::sleep(self.timeout),
+            elapsed: Elapsed::new(self.stage, self.name),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Timeout`].
+    pub struct TimeoutFuture<F> {
+        #[pin]
+        inner: F,
+        #[pin]
+        sleep: Sleep,
+        elapsed: Elapsed,
+    }
+}
+
+impl<F, T, E> Future for TimeoutFuture<F>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Into<BoxError>,
+{
+    type Output = Result<T, BoxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        if let Poll::Ready(result) = this.inner.poll(cx) {
+            return Poll::Ready(result.map_err(Into::into));
+        }
+
+        match this.sleep.poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(Box::new(*this.elapsed))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Error produced when a stage of a pipeline times out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed {
+    stage: Stage,
+    name: &'static str,
+}
+
+impl Elapsed {
+    pub(crate) fn new(stage: Stage, name: &'static str) -> Self {
+        Self { stage, name }
+    }
+
+    /// The stage that timed out.
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    /// The name of the stage, as set with [`Pipeline::named`].
+    ///
+    /// [`Pipeline::named`]: crate::Pipeline::named
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} service timed out", self.name)
+    }
+}
+
+impl Error for Elapsed {}
@@ -0,0 +1,56 @@
+//! Spans for the stages of a pipeline.
+//!
+//! Without the `tracing` feature these are no-ops.
+
+use crate::{Stage, StageNames};
+
+#[derive(Debug, Clone)]
+pub(crate) struct StageSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl StageSpan {
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn new(stage: Stage, names: StageNames) -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            span: match stage {
+                Stage::First => tracing::debug_span!(
+                    "pipeline.first",
+                    name = names.first(),
+                    outcome = tracing::field::Empty
+                ),
+                Stage::Second => tracing::debug_span!(
+                    "pipeline.second",
+                    name = names.second(),
+                    outcome = tracing::field::Empty
+                ),
+            },
+        }
+    }
+
+    pub(crate) fn in_scope<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        #[cfg(feature = "tracing")]
+        {
+            self.span.in_scope(f)
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        {
+            f()
+        }
+    }
+
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn record_outcome<T, E>(&self, result: &Result<T, E>) {
+        #[cfg(feature = "tracing")]
+        {
+            let outcome = if result.is_ok() { "success" } else { "error" };
+            self.span.record("outcome", outcome);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::ordering::{InFlight, Ordering};
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::stream::Stream;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+pin_project! {
+    /// Stream of responses produced by calling a service with every item of a stream, with
+    /// bounded concurrency.
+    ///
+    /// The service is driven to readiness before each call and at most `limit` calls are in
+    /// flight at a time. Responses are produced in the order given by an [`Ordering`], which by
+    /// default is the same order as the requests.
+    ///
+    /// If the service fails to become ready the error is yielded and the stream ends once the
+    /// calls in flight have completed.
+    ///
+    /// Created by the function returned from [`Pipeline::into_stream_transform`] or
+    /// [`Pipeline::into_stream_transform_with_ordering`], and as the response of
+    /// [`ForEachStream`].
+    ///
+    /// ```
+    /// use tower_pipeline::PipelineExt;
+    /// use tower::{service_fn, BoxError};
+    /// use futures_util::stream::{self, StreamExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let length_svc = service_fn(|input: &'static str| async move {
+    ///     Ok::<_, BoxError>(input.len())
+    /// });
+    ///
+    /// let double_svc = service_fn(|input: usize| async move {
+    ///     Ok::<_, BoxError>(input * 2)
+    /// });
+    ///
+    /// let transform = length_svc.pipeline(double_svc).into_stream_transform(8);
+    ///
+    /// let results = transform(stream::iter(vec!["a", "bb", "ccc"]))
+    ///     .map(Result::unwrap)
+    ///     .collect::<Vec<_>>()
+    ///     .await;
+    ///
+    /// assert_eq!(results, vec![2, 4, 6]);
+    /// # }
+    /// ```
+    ///
+    /// [`Pipeline::into_stream_transform`]: crate::Pipeline::into_stream_transform
+    /// [`Pipeline::into_stream_transform_with_ordering`]: crate::Pipeline::into_stream_transform_with_ordering
+    /// [`ForEachStream`]: crate::ForEachStream
+    pub struct TransformStream<St, S>
+    where
+        St: Stream,
+        S: Service<St::Item>,
+    {
+        #[pin]
+        requests: St,
+        service: S,
+        in_flight: InFlight<S::Future>,
+        limit: usize,
+        ready_error: Option<S::Error>,
+        exhausted: bool,
+    }
+}
+
+impl<St, S> TransformStream<St, S>
+where
+    St: Stream,
+    S: Service<St::Item>,
+{
+    pub(crate) fn new(service: S, requests: St, limit: usize, ordering: Ordering) -> Self {
+        Self {
+            requests,
+            service,
+            in_flight: InFlight::new(ordering),
+            limit,
+            ready_error: None,
+            exhausted: false,
+        }
+    }
+}
+
+impl<St, S> Stream for TransformStream<St, S>
+where
+    St: Stream,
+    S: Service<St::Item>,
+{
+    type Item = Result<S::Response, S::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        while !*this.exhausted && (*this.limit == 0 || this.in_flight.len() < *this.limit) {
+            match this.service.poll_ready(cx) {
+                Poll::Ready(Ok(())) => match this.requests.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(req)) => this.in_flight.push(this.service.call(req)),
+                    Poll::Ready(None) => *this.exhausted = true,
+                    Poll::Pending => break,
+                },
+                Poll::Ready(Err(err)) => {
+                    *this.exhausted = true;
+                    *this.ready_error = Some(err);
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        match this.in_flight.poll_next(cx) {
+            Poll::Ready(Some(result)) => Poll::Ready(Some(result)),
+            Poll::Ready(None) if *this.exhausted => Poll::Ready(this.ready_error.take().map(Err)),
+            Poll::Ready(None) | Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<St, S> fmt::Debug for TransformStream<St, S>
+where
+    St: Stream + fmt::Debug,
+    S: Service<St::Item> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransformStream")
+            .field("requests", &self.requests)
+            .field("service", &self.service)
+            .field("in_flight", &self.in_flight.len())
+            .field("limit", &self.limit)
+            .field("exhausted", &self.exhausted)
+            .finish()
+    }
+}
@@ -0,0 +1,218 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::{
+    future::{maybe_done, MaybeDone},
+    ready,
+};
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// A service whose response is a pair, where each half is sent to its own service concurrently
+/// and the pair of their responses becomes the response.
+///
+/// This is the counterpart of [`Join`], which combines two responses into one request.
+///
+/// Created with [`PipelineExt::pipeline_unzip`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let split_svc = service_fn(|input: &'static str| async move {
+///     let (user, repo) = input.split_once('/').ok_or("invalid input")?;
+///     Ok::<_, BoxError>((user, repo))
+/// });
+///
+/// let user_svc = service_fn(|user: &'static str| async move {
+///     Ok::<_, BoxError>(user.to_uppercase())
+/// });
+///
+/// let repo_svc = service_fn(|repo: &'static str| async move {
+///     Ok::<_, BoxError>(repo.len())
+/// });
+///
+/// let combined = split_svc.pipeline_unzip(user_svc, repo_svc);
+///
+/// let result = combined.oneshot("davidpdrsn/tower-pipeline").await.unwrap();
+///
+/// assert_eq!(result, ("DAVIDPDRSN".to_string(), 14));
+/// # }
+/// ```
+///
+/// [`Join`]: crate::Join
+/// [`PipelineExt::pipeline_unzip`]: crate::PipelineExt::pipeline_unzip
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Unzip<A, L, R> {
+    first: A,
+    left: L,
+    right: R,
+}
+
+impl<A, L, R> Unzip<A, L, R> {
+    /// Create a new [`Unzip`] from a [`Service`] and the two [`Service`]s the halves of its
+    /// response are sent to.
+    pub fn new(first: A, left: L, right: R) -> Self {
+        Self { first, left, right }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the service the left half is sent to.
+    pub fn left_as_ref(&self) -> &L {
+        &self.left
+    }
+
+    /// Get a mutable reference to the service the left half is sent to.
+    pub fn left_as_mut(&mut self) -> &mut L {
+        &mut self.left
+    }
+
+    /// Get a reference to the service the right half is sent to.
+    pub fn right_as_ref(&self) -> &R {
+        &self.right
+    }
+
+    /// Get a mutable reference to the service the right half is sent to.
+    pub fn right_as_mut(&mut self) -> &mut R {
+        &mut self.right
+    }
+}
+
+impl<Req, A, L, R, X, Y> Service<Req> for Unzip<A, L, R>
+where
+    A: Service<Req, Response = (X, Y)>,
+    L: Service<X> + Clone,
+    R: Service<Y> + Clone,
+    A::Error: Into<L::Error>,
+    R::Error: Into<L::Error>,
+{
+    type Response = (L::Response, R::Response);
+    type Error = L::Error;
+    type Future = UnzipFuture<Req, A, L, R, X, Y>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        UnzipFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            left: Some(self.left.clone()),
+            right: Some(self.right.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Unzip`].
+    pub struct UnzipFuture<Req, A, L, R, X, Y>
+    where
+        A: Service<Req>,
+        L: Service<X>,
+        R: Service<Y>,
+    {
+        #[pin]
+        state: State<Req, A, L, R, X, Y>,
+        left: Option<L>,
+        right: Option<R>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<Req, A, L, R, X, Y>
+    where
+        A: Service<Req>,
+        L: Service<X>,
+        R: Service<Y>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        Joining {
+            #[pin]
+            left: MaybeDone<Oneshot<L, X>>,
+            #[pin]
+            right: MaybeDone<Oneshot<R, Y>>,
+        },
+    }
+}
+
+impl<Req, A, L, R, X, Y> Future for UnzipFuture<Req, A, L, R, X, Y>
+where
+    A: Service<Req, Response = (X, Y)>,
+    L: Service<X>,
+    R: Service<Y>,
+    A::Error: Into<L::Error>,
+    R::Error: Into<L::Error>,
+{
+    type Output = Result<(L::Response, R::Response), L::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let (x, y) = ready!(future.poll(cx).map_err(Into::into)?);
+                    State::Joining {
+                        left: maybe_done(Oneshot::new(this.left.take().unwrap(), x)),
+                        right: maybe_done(Oneshot::new(this.right.take().unwrap(), y)),
+                    }
+                }
+
+                StateProj::Joining {
+                    mut left,
+                    mut right,
+                } => {
+                    let left_done = left.as_mut().poll(cx).is_ready();
+                    let right_done = right.as_mut().poll(cx).is_ready();
+
+                    if let Some(Err(_)) = left.as_mut().output_mut() {
+                        if let Some(Err(err)) = left.as_mut().take_output() {
+                            return Poll::Ready(Err(err));
+                        }
+                    }
+
+                    if let Some(Err(_)) = right.as_mut().output_mut() {
+                        if let Some(Err(err)) = right.as_mut().take_output() {
+                            return Poll::Ready(Err(err.into()));
+                        }
+                    }
+
+                    if !(left_done && right_done) {
+                        return Poll::Pending;
+                    }
+
+                    return match (left.take_output(), right.take_output()) {
+                        (Some(Ok(left_res)), Some(Ok(right_res))) => {
+                            Poll::Ready(Ok((left_res, right_res)))
+                        }
+                        _ => unreachable!(),
+                    };
+                }
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{oneshot::Oneshot, PipelineError, ValidationError};
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Two services combined where the response of the first is checked by a validator before it is
+/// passed to the second.
+///
+/// Rejected responses fail with [`PipelineError::Invalid`], without calling the second service.
+/// Like [`SplitErrors`] the error types of the two services are unrelated and errors are
+/// reported as a [`PipelineError`] that says which service failed.
+///
+/// Created with [`Pipeline::validate`].
+///
+/// ```
+/// use tower_pipeline::{Pipeline, ValidationError};
+/// use tower::{service_fn, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let parse_svc = service_fn(|input: &'static str| async move {
+///     input.parse::<u32>()
+/// });
+///
+/// let double_svc = service_fn(|input: u32| async move {
+///     input.checked_mul(2).ok_or("overflow")
+/// });
+///
+/// let combined = Pipeline::new(parse_svc, double_svc).validate(|n: &u32| {
+///     if *n == 0 {
+///         Err(ValidationError::new("must not be zero"))
+///     } else {
+///         Ok(())
+///     }
+/// });
+///
+/// let result = combined.clone().oneshot("21").await;
+/// assert_eq!(result, Ok(42));
+///
+/// let err = combined.oneshot("0").await.unwrap_err();
+/// assert_eq!(err.into_invalid().unwrap().message(), "must not be zero");
+/// # }
+/// ```
+///
+/// [`SplitErrors`]: crate::SplitErrors
+/// [`Pipeline::validate`]: crate::Pipeline::validate
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Validate<A, B, F> {
+    first: A,
+    second: B,
+    validator: F,
+}
+
+impl<A, B, F> Validate<A, B, F> {
+    /// Create a new [`Validate`] from two [`Service`]s and a validator.
+    pub fn new(first: A, second: B, validator: F) -> Self {
+        Self {
+            first,
+            second,
+            validator,
+        }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, A, B, F> Service<R> for Validate<A, B, F>
+where
+    A: Service<R>,
+    B: Service<A::Response> + Clone,
+    F: FnOnce(&A::Response) -> Result<(), ValidationError> + Clone,
+{
+    type Response = B::Response;
+    type Error = PipelineError<A::Error, B::Error>;
+    type Future = ValidateFuture<R, A, B, F>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(PipelineError::First)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        ValidateFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            second: Some(self.second.clone()),
+            validator: Some(self.validator.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`Validate`].
+    pub struct ValidateFuture<R, A, B, F>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        #[pin]
+        state: State<R, A, B>,
+        second: Option<B>,
+        validator: Option<F>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<A::Response>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturePending { #[pin] future: Oneshot<B, A::Response> },
+    }
+}
+
+impl<R, A, B, F> Future for ValidateFuture<R, A, B, F>
+where
+    A: Service<R>,
+    B: Service<A::Response>,
+    F: FnOnce(&A::Response) -> Result<(), ValidationError>,
+{
+    type Output = Result<B::Response, PipelineError<A::Error, B::Error>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(PipelineError::First)?);
+                    (this.validator.take().unwrap())(&first_res).map_err(PipelineError::Invalid)?;
+                    let second = this.second.take().unwrap();
+                    State::SecondFuturePending {
+                        future: Oneshot::new(second, first_res),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => {
+                    return future.poll(cx).map_err(PipelineError::Second)
+                }
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Two services combined where the second service receives both the original request and the
+/// response of the first.
+///
+/// Created with [`PipelineExt::pipeline_with_context`] or [`Pipeline::with_context`].
+///
+/// ```
+/// use tower_pipeline::PipelineExt;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let length_svc = service_fn(|input: &'static str| async move {
+///     Ok::<_, BoxError>(input.len())
+/// });
+///
+/// let describe_svc = service_fn(|(input, len): (&'static str, usize)| async move {
+///     Ok::<_, BoxError>(format!("{} has {} characters", input, len))
+/// });
+///
+/// let combined = length_svc.pipeline_with_context(describe_svc);
+///
+/// let result = combined.oneshot("rust").await.unwrap();
+///
+/// assert_eq!(result, "rust has 4 characters");
+/// # }
+/// ```
+///
+/// [`PipelineExt::pipeline_with_context`]: crate::PipelineExt::pipeline_with_context
+/// [`Pipeline::with_context`]: crate::Pipeline::with_context
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WithContext<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> WithContext<A, B> {
+    /// Create a new [`WithContext`] from two [`Service`]s.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, A, B> Service<R> for WithContext<A, B>
+where
+    R: Clone,
+    A: Service<R>,
+    B: Service<(R, A::Response)> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = WithContextFuture<R, A, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        WithContextFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req.clone()),
+            },
+            req: Some(req),
+            second: Some(self.second.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`WithContext`].
+    pub struct WithContextFuture<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<(R, A::Response)>,
+    {
+        #[pin]
+        state: State<R, A, B>,
+        req: Option<R>,
+        second: Option<B>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<(R, A::Response)>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturePending { #[pin] future: Oneshot<B, (R, A::Response)> },
+    }
+}
+
+impl<R, A, B> Future for WithContextFuture<R, A, B>
+where
+    A: Service<R>,
+    B: Service<(R, A::Response)>,
+    A::Error: Into<B::Error>,
+{
+    type Output = Result<B::Response, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx).map_err(Into::into)?);
+                    let req = this.req.take().unwrap();
+                    let second = this.second.take().unwrap();
+                    State::SecondFuturePending {
+                        future: Oneshot::new(second, (req, first_res)),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}
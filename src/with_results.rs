@@ -0,0 +1,166 @@
+use crate::oneshot::Oneshot;
+use core::future::Future;
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_util::ready;
+use pin_project_lite::pin_project;
+use tower_service::Service;
+
+/// Two services combined where the second service receives the result of the first, rather than
+/// only its successful responses.
+///
+/// Errors from the first service don't short-circuit the pipeline, so the second service can
+/// decide how to handle them. Errors from `poll_ready` of the first service still fail the
+/// pipeline.
+///
+/// Created with [`Pipeline::with_results`].
+///
+/// ```
+/// use tower_pipeline::Pipeline;
+/// use tower::{service_fn, BoxError, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let parse_svc = service_fn(|input: &'static str| async move {
+///     input.parse::<usize>().map_err(BoxError::from)
+/// });
+///
+/// let describe_svc = service_fn(|result: Result<usize, BoxError>| async move {
+///     match result {
+///         Ok(n) => Ok::<_, BoxError>(format!("got {}", n)),
+///         Err(err) => Ok(format!("failed: {}", err)),
+///     }
+/// });
+///
+/// let combined = Pipeline::new(parse_svc, describe_svc).with_results();
+///
+/// assert_eq!(combined.clone().oneshot("2").await.unwrap(), "got 2");
+/// assert_eq!(
+///     combined.oneshot("two").await.unwrap(),
+///     "failed: invalid digit found in string",
+/// );
+/// # }
+/// ```
+///
+/// [`Pipeline::with_results`]: crate::Pipeline::with_results
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WithResults<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> WithResults<A, B> {
+    /// Create a new [`WithResults`] from two [`Service`]s.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Get a reference to the first service.
+    pub fn first_as_ref(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a mutable reference to the first service.
+    pub fn first_as_mut(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Consume `self`, returning the first service
+    pub fn into_first(self) -> A {
+        self.first
+    }
+
+    /// Get a reference to the second service.
+    pub fn second_as_ref(&self) -> &B {
+        &self.second
+    }
+
+    /// Get a mutable reference to the second service.
+    pub fn second_as_mut(&mut self) -> &mut B {
+        &mut self.second
+    }
+
+    /// Consume `self`, returning the second service
+    pub fn into_second(self) -> B {
+        self.second
+    }
+}
+
+impl<R, A, B> Service<R> for WithResults<A, B>
+where
+    A: Service<R>,
+    B: Service<Result<A::Response, A::Error>> + Clone,
+    A::Error: Into<B::Error>,
+{
+    type Response = B::Response;
+    type Error = B::Error;
+    type Future = WithResultsFuture<R, A, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.first.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        WithResultsFuture {
+            state: State::FirstFuturePending {
+                future: self.first.call(req),
+            },
+            second: Some(self.second.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`WithResults`].
+    pub struct WithResultsFuture<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<Result<A::Response, A::Error>>,
+    {
+        #[pin]
+        state: State<R, A, B>,
+        second: Option<B>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<R, A, B>
+    where
+        A: Service<R>,
+        B: Service<Result<A::Response, A::Error>>,
+    {
+        FirstFuturePending { #[pin] future: A::Future },
+        SecondFuturePending { #[pin] future: Oneshot<B, Result<A::Response, A::Error>> },
+    }
+}
+
+impl<R, A, B> Future for WithResultsFuture<R, A, B>
+where
+    A: Service<R>,
+    B: Service<Result<A::Response, A::Error>>,
+{
+    type Output = Result<B::Response, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let mut this = self.as_mut().project();
+
+            let new_state = match this.state.as_mut().project() {
+                StateProj::FirstFuturePending { future } => {
+                    let first_res = ready!(future.poll(cx));
+                    let second = this.second.take().unwrap();
+                    State::SecondFuturePending {
+                        future: Oneshot::new(second, first_res),
+                    }
+                }
+
+                StateProj::SecondFuturePending { future } => return future.poll(cx),
+            };
+
+            this.state.set(new_state);
+        }
+    }
+}